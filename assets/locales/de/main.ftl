@@ -0,0 +1,148 @@
+## Hauptmenü
+
+menu-start = START
+menu-start-online = ONLINE SPIELEN
+menu-spectate = ZUSCHAUEN
+menu-practice = ÜBEN
+menu-versus = DUELL
+menu-daily = TÄGLICH
+menu-puzzles = RÄTSEL
+menu-classic = KLASSISCH
+menu-editor = EDITOR
+menu-achievements = ERFOLGE
+menu-settings = OPTIONEN
+menu-quit = BEENDEN
+menu-help =
+    Klicke graue Felder an, um sie weiß zu machen.
+    Je heller das Feld, desto mehr Punkte bringt es.
+    Werden alle Felder schwarz, ist das Spiel vorbei.
+
+    Esc oder Leertaste pausiert, T wechselt die Farben.
+
+## Andere Menüs
+
+menu-resume = WEITER
+menu-undo = RÜCKGÄNGIG
+menu-main-menu = HAUPTMENÜ
+menu-restart = NEUSTART
+menu-play = SPIELEN
+menu-play-unscored = OHNE WERTUNG
+title-paused = PAUSE
+title-select-level = LEVEL WÄHLEN
+title-achievements = ERFOLGE
+title-settings = OPTIONEN
+
+## Optionen
+
+settings-theme = FARBEN: { $theme }
+settings-ui-scale = SKALIERUNG: { $scale } %
+settings-high-contrast = HOHER KONTRAST: { $state }
+settings-reduced-motion = WENIGER BEWEGUNG: { $state }
+settings-language = SPRACHE: { $language }
+settings-dynamic-lighting = DYNAMISCHES LICHT: { $state }
+settings-on = AN
+settings-off = AUS
+theme-classic = Klassisch
+theme-amber = Bernstein
+theme-neon = Neon
+theme-high-contrast = Hoher Kontrast
+
+## Im Spiel
+
+hud-score = Punkte: { $score }
+hud-stats = +{ $rate }/Tick | Leuchtend: { $lit } | Maximum: { $peak } % | Zeit: { $time }
+hud-warning = WENIG LICHT
+hud-clicks = Klicks: { $clicks }
+hud-moves = Züge: { $moves }
+hud-versus = Duell
+hud-versus-scores = S1: { $one } | S2: { $two }
+hud-online = Online
+hud-spectating = Zuschauen
+hud-daily = Täglich: { $variant }
+hud-daily-unscored = Täglich: { $variant } (ohne Wertung)
+player-one = Spieler 1
+player-two = Spieler 2
+
+## Spielende
+
+title-game-over = SPIEL VORBEI
+title-level-complete = LEVEL GESCHAFFT
+title-solved = GELÖST
+title-winner = { $player } GEWINNT
+title-draw = UNENTSCHIEDEN
+game-over-moves = Züge: { $moves } (Bestwert: { $best })
+game-over-player-score = { $player }: { $score }
+stats-game =
+    Klicks: { $clicks } ({ $wasted } verschwendet)
+    Überlebt: { $survived }
+    Meiste leuchtende Felder: { $lit }
+    Meiste Punkte pro Tick: { $peak }
+    Spielfeld: { $width }x{ $height }
+stats-lifetime = Spiele: { $games }, Bestwert: { $best }, am längsten überlebt: { $survived }
+
+## Tägliche Herausforderung
+
+daily-heading =
+    TÄGLICHE HERAUSFORDERUNG
+    { $date } ({ $variant })
+    { $status }
+daily-status-score = Heutige Punkte: { $score }
+daily-status-abandoned = Der heutige Versuch wurde abgebrochen
+daily-status-new = Ein gewerteter Versuch pro Tag
+daily-variant-standard = Normal
+daily-variant-walled = Mauern
+daily-variant-scattered = Verstreut
+daily-variant-dim = Düster
+month-january = Januar
+month-february = Februar
+month-march = März
+month-april = April
+month-may = Mai
+month-june = Juni
+month-july = Juli
+month-august = August
+month-september = September
+month-october = Oktober
+month-november = November
+month-december = Dezember
+
+## Erfolge
+
+achievement-unlocked = FREIGESCHALTET: { $title }
+achievement-survivor = Überlebenskünstler
+achievement-survivor-description = Überlebe { $time }
+achievement-millionaire = Millionär
+achievement-millionaire-description = Erreiche { $score } Punkte
+achievement-floodlight = Flutlicht
+achievement-floodlight-description = Lass { $tiles } Felder gleichzeitig leuchten
+achievement-closed-loop = Geschlossener Kreis
+achievement-closed-loop-description = Umgib ein dunkles Feld mit leuchtenden Feldern
+
+## Zuschauen
+
+spectator-live = LIVE
+spectator-paused = PAUSE
+spectator-playing = WIEDERGABE { $speed }x
+spectator-status = { $status }    Tick { $tick } / { $last }
+
+## Editor
+
+editor-help =
+    LMT malen, RMT löschen, 0-9 Helligkeit, W Mauer, L Lampe, N normal, [ ] Pinselgröße
+    G Zielecke, Hoch/Runter Klicks, Z rückgängig, Y wiederholen, Entf leeren
+    Enter testen, F5 speichern, F9 laden, Esc Hauptmenü
+editor-text =
+    Pinsel: { $brush } ({ $size }x{ $size })
+    Klicks: { $clicks }
+    Ziel: { $goal }
+    { $status }
+editor-brush-lightness = Helligkeit { $lightness }
+editor-brush-wall = Mauer
+editor-brush-lamp = Lampe
+editor-goal-region = { $min } bis { $max } erleuchten
+editor-goal-score = { $score } Punkte
+editor-cant-test = Test nicht möglich: { $error }
+editor-saved = { $path } gespeichert
+editor-cant-save = Speichern nicht möglich: { $error }
+editor-loaded = { $path } geladen
+editor-cant-load = Laden nicht möglich: { $error }
@@ -0,0 +1,148 @@
+## Main menu
+
+menu-start = START
+menu-start-online = START ONLINE
+menu-spectate = SPECTATE
+menu-practice = PRACTICE
+menu-versus = VERSUS
+menu-daily = DAILY
+menu-puzzles = PUZZLES
+menu-classic = CLASSIC
+menu-editor = EDITOR
+menu-achievements = ACHIEVEMENTS
+menu-settings = SETTINGS
+menu-quit = QUIT
+menu-help =
+    Click gray squares to turn them white.
+    The lighter the square, the more points it will give.
+    If all squares turn black, the game ends.
+
+    Press Esc or Space to pause, and T to change colours.
+
+## Other menus
+
+menu-resume = RESUME
+menu-undo = UNDO
+menu-main-menu = MAIN MENU
+menu-restart = RESTART
+menu-play = PLAY
+menu-play-unscored = PLAY UNSCORED
+title-paused = PAUSED
+title-select-level = SELECT LEVEL
+title-achievements = ACHIEVEMENTS
+title-settings = SETTINGS
+
+## Settings
+
+settings-theme = THEME: { $theme }
+settings-ui-scale = UI SCALE: { $scale }%
+settings-high-contrast = HIGH CONTRAST: { $state }
+settings-reduced-motion = REDUCED MOTION: { $state }
+settings-language = LANGUAGE: { $language }
+settings-dynamic-lighting = DYNAMIC LIGHTING: { $state }
+settings-on = ON
+settings-off = OFF
+theme-classic = Classic
+theme-amber = Amber
+theme-neon = Neon
+theme-high-contrast = High Contrast
+
+## In game
+
+hud-score = Score: { $score }
+hud-stats = +{ $rate }/tick | Lit: { $lit } | Peak: { $peak }% | Time: { $time }
+hud-warning = LOW LIGHT
+hud-clicks = Clicks: { $clicks }
+hud-moves = Moves: { $moves }
+hud-versus = Versus
+hud-versus-scores = P1: { $one } | P2: { $two }
+hud-online = Online
+hud-spectating = Spectating
+hud-daily = Daily: { $variant }
+hud-daily-unscored = Daily: { $variant } (unscored)
+player-one = Player 1
+player-two = Player 2
+
+## Game over
+
+title-game-over = GAME OVER
+title-level-complete = LEVEL COMPLETE
+title-solved = SOLVED
+title-winner = { $player } WINS
+title-draw = DRAW
+game-over-moves = Moves: { $moves } (best: { $best })
+game-over-player-score = { $player }: { $score }
+stats-game =
+    Clicks: { $clicks } ({ $wasted } wasted)
+    Survived: { $survived }
+    Peak lit tiles: { $lit }
+    Peak score per tick: { $peak }
+    Board size: { $width }x{ $height }
+stats-lifetime = Games played: { $games }, best score: { $best }, longest survival: { $survived }
+
+## Daily challenge
+
+daily-heading =
+    DAILY CHALLENGE
+    { $date } ({ $variant })
+    { $status }
+daily-status-score = Today's score: { $score }
+daily-status-abandoned = Today's attempt was abandoned
+daily-status-new = One scored attempt per day
+daily-variant-standard = Standard
+daily-variant-walled = Walled
+daily-variant-scattered = Scattered
+daily-variant-dim = Dim
+month-january = January
+month-february = February
+month-march = March
+month-april = April
+month-may = May
+month-june = June
+month-july = July
+month-august = August
+month-september = September
+month-october = October
+month-november = November
+month-december = December
+
+## Achievements
+
+achievement-unlocked = UNLOCKED: { $title }
+achievement-survivor = Survivor
+achievement-survivor-description = Survive for { $time }
+achievement-millionaire = Millionaire
+achievement-millionaire-description = Reach { $score } points
+achievement-floodlight = Floodlight
+achievement-floodlight-description = Light { $tiles } tiles at once
+achievement-closed-loop = Closed Loop
+achievement-closed-loop-description = Surround a dark tile with lit tiles
+
+## Spectating
+
+spectator-live = LIVE
+spectator-paused = PAUSED
+spectator-playing = PLAYING { $speed }x
+spectator-status = { $status }    Tick { $tick } / { $last }
+
+## Editor
+
+editor-help =
+    LMB paint, RMB erase, 0-9 lightness, W wall, L lamp, N normal, [ ] brush size
+    G goal corner, Up/Down clicks, Z undo, Y redo, Delete clear
+    Enter test, F5 save, F9 load, Esc main menu
+editor-text =
+    Brush: { $brush } ({ $size }x{ $size })
+    Clicks: { $clicks }
+    Goal: { $goal }
+    { $status }
+editor-brush-lightness = Lightness { $lightness }
+editor-brush-wall = Wall
+editor-brush-lamp = Lamp
+editor-goal-region = Light { $min } to { $max }
+editor-goal-score = Score { $score }
+editor-cant-test = Can't test: { $error }
+editor-saved = Saved { $path }
+editor-cant-save = Can't save: { $error }
+editor-loaded = Loaded { $path }
+editor-cant-load = Can't load: { $error }
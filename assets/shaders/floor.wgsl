@@ -0,0 +1,64 @@
+#import bevy_sprite::mesh2d_view_bind_group
+#import bevy_sprite::mesh2d_struct
+
+// These must match `FLOOR_GRID_SIZE` and `LIGHT_RADIUS` in board_plugin/src/materials.rs.
+let GRID_SIZE: i32 = 22;
+let LIGHT_RADIUS: i32 = 3;
+
+struct FloorMaterial {
+    ambient: vec4<f32>;
+    origin: vec2<f32>;
+    tile_size: f32;
+    // The colour of each tile in the grid, with its brightness in w.
+    lights: array<vec4<f32>, 484>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> view: View;
+
+[[group(1), binding(0)]]
+var<uniform> material: FloorMaterial;
+
+[[group(2), binding(0)]]
+var<uniform> mesh: Mesh2d;
+
+struct FragmentInput {
+    [[builtin(front_facing)]] is_front: bool;
+    [[location(0)]] world_position: vec4<f32>;
+    [[location(1)]] world_normal: vec3<f32>;
+    [[location(2)]] uv: vec2<f32>;
+#ifdef VERTEX_TANGENTS
+    [[location(3)]] world_tangent: vec4<f32>;
+#endif
+};
+
+[[stage(fragment)]]
+fn fragment(in: FragmentInput) -> [[location(0)]] vec4<f32> {
+    // In tiles, from the centre of the grid's first tile.
+    let position = (in.world_position.xy - material.origin) / material.tile_size;
+    let nearest_x = i32(floor(position.x + 0.5));
+    let nearest_y = i32(floor(position.y + 0.5));
+
+    var light: vec3<f32> = material.ambient.rgb;
+
+    for (var dy: i32 = -LIGHT_RADIUS; dy <= LIGHT_RADIUS; dy = dy + 1) {
+        for (var dx: i32 = -LIGHT_RADIUS; dx <= LIGHT_RADIUS; dx = dx + 1) {
+            let x = nearest_x + dx;
+            let y = nearest_y + dy;
+            if (x < 0 || y < 0 || x >= GRID_SIZE || y >= GRID_SIZE) {
+                continue;
+            }
+
+            let tile = material.lights[y * GRID_SIZE + x];
+            let distance = length(position - vec2<f32>(f32(x), f32(y)));
+
+            // Inverse square falloff, faded to nothing at the edge of the light's reach.
+            let reach = clamp(f32(LIGHT_RADIUS) - distance, 0.0, 1.0);
+            let falloff = tile.w * reach / (1.0 + 2.0 * distance * distance);
+
+            light = light + tile.rgb * falloff;
+        }
+    }
+
+    return vec4<f32>(light, 1.0);
+}
@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+/// A notice of an unlocked achievement, removed when its timer finishes.
+#[derive(Component)]
+pub struct AchievementToast(pub Timer);
@@ -1,11 +1,41 @@
+use bevy::app::Events;
+use bevy::log;
 use bevy::prelude::*;
+use std::fmt::Debug;
+use std::sync::Arc;
 
 #[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
 #[derive(Component)]
 pub struct UiRoot(pub Entity);
 
-#[derive(Component)]
-pub struct UiButton<'a, T: Send + Sync + Copy>(pub &'a T);
+/// Sends an event when clicked. The event can be of any type and carry data, such as which level
+/// to start.
+#[derive(Clone, Component)]
+pub struct UiButton {
+    send: Arc<dyn Fn(&mut World) + Send + Sync>,
+    description: String,
+}
+
+impl UiButton {
+    pub fn new<T: Debug + Clone + Send + Sync + 'static>(event: T) -> Self {
+        let description = format!("{:?}", event);
+
+        Self {
+            send: Arc::new(move |world| {
+                match world.get_resource_mut::<Events<T>>() {
+                    Some(mut events) => events.send(event.clone()),
+                    None => log::error!("{} isn't registered as an event", std::any::type_name::<T>()),
+                }
+            }),
+            description,
+        }
+    }
+
+    pub fn send(&self, world: &mut World) {
+        log::info!("Sending {}", self.description);
+        (self.send)(world);
+    }
+}
 
 #[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
 #[derive(Component)]
@@ -16,6 +46,28 @@ pub struct UiHighlightable {
     pub root_entity: Entity,
 }
 
+/// The root of a menu built by `spawn_menu`, removed when its screen is left.
 #[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
 #[derive(Component)]
-pub struct PauseMenu;
\ No newline at end of file
+pub struct MenuPanel;
+
+/// A button that can't be used right now. It's drawn faintly, and ignores clicks and keyboard focus.
+#[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
+#[derive(Component)]
+pub struct Disabled;
+
+/// The colours a UI node was built with, kept so it can be recoloured whenever the theme changes.
+#[derive(Debug, Clone, Component)]
+pub struct ThemedUi {
+    pub color: Option<Color>,
+    pub text_colors: Vec<Color>,
+    /// The default, hover and pressed colours of a highlightable node.
+    pub highlight_colors: Option<[Color; 3]>,
+}
+
+/// The sizes a UI node was built with, kept so it can be resized whenever the UI scale changes.
+#[derive(Debug, Clone, Component)]
+pub struct ScaledUi {
+    pub style: Style,
+    pub font_sizes: Vec<f32>,
+}
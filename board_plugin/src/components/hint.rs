@@ -0,0 +1,11 @@
+use crate::resources::Hint;
+use bevy::prelude::*;
+use bevy::tasks::Task;
+
+/// Highlights the tile a hint suggests triggering.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct HintMarker;
+
+/// A hint being searched for off the main thread.
+#[derive(Component)]
+pub struct HintTask(pub Task<Option<Hint>>);
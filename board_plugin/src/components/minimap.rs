@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+
+/// The minimap's frame, removed along with everything in it when the game ends.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+pub struct MinimapPanel;
+
+/// The map to click or drag across to move the camera.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+pub struct MinimapMap;
+
+/// The part of the board the camera can see, drawn over the map.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+pub struct MinimapViewport;
+
+/// An arrow at the edge of the screen pointing towards lit tiles out of view.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+pub struct OffscreenArrow;
@@ -1,9 +1,21 @@
+pub use achievements::*;
 pub use camera::*;
+pub use editor::*;
 pub use game_ui::*;
+pub use hint::*;
+pub use minimap::*;
+pub use online::*;
 pub use score::*;
+pub use spectator::*;
 pub use tile::*;
 
+mod achievements;
 mod camera;
+mod editor;
 mod game_ui;
+mod hint;
+mod minimap;
+mod online;
 mod score;
+mod spectator;
 mod tile;
@@ -0,0 +1,12 @@
+use crate::network::GameClient;
+use crate::resources::GameMode;
+use bevy::prelude::*;
+use bevy::tasks::Task;
+
+/// A connection to the server being made off the main thread.
+#[derive(Component)]
+pub struct ConnectTask {
+    pub task: Task<Result<GameClient, anyhow::Error>>,
+    /// `GameMode::Online` to play once connected, or `GameMode::Spectate` to watch.
+    pub game_mode: GameMode,
+}
@@ -3,3 +3,19 @@ use bevy::prelude::*;
 #[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
 pub struct Score(pub u64);
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct ClicksText;
+
+/// The line under the score with the rate, lit tiles, brightest tile and time, and a warning when
+/// the light is close to going out.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Component)]
+pub struct HudText {
+    pub is_warning: bool,
+}
+
+/// The final score on the game over menu, counting up from zero.
+#[derive(Debug, Copy, Clone, PartialEq, Component)]
+pub struct FinalScoreCountUp {
+    pub elapsed: f32,
+}
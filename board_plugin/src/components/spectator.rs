@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+pub struct SpectatorPanel;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+pub struct PlaybackText;
+
+/// The bar to click or drag along to seek.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+pub struct TimelineTrack;
+
+/// How far through the timeline playback is.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+pub struct TimelineFill;
@@ -1,22 +1,96 @@
 use crate::resources::Coordinates;
-use crate::resources::MAX_LIGHTNESS;
+use crate::resources::{Player, Theme, TileKind};
 use bevy::prelude::*;
+use bevy::tasks::Task;
 
 #[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
 pub struct TileComponent {
     pub coordinates: Coordinates,
     pub lightness: u8,
+    pub kind: TileKind,
+    pub owner: Option<Player>,
 }
 
 impl TileComponent {
-    pub fn get_color(&self) -> Color {
-        let c = (self.lightness as f32) / (MAX_LIGHTNESS as f32);
+    pub fn get_color(&self, theme: &Theme) -> Color {
+        theme.tile_color(self.lightness, self.kind, self.owner)
+    }
+}
+
+/// The lightness a tile is drawn with, which follows its real lightness over one tick rather than
+/// jumping to it.
+#[derive(Debug, Copy, Clone, PartialEq, Component)]
+pub struct TileFade {
+    pub from: f32,
+    pub to: f32,
+    pub shown: f32,
+    pub elapsed: f32,
+}
 
-        Color::rgb(c, c, c)
+impl TileFade {
+    pub fn new(lightness: u8) -> Self {
+        Self {
+            from: lightness as f32,
+            to: lightness as f32,
+            shown: lightness as f32,
+            elapsed: 0.,
+        }
     }
 }
 
+/// The soft halo behind a bright tile.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct TileGlow;
+
+/// A chunk of the lit floor drawn under the board in dynamic lighting mode.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct FloorChunk;
+
+/// A ring of light spreading out from a triggered tile.
+#[derive(Debug, Copy, Clone, PartialEq, Component)]
+pub struct TriggerPulse {
+    pub elapsed: f32,
+}
+
+/// A "+N" rising from a triggered tile with the score the trigger earns. It's hidden while it
+/// isn't in use.
+#[derive(Debug, Copy, Clone, PartialEq, Component)]
+pub struct ScorePopup {
+    /// Where it started, before rising.
+    pub origin: Vec3,
+    pub age: f32,
+    pub color: Color,
+}
+
+/// The score a trigger earns, being worked out off the main thread before its popup is shown.
+#[derive(Component)]
+pub struct ScorePopupTask {
+    pub coordinates: Coordinates,
+    pub task: Task<u64>,
+}
+
+/// A spark, ember or piece of a burst. It's hidden while it isn't in use.
+#[derive(Debug, Copy, Clone, PartialEq, Component)]
+pub struct Particle {
+    pub velocity: Vec2,
+    /// How much of its speed it loses each second.
+    pub drag: f32,
+    /// How fast it drifts upwards, in pixels per second per second.
+    pub lift: f32,
+    pub age: f32,
+    pub lifetime: f32,
+    pub color: Color,
+}
+
 #[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
 pub struct BoardComponent;
+
+/// Shows a tile's lightness band in high-contrast mode.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct LightnessLabel;
+
+/// Marks the tile player two would trigger in a versus game.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct VersusCursor;
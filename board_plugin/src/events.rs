@@ -1,7 +1,11 @@
-use crate::resources::Coordinates;
+use crate::resources::{Achievement, Coordinates, Player, Settings, TileMap};
 
 #[derive(Debug, Copy, Clone)]
-pub struct TileTriggerEvent(pub Coordinates);
+pub struct TileTriggerEvent {
+    pub coordinates: Coordinates,
+    /// The player the light is for in versus games. Clicks leave it to the mouse's player.
+    pub owner: Option<Player>,
+}
 
 #[derive(Debug, Copy, Clone)]
 pub struct TileUpdateEvent;
@@ -23,3 +27,96 @@ pub struct GoToMainMenuEvent;
 
 #[derive(Debug, Copy, Clone)]
 pub struct GameOverEvent(pub u64);
+
+#[derive(Debug, Copy, Clone)]
+pub struct OpenLevelSelectEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct StartLevelEvent(pub usize);
+
+#[derive(Debug, Copy, Clone)]
+pub struct LevelCompleteEvent(pub u64);
+
+#[derive(Debug, Copy, Clone)]
+pub struct StartClassicEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct ShowHintEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct ShowSolutionEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct OpenEditorEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct StartCustomLevelEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct StartPracticeEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct UndoEvent;
+
+/// Sent when a trigger lights tiles, unlike clicks that change nothing.
+#[derive(Debug, Copy, Clone)]
+pub struct TileLitEvent(pub Coordinates);
+
+/// Sent when a tile's light goes out.
+#[derive(Debug, Copy, Clone)]
+pub struct TileFadedOutEvent(pub Coordinates);
+
+/// Sent when a trigger closes a loop, with the unlit spaces it newly surrounds.
+#[derive(Debug, Clone)]
+pub struct LoopClosedEvent(pub Vec<Coordinates>);
+
+/// Sent when a trigger lights tiles, with the board before and after it, so what the trigger earns
+/// over the next `HINT_LOOKAHEAD_TICKS` ticks can be worked out off the main thread.
+#[derive(Debug, Clone)]
+pub struct TriggerScoreEvent {
+    pub coordinates: Coordinates,
+    pub previous_tile_map: TileMap,
+    pub tile_map: TileMap,
+}
+
+/// Sent when a click doesn't light anything new.
+#[derive(Debug, Copy, Clone)]
+pub struct WastedClickEvent(pub Coordinates);
+
+/// Sent after each tick is scored.
+#[derive(Debug, Copy, Clone)]
+pub struct TickEvent {
+    pub score: u64,
+    pub score_increment: u64,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct AchievementUnlockedEvent(pub Achievement);
+
+#[derive(Debug, Copy, Clone)]
+pub struct OpenAchievementsEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct OpenDailyEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct StartDailyEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct StartVersusEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct StartOnlineEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct StartSpectateEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct CycleThemeEvent;
+
+#[derive(Debug, Copy, Clone)]
+pub struct OpenSettingsEvent;
+
+/// Replaces the settings, with the one option its button changes.
+#[derive(Debug, Copy, Clone)]
+pub struct ChangeSettingsEvent(pub Settings);
@@ -1,15 +1,21 @@
 pub mod components;
+pub mod network;
 pub mod resources;
+pub mod simulation;
 pub mod systems;
 pub mod utils;
 
 mod events;
+mod materials;
+mod persistence;
 mod tick;
 use bevy::log;
 use bevy::prelude::*;
 use components::*;
+use network::GameClient;
 use events::*;
 use game_state::*;
+use materials::FloorMaterialPlugin;
 use resources::*;
 use systems::*;
 use tick::*;
@@ -22,26 +28,69 @@ pub struct BoardPlugin;
 impl Plugin for BoardPlugin {
     fn build(&self, app: &mut App) {
         app.add_state(GameState::Init);
+        app.add_plugin(FloorMaterialPlugin);
 
         app.insert_resource(ClearColor(Color::BLACK));
+        app.insert_resource(Theme::default());
+        app.insert_resource(Settings::default());
+        app.insert_resource(Locale::default());
+        app.insert_resource(UiFocus::default());
         app.insert_resource(UpdateTickTimer::new(0.1));
         app.insert_resource(FinalScore(0));
+        app.insert_resource(GameMode::default());
+        app.insert_resource(Editor::default());
+
+        app.add_asset::<Level>();
+        app.add_asset::<LevelIndex>();
+        app.init_asset_loader::<LevelLoader>();
+        app.init_asset_loader::<LevelIndexLoader>();
 
         self.setup_global_systems(app);
         self.setup_init_systems(app);
         self.setup_main_menu_systems(app);
+        self.setup_level_select_systems(app);
+        self.setup_achievements_systems(app);
+        self.setup_daily_systems(app);
+        self.setup_settings_systems(app);
+        self.setup_editor_systems(app);
         self.setup_in_game_systems(app);
         self.setup_paused_systems(app);
         self.setup_game_over_systems(app);
 
         app.add_event::<TileTriggerEvent>();
         app.add_event::<TileUpdateEvent>();
+        app.add_event::<TileFadedOutEvent>();
+        app.add_event::<TileLitEvent>();
+        app.add_event::<LoopClosedEvent>();
+        app.add_event::<TriggerScoreEvent>();
         app.add_event::<TileCreateEvent>();
         app.add_event::<StartGameEvent>();
         app.add_event::<ExitGameEvent>();
         app.add_event::<TogglePauseEvent>();
         app.add_event::<GoToMainMenuEvent>();
         app.add_event::<GameOverEvent>();
+        app.add_event::<OpenLevelSelectEvent>();
+        app.add_event::<StartLevelEvent>();
+        app.add_event::<LevelCompleteEvent>();
+        app.add_event::<StartClassicEvent>();
+        app.add_event::<ShowHintEvent>();
+        app.add_event::<ShowSolutionEvent>();
+        app.add_event::<OpenEditorEvent>();
+        app.add_event::<StartCustomLevelEvent>();
+        app.add_event::<StartPracticeEvent>();
+        app.add_event::<UndoEvent>();
+        app.add_event::<WastedClickEvent>();
+        app.add_event::<TickEvent>();
+        app.add_event::<AchievementUnlockedEvent>();
+        app.add_event::<OpenAchievementsEvent>();
+        app.add_event::<OpenDailyEvent>();
+        app.add_event::<StartDailyEvent>();
+        app.add_event::<StartVersusEvent>();
+        app.add_event::<StartOnlineEvent>();
+        app.add_event::<StartSpectateEvent>();
+        app.add_event::<CycleThemeEvent>();
+        app.add_event::<OpenSettingsEvent>();
+        app.add_event::<ChangeSettingsEvent>();
 
         #[cfg(feature = "debug")]
         self.register_inspectables(app);
@@ -55,12 +104,41 @@ impl BoardPlugin {
         app.add_system_set(
             SystemSet::new()
                 .with_system(game_state::handle_start_game_event)
+                .with_system(game_state::handle_start_practice_event)
+                .with_system(game_state::handle_start_versus_event)
+                .with_system(online::handle_start_online_event)
+                .with_system(online::poll_connection)
+                .with_system(spectator::handle_start_spectate_event)
                 .with_system(game_state::handle_exit_game_event)
                 .with_system(game_state::handle_go_to_main_menu_event)
                 .with_system(game_state::handle_game_over_event)
                 .with_system(game_ui::handle_ui_highlight)
+                .with_system(game_ui::handle_ui_button_click)
+                .with_system(game_ui::handle_ui_focus_keys)
                 .with_system(input::handle_keyboard_input)
                 .with_system(game_state::handle_toggle_pause_event)
+                .with_system(game_state::handle_open_level_select_event)
+                .with_system(game_state::handle_start_level_event)
+                .with_system(game_state::handle_start_classic_event)
+                .with_system(game_state::handle_open_editor_event)
+                .with_system(game_state::handle_start_custom_level_event)
+                .with_system(puzzle::handle_level_complete_event)
+                .with_system(puzzle::populate_levels)
+                .with_system(stats::handle_game_end)
+                .with_system(game_state::handle_open_achievements_event)
+                .with_system(achievements::show_toasts)
+                .with_system(achievements::update_toasts)
+                .with_system(game_state::handle_open_daily_event)
+                .with_system(daily::handle_start_daily_event)
+                .with_system(daily::handle_game_over_event)
+                .with_system(theme::handle_cycle_theme_event)
+                .with_system(theme::theme_new_ui)
+                .with_system(theme::apply_theme)
+                .with_system(game_state::handle_open_settings_event)
+                .with_system(settings::handle_settings_events)
+                .with_system(settings::scale_new_ui)
+                .with_system(settings::apply_ui_scale)
+                .with_system(settings::apply_language)
         );
     }
 
@@ -69,6 +147,13 @@ impl BoardPlugin {
             SystemSet::on_enter(GameState::Init)
                 .label("Initialize UI")
                 .with_system(game_ui::setup_ui)
+                .with_system(puzzle::load_levels)
+                .with_system(stats::load_lifetime_stats)
+                .with_system(achievements::load_achievements)
+                .with_system(daily::load_daily_results)
+                .with_system(theme::load_theme)
+                .with_system(settings::load_settings)
+                .with_system(effects::setup_effect_textures)
         );
         app.add_system_set(
             SystemSet::on_enter(GameState::Init)
@@ -83,37 +168,163 @@ impl BoardPlugin {
         app.add_system_set(
             SystemSet::on_enter(GameState::MainMenu)
                 .with_system(game_ui::setup_main_menu_ui)
+                .with_system(online::disconnect)
+                .with_system(spectator::stop_spectating)
         );
         app.add_system_set(
-            SystemSet::on_update(GameState::MainMenu)
-                .with_system(game_ui::handle_ui_button_click::<StartGameEvent>)
-                .with_system(game_ui::handle_ui_button_click::<ExitGameEvent>)
+            SystemSet::on_exit(GameState::MainMenu)
+                .with_system(menu::teardown_menu)
+                .with_system(online::cancel_connecting)
         );
+    }
+
+    pub fn setup_level_select_systems(&self, app: &mut App) {
         app.add_system_set(
-            SystemSet::on_exit(GameState::MainMenu)
-                .with_system(game_ui::teardown_main_menu_ui)
+            SystemSet::on_enter(GameState::LevelSelect)
+                .with_system(game_ui::setup_level_select_ui)
+        );
+        app.add_system_set(
+            SystemSet::on_update(GameState::LevelSelect)
+                .with_system(game_ui::refresh_level_select_ui)
+        );
+        app.add_system_set(
+            SystemSet::on_exit(GameState::LevelSelect)
+                .with_system(menu::teardown_menu)
         );
     }
     
+    pub fn setup_achievements_systems(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(GameState::Achievements)
+                .with_system(game_ui::setup_achievements_ui)
+        );
+        app.add_system_set(
+            SystemSet::on_exit(GameState::Achievements)
+                .with_system(menu::teardown_menu)
+        );
+    }
+
+    pub fn setup_daily_systems(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(GameState::Daily)
+                .with_system(game_ui::setup_daily_ui)
+        );
+        app.add_system_set(
+            SystemSet::on_exit(GameState::Daily)
+                .with_system(menu::teardown_menu)
+        );
+    }
+
+    pub fn setup_settings_systems(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(GameState::Settings)
+                .with_system(game_ui::setup_settings_ui)
+        );
+        app.add_system_set(
+            SystemSet::on_update(GameState::Settings)
+                .with_system(game_ui::refresh_settings_ui)
+        );
+        app.add_system_set(
+            SystemSet::on_exit(GameState::Settings)
+                .with_system(menu::teardown_menu)
+        );
+    }
+
+    pub fn setup_editor_systems(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(GameState::Editor)
+                .with_system(Self::setup_camera)
+                .with_system(editor::setup_editor)
+                .with_system(editor::setup_editor_ui)
+        );
+        app.add_system_set(
+            SystemSet::on_update(GameState::Editor)
+                .with_system(editor::paint_tiles)
+                .with_system(editor::handle_editor_keys)
+                .with_system(editor::update_editor_text)
+                .with_system(editor::update_goal_marker)
+                .with_system(tile::update_tiles)
+                .with_system(tile::create_new_tiles)
+                .with_system(tile::animate_tiles)
+                .with_system(floor::update_floor)
+                .with_system(settings::update_lightness_labels)
+        );
+        app.add_system_set(
+            SystemSet::on_exit(GameState::Editor)
+                .with_system(editor::teardown_editor)
+                .with_system(floor::teardown_floor)
+                .with_system(Self::teardown_camera)
+                .with_system(Self::teardown_board)
+        );
+    }
+
     pub fn setup_in_game_systems(&self, app: &mut App) {
         app.add_system_set(
             SystemSet::on_enter(GameState::InGame)
                 .with_system(Self::setup_camera)
                 .with_system(Self::create_board)
                 .with_system(scoring::setup_score_ui)
+                .with_system(spectator::setup_spectator_ui)
+                .with_system(particles::setup_particles)
+                .with_system(popups::setup_score_popups)
+                .with_system(minimap::setup_minimap)
         );
         app.add_system_set(
             SystemSet::on_update(GameState::InGame)
                 .with_system(input::handle_mouse_input)
-                .with_system(lighting::handle_tile_trigger)
+                .with_system(lighting::handle_tile_trigger.label("Trigger tiles"))
                 .with_system(lighting::update)
+                .with_system(lighting::handle_undo)
                 .with_system(scoring::update_score)
+                .with_system(scoring::update_hud)
+                .with_system(scoring::pulse_hud_warning)
                 .with_system(tile::update_tiles)
                 .with_system(tile::create_new_tiles)
+                .with_system(tile::animate_tiles)
+                .with_system(floor::update_floor)
+                .with_system(effects::spawn_trigger_pulses)
+                .with_system(effects::animate_trigger_pulses)
+                .with_system(particles::emit_particles)
+                .with_system(particles::update_particles)
+                .with_system(popups::start_score_popups)
+                .with_system(popups::show_score_popups)
+                .with_system(popups::update_score_popups)
+                .with_system(settings::update_lightness_labels)
+                .with_system(puzzle::check_goal)
+                .with_system(puzzle::update_clicks_text)
+                .with_system(classic::check_solved)
+                .with_system(classic::show_hints)
+                .with_system(hint::request_hint)
+                .with_system(hint::show_hint)
+                .with_system(hint::clear_hints)
+                // Clicks are counted after the move is recorded, so undoing it takes them back too.
+                .with_system(stats::record_clicks.after("Trigger tiles"))
+                .with_system(stats::record_ticks)
+                .with_system(achievements::check_achievements)
+                .with_system(versus::handle_cursor_input)
+                .with_system(versus::update_cursor)
+                .with_system(versus::update_scores)
+                .with_system(online::send_triggers)
+                .with_system(online::poll_server)
+                .with_system(spectator::handle_playback_input)
+                .with_system(spectator::handle_timeline_input)
+                .with_system(spectator::update_playback)
+                .with_system(spectator::update_spectator_ui)
+                .with_system(spectator::move_camera)
+                .with_system(minimap::update_minimap)
+                .with_system(minimap::handle_minimap_input)
+                .with_system(minimap::update_minimap_viewport)
+                .with_system(minimap::update_offscreen_arrows)
         );
         app.add_system_set(
             SystemSet::on_exit(GameState::InGame)
                 .with_system(scoring::teardown_score_ui)
+                .with_system(hint::teardown_hints)
+                .with_system(spectator::teardown_spectator_ui)
+                .with_system(particles::teardown_particles)
+                .with_system(popups::teardown_score_popups)
+                .with_system(minimap::teardown_minimap)
+                .with_system(floor::teardown_floor)
                 .with_system(Self::teardown_camera)
                 .with_system(Self::teardown_board)
         );
@@ -126,13 +337,12 @@ impl BoardPlugin {
         );
         app.add_system_set(
             SystemSet::on_update(GameState::Paused)
-                .with_system(game_ui::handle_ui_button_click::<TogglePauseEvent>)
-                .with_system(game_ui::handle_ui_button_click::<GoToMainMenuEvent>)
-                .with_system(game_ui::handle_ui_button_click::<ExitGameEvent>)
+                .with_system(lighting::handle_undo)
+                .with_system(game_ui::refresh_pause_menu_ui)
         );
         app.add_system_set(
             SystemSet::on_exit(GameState::Paused)
-                .with_system(game_ui::teardown_pause_menu_ui)
+                .with_system(menu::teardown_menu)
         );
     }
     
@@ -143,13 +353,11 @@ impl BoardPlugin {
         );
         app.add_system_set(
             SystemSet::on_update(GameState::GameOver)
-                .with_system(game_ui::handle_ui_button_click::<StartGameEvent>)
-                .with_system(game_ui::handle_ui_button_click::<GoToMainMenuEvent>)
-                .with_system(game_ui::handle_ui_button_click::<ExitGameEvent>)
+                .with_system(scoring::count_up_final_score)
         );
         app.add_system_set(
             SystemSet::on_exit(GameState::GameOver)
-                .with_system(game_ui::teardown_game_over_menu_ui)
+                .with_system(menu::teardown_menu)
         );
     }
 
@@ -167,33 +375,96 @@ impl BoardPlugin {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn create_board(
         mut commands: Commands,
+        game_mode: Res<GameMode>,
+        levels: Res<Levels>,
+        level_assets: Res<Assets<Level>>,
+        editor: Res<Editor>,
+        daily_challenge: Option<Res<DailyChallenge>>,
+        game_client: Option<Res<GameClient>>,
+        spectator: Option<Res<Spectator>>,
         mut tile_create_evw: EventWriter<TileCreateEvent>,
     ) {
-        let tile_map = TileMap::new();
+        let mut tile_size = 16.;
+        let mut tile_padding = 2.;
 
-        let tile_size = 16.;
-        let tile_padding = 2.;
+        commands.remove_resource::<PuzzleState>();
+        commands.remove_resource::<ClassicPuzzle>();
+        commands.remove_resource::<GameHistory>();
+        commands.remove_resource::<Versus>();
+        commands.insert_resource(GameStats::default());
 
-        let entity = commands.spawn()
-            .insert(Name::new("Board"))
-            .insert(Transform::from_translation(Vec3::new(0., 0., 0.)))
-            .insert(GlobalTransform::default())
-            .insert(BoardComponent)
-            .id();
-        
-        commands.insert_resource(Board {
-            tile_map,
-            tile_size,
-            tile_padding,
-            entity,
-        });
-
-        for coordinate in NEIGHBOUR_OFFSETS {
-            tile_create_evw.send(TileCreateEvent(coordinate.into()));
+        if game_mode.allows_undo() {
+            commands.insert_resource(GameHistory::default());
         }
-        tile_create_evw.send(TileCreateEvent(Coordinates::ZERO));
+
+        let tile_map = match *game_mode {
+            GameMode::Endless | GameMode::Practice => TileMap::new(),
+            GameMode::Puzzle(i) => {
+                let level = levels.levels.get(i)
+                    .and_then(|(_, handle)| { level_assets.get(handle) });
+
+                match level {
+                    Some(level) => {
+                        commands.insert_resource(PuzzleState {
+                            level: Some(i),
+                            clicks_remaining: level.clicks,
+                            goal: level.goal,
+                            is_complete: false,
+                        });
+                        level.to_tile_map()
+                    },
+                    None => {
+                        log::error!("Level {} isn't loaded, starting an endless game instead", i);
+                        TileMap::new()
+                    },
+                }
+            },
+            GameMode::Daily => match daily_challenge {
+                Some(challenge) => challenge.generate(),
+                None => {
+                    log::error!("No daily challenge was set up, starting an endless game instead");
+                    TileMap::new()
+                },
+            },
+            GameMode::Online => match game_client {
+                Some(game_client) => game_client.tile_map().clone(),
+                None => {
+                    log::error!("Not connected to a server, starting an endless game instead");
+                    TileMap::new()
+                },
+            },
+            GameMode::Spectate => match spectator.as_ref().and_then(|spectator| { spectator.frame() }) {
+                Some(frame) => frame.tile_map.clone(),
+                None => TileMap::new(),
+            },
+            GameMode::Versus => {
+                commands.insert_resource(Versus::default());
+                Versus::generate_tile_map()
+            },
+            GameMode::Classic(size) => {
+                tile_size = 64.;
+                tile_padding = 4.;
+
+                let tile_map = ClassicPuzzle::generate(size, &mut rand::thread_rng());
+                commands.insert_resource(ClassicPuzzle::new(size, &tile_map));
+                tile_map
+            },
+            GameMode::Custom => {
+                let level = editor.to_level(&editor.tile_map);
+                commands.insert_resource(PuzzleState {
+                    level: None,
+                    clicks_remaining: level.clicks,
+                    goal: level.goal,
+                    is_complete: false,
+                });
+                level.to_tile_map()
+            },
+        };
+
+        tile::spawn_board(&mut commands, tile_map, tile_size, tile_padding, &mut tile_create_evw);
     }
 
     fn teardown_board(
@@ -0,0 +1,170 @@
+use bevy::ecs::system::{lifetimeless::SRes, SystemParamItem};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::render::render_asset::{PrepareAssetError, RenderAsset};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::RenderDevice;
+use bevy::sprite::{Material2d, Material2dPipeline, Material2dPlugin};
+
+pub const FLOOR_SHADER_HANDLE: HandleUntyped = HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 7304511942061538213);
+
+/// How many tiles along each side of a chunk of floor.
+pub const FLOOR_CHUNK_SIZE: i32 = 16;
+/// How far a tile's light reaches, in tiles.
+pub const LIGHT_RADIUS: i32 = 3;
+/// How many tiles along each side of the grid a chunk of floor is lit from: the chunk, and the
+/// tiles close enough around it to light its edges. This and `LIGHT_RADIUS` must match
+/// `assets/shaders/floor.wgsl`.
+pub const FLOOR_GRID_SIZE: i32 = FLOOR_CHUNK_SIZE + 2 * LIGHT_RADIUS;
+
+/// The size of a floor material's uniform: the ambient colour, the origin and tile size padded to
+/// 16 bytes, and a colour for every tile in the grid.
+const FLOOR_UNIFORM_SIZE: u64 = 32 + 16 * (FLOOR_GRID_SIZE * FLOOR_GRID_SIZE) as u64;
+
+/// Adds the floor material and its shader, which is built into the game like the locale files.
+#[derive(Default)]
+pub struct FloorMaterialPlugin;
+
+impl Plugin for FloorMaterialPlugin {
+    fn build(&self, app: &mut App) {
+        let mut shaders = app.world.get_resource_mut::<Assets<Shader>>().unwrap();
+        shaders.set_untracked(
+            FLOOR_SHADER_HANDLE,
+            Shader::from_wgsl(include_str!("../../assets/shaders/floor.wgsl")),
+        );
+
+        app.add_plugin(Material2dPlugin::<FloorMaterial>::default());
+    }
+}
+
+/// A chunk of dark floor lit by the tiles on and around it, each acting as a point light.
+#[derive(Debug, Clone, PartialEq, TypeUuid)]
+#[uuid = "5b0e7c1e-3c55-4d0e-9a47-6f1f2d1b8a90"]
+pub struct FloorMaterial {
+    /// The colour of floor no light reaches.
+    pub ambient: Color,
+    /// Where the centre of the grid's first tile is, in world space.
+    pub origin: Vec2,
+    pub tile_size: f32,
+    /// The colour of each tile in the grid, row by row from the bottom, and how brightly it shines
+    /// from 0 to 1.
+    pub lights: Vec<(Color, f32)>,
+}
+
+impl FloorMaterial {
+    /// The material as the shader's uniform, laid out by std140 rules.
+    fn uniform_bytes(&self) -> Vec<u8> {
+        let [r, g, b, a] = self.ambient.as_linear_rgba_f32();
+        let mut values = vec![r, g, b, a, self.origin.x, self.origin.y, self.tile_size, 0.];
+
+        for (color, brightness) in self.lights.iter() {
+            let [r, g, b, _] = color.as_linear_rgba_f32();
+            values.extend_from_slice(&[r, g, b, *brightness]);
+        }
+        values.resize((FLOOR_UNIFORM_SIZE / 4) as usize, 0.);
+
+        values.into_iter().flat_map(|value| { value.to_le_bytes() }).collect()
+    }
+}
+
+/// The GPU side of a [`FloorMaterial`].
+pub struct GpuFloorMaterial {
+    pub buffer: Buffer,
+    pub bind_group: BindGroup,
+}
+
+impl RenderAsset for FloorMaterial {
+    type ExtractedAsset = FloorMaterial;
+    type PreparedAsset = GpuFloorMaterial;
+    type Param = (SRes<RenderDevice>, SRes<Material2dPipeline<FloorMaterial>>);
+
+    fn extract_asset(&self) -> Self::ExtractedAsset {
+        self.clone()
+    }
+
+    fn prepare_asset(
+        material: Self::ExtractedAsset,
+        (render_device, pipeline): &mut SystemParamItem<Self::Param>,
+    ) -> Result<Self::PreparedAsset, PrepareAssetError<Self::ExtractedAsset>> {
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("floor_material_uniform_buffer"),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            contents: &material.uniform_bytes(),
+        });
+
+        let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("floor_material_bind_group"),
+            layout: &pipeline.material2d_layout,
+        });
+
+        Ok(GpuFloorMaterial {
+            buffer,
+            bind_group,
+        })
+    }
+}
+
+impl Material2d for FloorMaterial {
+    fn fragment_shader(_asset_server: &AssetServer) -> Option<Handle<Shader>> {
+        Some(FLOOR_SHADER_HANDLE.typed())
+    }
+
+    fn bind_group(material: &GpuFloorMaterial) -> &BindGroup {
+        &material.bind_group
+    }
+
+    fn bind_group_layout(render_device: &RenderDevice) -> BindGroupLayout {
+        render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: BufferSize::new(FLOOR_UNIFORM_SIZE),
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("floor_material_layout"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHADER: &str = include_str!("../../assets/shaders/floor.wgsl");
+
+    #[test]
+    fn shader_constants_match() {
+        assert!(SHADER.contains(&format!("let GRID_SIZE: i32 = {};", FLOOR_GRID_SIZE)));
+        assert!(SHADER.contains(&format!("let LIGHT_RADIUS: i32 = {};", LIGHT_RADIUS)));
+        assert!(SHADER.contains(&format!("array<vec4<f32>, {}>", FLOOR_GRID_SIZE * FLOOR_GRID_SIZE)));
+    }
+
+    #[test]
+    fn uniform_fills_the_binding() {
+        let material = FloorMaterial {
+            ambient: Color::BLACK,
+            origin: Vec2::new(-3., -3.),
+            tile_size: 20.,
+            lights: vec![(Color::WHITE, 1.); 10],
+        };
+
+        let bytes = material.uniform_bytes();
+        assert_eq!(bytes.len() as u64, FLOOR_UNIFORM_SIZE);
+
+        // The first light starts after the ambient colour, origin and tile size.
+        let first_brightness = f32::from_le_bytes(bytes[44..48].try_into().unwrap());
+        assert_eq!(first_brightness, 1.);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::resources::{Coordinates, TileMap};
+use std::collections::{BTreeMap, VecDeque};
+
+/// How many ticks a late trigger can be moved back by default.
+pub const DEFAULT_MAX_REWIND_TICKS: u64 = 20;
+
+/// The server's copy of the game, the only one that counts.
+///
+/// Triggers arrive late, after the player's click has travelled over the network. Each one is
+/// tagged with the tick the player saw, so the game rewinds to that tick, applies the trigger,
+/// and replays every tick since. Players on slow connections light the tile they aimed at rather
+/// than one that has faded in the meantime.
+#[derive(Debug, Clone)]
+pub struct Authority {
+    tile_map: TileMap,
+    tick: u64,
+    score: u64,
+    is_over: bool,
+    max_rewind_ticks: u64,
+    /// The board at the start of each recent tick, before its triggers, oldest first.
+    history: VecDeque<TickStart>,
+    /// The triggers applied during each recent tick, in the order they arrived.
+    triggers: BTreeMap<u64, Vec<Coordinates>>,
+}
+
+#[derive(Debug, Clone)]
+struct TickStart {
+    tick: u64,
+    tile_map: TileMap,
+    score: u64,
+}
+
+impl Authority {
+    pub fn new(tile_map: TileMap, max_rewind_ticks: u64) -> Self {
+        let mut authority = Self {
+            tile_map,
+            tick: 0,
+            score: 0,
+            is_over: false,
+            max_rewind_ticks,
+            history: VecDeque::new(),
+            triggers: BTreeMap::new(),
+        };
+        authority.record_tick_start();
+
+        authority
+    }
+
+    pub fn tile_map(&self) -> &TileMap {
+        &self.tile_map
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn score(&self) -> u64 {
+        self.score
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_over
+    }
+
+    /// Triggers a tile as the board was at `tick`. Ticks older than the rewind window are treated
+    /// as the oldest one kept, and ticks from the future as the current one.
+    ///
+    /// Returns `true` if the tile was lit.
+    pub fn trigger(&mut self, tick: u64, coordinates: Coordinates) -> bool {
+        let oldest_tick = self.history.front().map_or(self.tick, |start| { start.tick });
+        let tick = tick.clamp(oldest_tick, self.tick);
+
+        self.triggers.entry(tick).or_default().push(coordinates);
+
+        if tick == self.tick {
+            return self.tile_map.make_tile_white(coordinates.x, coordinates.y);
+        }
+
+        // Rewind to the start of the trigger's tick, then replay up to now.
+        let current_tick = self.tick;
+        let start = self.history[(tick - oldest_tick) as usize].clone();
+        self.history.truncate((tick - oldest_tick) as usize);
+        self.tile_map = start.tile_map;
+        self.score = start.score;
+        self.tick = start.tick;
+
+        let mut is_lit = false;
+        loop {
+            self.record_tick_start();
+
+            let results = self.apply_triggers();
+            if self.tick == tick {
+                is_lit = results.last().copied().unwrap_or(false);
+            }
+
+            if self.tick == current_tick {
+                break;
+            }
+
+            self.advance();
+        }
+
+        is_lit
+    }
+
+    /// Scores the board and lets it fade, as a tick of endless mode does. Returns the score
+    /// earned.
+    pub fn step(&mut self) -> u64 {
+        let score_increment = self.advance();
+        self.record_tick_start();
+
+        while self.history.len() as u64 > self.max_rewind_ticks + 1 {
+            self.history.pop_front();
+        }
+
+        if let Some(oldest) = self.history.front() {
+            let oldest_tick = oldest.tick;
+            self.triggers.retain(|tick, _| { *tick >= oldest_tick });
+        }
+
+        score_increment
+    }
+
+    fn advance(&mut self) -> u64 {
+        let score_increment = self.tile_map.tick_score();
+        self.score += score_increment;
+        self.is_over = !self.tile_map.has_fading_light();
+
+        self.tile_map.tick_update();
+        self.tick += 1;
+
+        score_increment
+    }
+
+    fn apply_triggers(&mut self) -> Vec<bool> {
+        let triggers = self.triggers.get(&self.tick).cloned().unwrap_or_default();
+
+        triggers.into_iter()
+            .map(|coordinates| { self.tile_map.make_tile_white(coordinates.x, coordinates.y) })
+            .collect()
+    }
+
+    fn record_tick_start(&mut self) {
+        self.history.push_back(TickStart {
+            tick: self.tick,
+            tile_map: self.tile_map.clone(),
+            score: self.score,
+        });
+    }
+}
@@ -0,0 +1,273 @@
+use crate::network::{apply_tiles, decode, encode, ClientMessage, ServerMessage};
+use crate::resources::{Coordinates, TileMap};
+use bevy::log;
+use bevy::tasks::{Task, TaskPool};
+use futures_lite::future;
+use std::io::ErrorKind;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use tungstenite::{Error, Message, WebSocket};
+
+/// How long to wait between attempts to reconnect.
+const RECONNECT_INTERVAL: Duration = Duration::from_millis(500);
+/// How long connecting and the handshake may each take before the attempt is given up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A connection to a game server, with a copy of the board kept up to date from its messages.
+/// The connection is reopened whenever it's lost, as the same player.
+pub struct GameClient {
+    addr: String,
+    socket: Option<WebSocket<TcpStream>>,
+    /// Reconnecting is done on the thread pool, so polling never waits on the server.
+    thread_pool: TaskPool,
+    reconnect_task: Option<Task<Result<WebSocket<TcpStream>, anyhow::Error>>>,
+    session: Option<u64>,
+    player: Option<u32>,
+    tile_map: TileMap,
+    tick: u64,
+    score: u64,
+    /// Triggers made while disconnected, sent once the connection is back.
+    pending: Vec<ClientMessage>,
+    next_reconnect: Instant,
+}
+
+impl GameClient {
+    /// Connects to the server at `addr`, such as `127.0.0.1:9001`. This blocks until connected, so
+    /// it should be called from `thread_pool`, which is also where lost connections are reopened.
+    pub fn connect(addr: &str, thread_pool: TaskPool) -> Result<Self, anyhow::Error> {
+        let mut client = Self {
+            addr: addr.to_string(),
+            socket: None,
+            thread_pool,
+            reconnect_task: None,
+            session: None,
+            player: None,
+            tile_map: TileMap::from_tiles([]),
+            tick: 0,
+            score: 0,
+            pending: Vec::new(),
+            next_reconnect: Instant::now(),
+        };
+        client.open()?;
+
+        Ok(client)
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.socket.is_some()
+    }
+
+    /// `None` until the server has welcomed the client.
+    pub fn session(&self) -> Option<u64> {
+        self.session
+    }
+
+    pub fn player(&self) -> Option<u32> {
+        self.player
+    }
+
+    pub fn tile_map(&self) -> &TileMap {
+        &self.tile_map
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn score(&self) -> u64 {
+        self.score
+    }
+
+    /// Asks the server to trigger a tile, tagged with the tick the board is at here.
+    pub fn trigger(&mut self, coordinates: Coordinates) {
+        let message = ClientMessage::Trigger {
+            tick: self.tick,
+            x: coordinates.x,
+            y: coordinates.y,
+        };
+
+        if !self.send(message) {
+            self.pending.push(message);
+        }
+    }
+
+    /// Applies every message the server has sent since the last poll, and returns them. Lost
+    /// connections are reopened in the background, and picked up here once they're made.
+    pub fn poll(&mut self) -> Vec<ServerMessage> {
+        self.poll_reconnect();
+        self.flush();
+
+        let mut messages = Vec::new();
+        while let Some(message) = self.read() {
+            self.apply(&message);
+            messages.push(message);
+        }
+
+        messages
+    }
+
+    /// Drops the connection and opens a new one, carrying on as the same player. Unlike polling, this
+    /// blocks until connected.
+    pub fn reconnect(&mut self) -> Result<(), anyhow::Error> {
+        if let Some(mut socket) = self.socket.take() {
+            let _ = socket.close(None);
+            let _ = socket.flush();
+        }
+        self.reconnect_task = None;
+
+        self.open()
+    }
+
+    fn open(&mut self) -> Result<(), anyhow::Error> {
+        self.next_reconnect = Instant::now() + RECONNECT_INTERVAL;
+        let socket = open_socket(&self.addr)?;
+        self.attach(socket);
+
+        Ok(())
+    }
+
+    /// Starts reconnecting once the connection has been lost for long enough, and takes the new
+    /// connection once it's made.
+    fn poll_reconnect(&mut self) {
+        if self.socket.is_some() {
+            return;
+        }
+
+        let task = match self.reconnect_task.as_mut() {
+            Some(task) => task,
+            None => {
+                if Instant::now() >= self.next_reconnect {
+                    let addr = self.addr.clone();
+                    self.reconnect_task = Some(self.thread_pool.spawn(async move { open_socket(&addr) }));
+                }
+                return;
+            },
+        };
+
+        let result = match future::block_on(future::poll_once(task)) {
+            Some(result) => result,
+            None => return,
+        };
+
+        self.reconnect_task = None;
+        self.next_reconnect = Instant::now() + RECONNECT_INTERVAL;
+
+        match result {
+            Ok(socket) => self.attach(socket),
+            Err(e) => log::warn!("Can't reconnect to {}: {}", self.addr, e),
+        }
+    }
+
+    /// Starts using a newly opened connection, as the same player as before.
+    fn attach(&mut self, socket: WebSocket<TcpStream>) {
+        self.socket = Some(socket);
+
+        log::info!("Connected to {}", self.addr);
+
+        self.send(ClientMessage::Hello { session: self.session });
+        for message in std::mem::take(&mut self.pending) {
+            self.send(message);
+        }
+    }
+
+    fn apply(&mut self, message: &ServerMessage) {
+        match message {
+            ServerMessage::Welcome { session, player } => {
+                self.session = Some(*session);
+                self.player = Some(*player);
+            },
+            ServerMessage::Snapshot { tick, score, tiles } => {
+                self.tile_map = TileMap::from_tiles([]);
+                apply_tiles(&mut self.tile_map, tiles);
+                self.tick = *tick;
+                self.score = *score;
+            },
+            ServerMessage::Delta { tick, score, tiles } => {
+                apply_tiles(&mut self.tile_map, tiles);
+                self.tick = *tick;
+                self.score = *score;
+            },
+            ServerMessage::GameOver { .. } => {},
+        }
+    }
+
+    fn read(&mut self) -> Option<ServerMessage> {
+        let socket = self.socket.as_mut()?;
+
+        loop {
+            match socket.read() {
+                Ok(message @ Message::Text(_)) => match decode(&message) {
+                    Some(message) => return Some(message),
+                    None => log::warn!("Ignoring malformed message: {}", message),
+                },
+                Ok(_) => {},
+                Err(Error::Io(e)) if e.kind() == ErrorKind::WouldBlock => return None,
+                Err(e) => {
+                    self.lose_connection(e);
+                    return None;
+                },
+            }
+        }
+    }
+
+    /// Returns `false` if there's no connection to send on.
+    fn send(&mut self, message: ClientMessage) -> bool {
+        let socket = match self.socket.as_mut() {
+            Some(socket) => socket,
+            None => return false,
+        };
+
+        match socket.send(encode(&message)) {
+            Ok(_) => true,
+            // Queued, and written by the next poll.
+            Err(Error::Io(e)) if e.kind() == ErrorKind::WouldBlock => true,
+            Err(e) => {
+                self.lose_connection(e);
+                false
+            },
+        }
+    }
+
+    fn flush(&mut self) {
+        let socket = match self.socket.as_mut() {
+            Some(socket) => socket,
+            None => return,
+        };
+
+        match socket.flush() {
+            Ok(_) => {},
+            Err(Error::Io(e)) if e.kind() == ErrorKind::WouldBlock => {},
+            Err(e) => self.lose_connection(e),
+        }
+    }
+
+    fn lose_connection(&mut self, error: Error) {
+        log::warn!("Lost connection to {}: {}", self.addr, error);
+        self.socket = None;
+    }
+}
+
+/// Opens a WebSocket connection to `addr`, giving up if the server doesn't answer in time. The
+/// returned socket is non-blocking.
+fn open_socket(addr: &str) -> Result<WebSocket<TcpStream>, anyhow::Error> {
+    let socket_addr = addr.to_socket_addrs()?
+        .next()
+        .ok_or_else(|| { anyhow::anyhow!("{} has no address", addr) })?;
+    let stream = TcpStream::connect_timeout(&socket_addr, CONNECT_TIMEOUT)?;
+    stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+
+    let (socket, _) = tungstenite::client(format!("ws://{}/", addr), stream)
+        .map_err(|e| {
+            match e {
+                tungstenite::HandshakeError::Failure(e) => e,
+                // A read or write timed out part way through.
+                tungstenite::HandshakeError::Interrupted(_) => Error::Io(ErrorKind::TimedOut.into()),
+            }
+        })?;
+    socket.get_ref().set_read_timeout(None)?;
+    socket.get_ref().set_write_timeout(None)?;
+    socket.get_ref().set_nonblocking(true)?;
+
+    Ok(socket)
+}
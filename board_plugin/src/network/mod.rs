@@ -0,0 +1,9 @@
+pub use authority::*;
+pub use client::*;
+pub use protocol::*;
+pub use server::*;
+
+mod authority;
+mod client;
+mod protocol;
+mod server;
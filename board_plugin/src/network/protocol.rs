@@ -0,0 +1,99 @@
+use crate::resources::{Coordinates, Tile, TileKind, TileMap};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tungstenite::Message;
+
+pub const DEFAULT_PORT: u16 = 9001;
+
+/// One tile of the board, as it's sent over the network.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TileState {
+    pub x: i32,
+    pub y: i32,
+    pub lightness: u8,
+    pub kind: TileKind,
+}
+
+impl TileState {
+    pub fn new(coordinates: Coordinates, tile: &Tile) -> Self {
+        Self {
+            x: coordinates.x,
+            y: coordinates.y,
+            lightness: tile.lightness,
+            kind: tile.kind,
+        }
+    }
+
+    pub fn coordinates(&self) -> Coordinates {
+        (self.x, self.y).into()
+    }
+
+    pub fn tile(&self) -> Tile {
+        Tile::new(self.lightness, self.kind)
+    }
+}
+
+/// Sent by clients to the server.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// The first message on every connection. Reconnecting clients send the session they were
+    /// given, to carry on as the same player.
+    Hello { session: Option<u64> },
+    /// Asks for a tile to be triggered, as the board was at `tick` when the player clicked.
+    Trigger { tick: u64, x: i32, y: i32 },
+}
+
+/// Sent by the server to clients.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Answers `Hello`. The session can be used to reconnect as the same player.
+    Welcome { session: u64, player: u32 },
+    /// The whole board, sent after `Welcome` and whenever a new game starts.
+    Snapshot { tick: u64, score: u64, tiles: Vec<TileState> },
+    /// The tiles that changed during the last tick. `tick` is the tick the board is now at.
+    Delta { tick: u64, score: u64, tiles: Vec<TileState> },
+    /// The board went dark. A `Snapshot` of the next game follows.
+    GameOver { tick: u64, score: u64 },
+}
+
+/// Every tile on the board, sorted so the same board always encodes the same way.
+pub fn encode_tiles(tile_map: &TileMap) -> Vec<TileState> {
+    let mut tiles: Vec<TileState> = tile_map.iter()
+        .map(|(coordinates, tile)| { TileState::new(*coordinates, tile) })
+        .collect();
+    tiles.sort_by_key(|tile| { tile.coordinates() });
+
+    tiles
+}
+
+/// The tiles of `current` that are new or different since `previous`. Tiles are never removed
+/// from a board, so this is enough to bring `previous` up to date.
+pub fn diff_tiles(previous: &TileMap, current: &TileMap) -> Vec<TileState> {
+    let mut tiles: Vec<TileState> = current.iter()
+        .filter(|(coordinates, tile)| { previous.get(coordinates) != Some(tile) })
+        .map(|(coordinates, tile)| { TileState::new(*coordinates, tile) })
+        .collect();
+    tiles.sort_by_key(|tile| { tile.coordinates() });
+
+    tiles
+}
+
+pub fn apply_tiles(tile_map: &mut TileMap, tiles: &[TileState]) {
+    for tile in tiles {
+        tile_map.insert(tile.coordinates(), tile.tile());
+    }
+}
+
+pub fn encode<T: Serialize>(value: &T) -> Message {
+    Message::Text(serde_json::to_string(value).expect("Network messages always serialize"))
+}
+
+/// Returns `None` for messages that aren't text, or don't parse as a `T`.
+pub fn decode<T: DeserializeOwned>(message: &Message) -> Option<T> {
+    match message {
+        Message::Text(text) => serde_json::from_str(text).ok(),
+        _ => None,
+    }
+}
@@ -0,0 +1,319 @@
+use crate::network::{decode, diff_tiles, encode, encode_tiles, Authority, ClientMessage, ServerMessage, DEFAULT_MAX_REWIND_TICKS};
+use crate::resources::TileMap;
+use bevy::log;
+use bevy::utils::HashMap;
+use std::io::{self, ErrorKind};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::error::Error as StdError;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tungstenite::{Error, Message, WebSocket};
+
+/// How long a new connection has to finish the WebSocket handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ServerConfig {
+    pub tick_interval: Duration,
+    pub max_rewind_ticks: u64,
+    /// Stops ticking, but keeps serving, once the game reaches this tick.
+    pub max_ticks: Option<u64>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            tick_interval: Duration::from_millis(100),
+            max_rewind_ticks: DEFAULT_MAX_REWIND_TICKS,
+            max_ticks: None,
+        }
+    }
+}
+
+/// An authoritative game server. It plays a shared endless game, applies the triggers clients
+/// send, and sends every client the tiles that changed each tick. When the board goes dark a new
+/// game starts straight away.
+pub struct GameServer {
+    listener: TcpListener,
+    config: ServerConfig,
+    authority: Authority,
+    /// The board as clients last saw it.
+    sent_tile_map: TileMap,
+    connections: Vec<Connection>,
+    /// Connections whose handshakes have finished on their own threads, waiting to be added.
+    handshakes: Receiver<(WebSocket<TcpStream>, SocketAddr)>,
+    handshake_sender: Sender<(WebSocket<TcpStream>, SocketAddr)>,
+    /// The player each session belongs to, kept after disconnecting so players can come back.
+    sessions: HashMap<u64, u32>,
+    next_player: u32,
+}
+
+struct Connection {
+    socket: WebSocket<TcpStream>,
+    /// `None` until the client says hello.
+    session: Option<u64>,
+    is_open: bool,
+}
+
+/// A server running on its own thread.
+pub struct ServerHandle {
+    pub local_addr: SocketAddr,
+    is_stopped: Arc<AtomicBool>,
+    thread: JoinHandle<io::Result<()>>,
+}
+
+impl GameServer {
+    pub fn bind(addr: impl ToSocketAddrs, config: ServerConfig) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+
+        let tile_map = TileMap::new();
+        let (handshake_sender, handshakes) = mpsc::channel();
+
+        Ok(Self {
+            listener,
+            config,
+            authority: Authority::new(tile_map.clone(), config.max_rewind_ticks),
+            sent_tile_map: tile_map,
+            connections: Vec::new(),
+            handshakes,
+            handshake_sender,
+            sessions: HashMap::default(),
+            next_player: 0,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serves clients until `is_stopped` is set.
+    pub fn run(&mut self, is_stopped: &AtomicBool) -> io::Result<()> {
+        let mut next_tick = Instant::now() + self.config.tick_interval;
+
+        while !is_stopped.load(Ordering::Relaxed) {
+            self.accept_connections()?;
+            self.add_connections();
+            self.read_messages();
+
+            if Instant::now() >= next_tick {
+                next_tick += self.config.tick_interval;
+                self.step();
+            }
+
+            for connection in self.connections.iter_mut() {
+                connection.flush();
+            }
+            self.connections.retain(|connection| { connection.is_open });
+
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        Ok(())
+    }
+
+    /// Runs the server on a new thread.
+    pub fn spawn(mut self) -> io::Result<ServerHandle> {
+        let local_addr = self.local_addr()?;
+        let is_stopped = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let is_stopped = is_stopped.clone();
+            thread::spawn(move || { self.run(&is_stopped) })
+        };
+
+        Ok(ServerHandle {
+            local_addr,
+            is_stopped,
+            thread,
+        })
+    }
+
+    fn accept_connections(&mut self) -> io::Result<()> {
+        loop {
+            let (stream, addr) = match self.listener.accept() {
+                Ok(connection) => connection,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(()),
+                Err(e) => return Err(e),
+            };
+
+            // The handshake blocks, so it's done on its own thread rather than holding up the ticks
+            // of everyone already playing.
+            let handshake_sender = self.handshake_sender.clone();
+            thread::spawn(move || {
+                match handshake(stream) {
+                    // The server may have stopped in the meantime, and then nobody's waiting for it.
+                    Ok(socket) => {
+                        let _ = handshake_sender.send((socket, addr));
+                    },
+                    Err(e) => log::warn!("Handshake with {} failed: {}", addr, e),
+                }
+            });
+        }
+    }
+
+    fn add_connections(&mut self) {
+        for (socket, addr) in self.handshakes.try_iter() {
+            log::info!("Accepted connection from {}", addr);
+            self.connections.push(Connection {
+                socket,
+                session: None,
+                is_open: true,
+            });
+        }
+    }
+
+    fn read_messages(&mut self) {
+        for i in 0..self.connections.len() {
+            while let Some(message) = self.connections[i].read() {
+                match message {
+                    ClientMessage::Hello { session } => self.welcome(i, session),
+                    ClientMessage::Trigger { tick, x, y } => {
+                        if self.connections[i].session.is_some() {
+                            self.authority.trigger(tick, (x, y).into());
+                        }
+                    },
+                }
+            }
+        }
+    }
+
+    fn welcome(&mut self, i: usize, session: Option<u64>) {
+        let (session, player) = match session.and_then(|session| { Some((session, *self.sessions.get(&session)?)) }) {
+            Some((session, player)) => {
+                log::info!("Player {} reconnected", player);
+                (session, player)
+            },
+            None => {
+                let session = rand::random();
+                let player = self.next_player;
+                self.next_player += 1;
+                self.sessions.insert(session, player);
+
+                log::info!("Player {} joined", player);
+                (session, player)
+            },
+        };
+
+        // The client is brought up to date with the board as everyone else last saw it. Triggers
+        // since then arrive in the next delta, like they do for everyone.
+        let connection = &mut self.connections[i];
+        connection.session = Some(session);
+        connection.send(ServerMessage::Welcome { session, player });
+        connection.send(ServerMessage::Snapshot {
+            tick: self.authority.tick(),
+            score: self.authority.score(),
+            tiles: encode_tiles(&self.sent_tile_map),
+        });
+    }
+
+    fn step(&mut self) {
+        if self.config.max_ticks.is_some_and(|max_ticks| { self.authority.tick() >= max_ticks }) {
+            return;
+        }
+
+        self.authority.step();
+
+        let tiles = diff_tiles(&self.sent_tile_map, self.authority.tile_map());
+        self.sent_tile_map = self.authority.tile_map().clone();
+        self.broadcast(ServerMessage::Delta {
+            tick: self.authority.tick(),
+            score: self.authority.score(),
+            tiles,
+        });
+
+        if self.authority.is_over() {
+            log::info!("Game over! Score: {}", self.authority.score());
+            self.broadcast(ServerMessage::GameOver {
+                tick: self.authority.tick(),
+                score: self.authority.score(),
+            });
+
+            let tile_map = TileMap::new();
+            self.authority = Authority::new(tile_map.clone(), self.config.max_rewind_ticks);
+            self.sent_tile_map = tile_map;
+            self.broadcast(ServerMessage::Snapshot {
+                tick: 0,
+                score: 0,
+                tiles: encode_tiles(&self.sent_tile_map),
+            });
+        }
+    }
+
+    fn broadcast(&mut self, message: ServerMessage) {
+        for connection in self.connections.iter_mut() {
+            if connection.session.is_some() {
+                connection.send(message.clone());
+            }
+        }
+    }
+}
+
+impl Connection {
+    /// Returns the next message waiting, if there is one.
+    fn read(&mut self) -> Option<ClientMessage> {
+        while self.is_open {
+            match self.socket.read() {
+                Ok(message @ Message::Text(_)) => match decode(&message) {
+                    Some(message) => return Some(message),
+                    None => log::warn!("Ignoring malformed message: {}", message),
+                },
+                Ok(_) => {},
+                Err(Error::Io(e)) if e.kind() == ErrorKind::WouldBlock => return None,
+                Err(e) => self.close(e),
+            }
+        }
+
+        None
+    }
+
+    fn send(&mut self, message: ServerMessage) {
+        // Messages that can't be written yet stay queued until the next flush.
+        match self.socket.send(encode(&message)) {
+            Ok(_) => {},
+            Err(Error::Io(e)) if e.kind() == ErrorKind::WouldBlock => {},
+            Err(e) => self.close(e),
+        }
+    }
+
+    fn flush(&mut self) {
+        match self.socket.flush() {
+            Ok(_) => {},
+            Err(Error::Io(e)) if e.kind() == ErrorKind::WouldBlock => {},
+            Err(e) => self.close(e),
+        }
+    }
+
+    fn close(&mut self, error: Error) {
+        match error {
+            Error::ConnectionClosed | Error::AlreadyClosed => log::info!("Connection closed"),
+            e => log::warn!("Connection lost: {}", e),
+        }
+
+        self.is_open = false;
+    }
+}
+
+impl ServerHandle {
+    /// Stops the server and waits for it to finish.
+    pub fn stop(self) -> io::Result<()> {
+        self.is_stopped.store(true, Ordering::Relaxed);
+        self.thread.join().expect("Server thread panicked")
+    }
+}
+
+/// Completes the WebSocket handshake on `stream`, giving up after `HANDSHAKE_TIMEOUT`, and leaves
+/// the socket non-blocking for the server loop.
+fn handshake(stream: TcpStream) -> Result<WebSocket<TcpStream>, Box<dyn StdError>> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+
+    let socket = tungstenite::accept(stream).map_err(|e| { e.to_string() })?;
+    socket.get_ref().set_read_timeout(None)?;
+    socket.get_ref().set_nonblocking(true)?;
+
+    Ok(socket)
+}
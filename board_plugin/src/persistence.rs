@@ -0,0 +1,46 @@
+use bevy::log;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+const SAVE_DIRECTORY: &str = "save";
+
+/// Loads `<name>.ron` from the save directory, falling back to the default value if the file is
+/// missing or can't be read.
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> T {
+    let path = get_save_path(name);
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(_) => return T::default(),
+    };
+
+    match ron::from_str(&contents) {
+        Ok(value) => value,
+        Err(e) => {
+            log::error!("Can't read save file {}: {}", path.display(), e);
+            T::default()
+        }
+    }
+}
+
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    let path = get_save_path(name);
+
+    let contents = match ron::ser::to_string_pretty(value, Default::default()) {
+        Ok(contents) => contents,
+        Err(e) => {
+            log::error!("Can't serialize save file {}: {}", path.display(), e);
+            return;
+        }
+    };
+
+    if let Err(e) = fs::create_dir_all(SAVE_DIRECTORY).and_then(|_| fs::write(&path, contents)) {
+        log::error!("Can't write save file {}: {}", path.display(), e);
+    }
+}
+
+fn get_save_path(name: &str) -> PathBuf {
+    PathBuf::from(SAVE_DIRECTORY).join(format!("{}.ron", name))
+}
@@ -0,0 +1,88 @@
+use crate::resources::{GameStats, Locale, TileMap};
+use crate::utils::format_duration;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+const SURVIVAL_SECONDS: f64 = 5. * 60.;
+const SCORE: u64 = 1_000_000;
+const LIT_TILES: usize = 100;
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    Survivor,
+    Millionaire,
+    Floodlight,
+    ClosedLoop,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 4] = [
+        Achievement::Survivor,
+        Achievement::Millionaire,
+        Achievement::Floodlight,
+        Achievement::ClosedLoop,
+    ];
+
+    /// The id of the achievement's title in the locale files.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Achievement::Survivor => "achievement-survivor",
+            Achievement::Millionaire => "achievement-millionaire",
+            Achievement::Floodlight => "achievement-floodlight",
+            Achievement::ClosedLoop => "achievement-closed-loop",
+        }
+    }
+
+    pub fn description_key(&self) -> &'static str {
+        match self {
+            Achievement::Survivor => "achievement-survivor-description",
+            Achievement::Millionaire => "achievement-millionaire-description",
+            Achievement::Floodlight => "achievement-floodlight-description",
+            Achievement::ClosedLoop => "achievement-closed-loop-description",
+        }
+    }
+
+    pub fn title(&self, locale: &Locale) -> String {
+        locale.get(self.key())
+    }
+
+    pub fn description(&self, locale: &Locale) -> String {
+        let args = match self {
+            Achievement::Survivor => vec![("time", format_duration(SURVIVAL_SECONDS))],
+            Achievement::Millionaire => vec![("score", locale.format_number(SCORE))],
+            Achievement::Floodlight => vec![("tiles", locale.format_number(LIT_TILES as u64))],
+            Achievement::ClosedLoop => vec![],
+        };
+
+        locale.format(self.description_key(), &args)
+    }
+
+    /// Whether the game so far has earned this achievement.
+    pub fn is_met(&self, game_stats: &GameStats, tile_map: &TileMap) -> bool {
+        match self {
+            Achievement::Survivor => game_stats.survival_time >= SURVIVAL_SECONDS,
+            Achievement::Millionaire => game_stats.final_score >= SCORE,
+            Achievement::Floodlight => game_stats.peak_lit_tiles >= LIT_TILES,
+            Achievement::ClosedLoop => tile_map.has_closed_loop(),
+        }
+    }
+}
+
+/// The achievements unlocked so far, kept between sessions.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AchievementProgress {
+    pub unlocked: BTreeSet<Achievement>,
+}
+
+impl AchievementProgress {
+    pub const SAVE_NAME: &'static str = "achievements";
+
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+
+    /// Returns `true` if the achievement wasn't already unlocked.
+    pub fn unlock(&mut self, achievement: Achievement) -> bool {
+        self.unlocked.insert(achievement)
+    }
+}
@@ -0,0 +1,306 @@
+use crate::resources::{Coordinates, Tile, TileMap, MAX_LIGHTNESS};
+use rand::Rng;
+
+pub const CLASSIC_SIZE: usize = 5;
+
+const ORTHOGONAL_OFFSETS: [(i32, i32); 4] = [
+    (0, -1),
+    (-1, 0),
+    (1, 0),
+    (0, 1),
+];
+
+/// Solutions are only searched exhaustively when there are at most this many free variables.
+const MAX_FREE_VARIABLES: usize = 16;
+
+/// The state of a classic Lights Out game, played on a finite `size` x `size` grid where pressing a
+/// tile toggles it and its orthogonal neighbours.
+#[derive(Debug, Clone)]
+pub struct ClassicPuzzle {
+    pub size: usize,
+    pub moves: u32,
+    pub minimum_moves: u32,
+    pub is_solved: bool,
+}
+
+impl ClassicPuzzle {
+    pub fn new(size: usize, tile_map: &TileMap) -> Self {
+        let minimum_moves = Self::solve(tile_map, size)
+            .map_or(0, |solution| { solution.len() as u32 });
+
+        Self {
+            size,
+            moves: 0,
+            minimum_moves,
+            is_solved: false,
+        }
+    }
+
+    /// Creates a random puzzle by pressing random tiles of a dark board, so it's always solvable.
+    pub fn generate<R: Rng>(size: usize, rng: &mut R) -> TileMap {
+        // An empty board is always solved, so no amount of pressing would make a puzzle of it.
+        assert!(size > 0, "Classic boards need at least one tile");
+
+        let mut tile_map = TileMap::from_tiles(
+            (0..size * size).map(|i| { (index_to_coordinates(i, size), Tile::new_black()) })
+        );
+
+        while Self::is_solved(&tile_map) {
+            for i in 0..size * size {
+                if rng.gen_bool(0.5) {
+                    Self::toggle(&mut tile_map, index_to_coordinates(i, size));
+                }
+            }
+        }
+
+        tile_map
+    }
+
+    /// Toggles the tile at `coordinates` and its orthogonal neighbours. Returns `false` if there is no
+    /// tile at `coordinates`.
+    pub fn toggle(tile_map: &mut TileMap, coordinates: Coordinates) -> bool {
+        if !tile_map.contains_key(&coordinates) {
+            return false;
+        }
+
+        let targets = ORTHOGONAL_OFFSETS.iter()
+            .map(|offset| { coordinates + (*offset).into() })
+            .chain([coordinates]);
+
+        for target in targets {
+            if let Some(tile) = tile_map.get_mut(&target) {
+                tile.lightness = if tile.is_black() { MAX_LIGHTNESS } else { 0 };
+            }
+        }
+
+        true
+    }
+
+    pub fn is_solved(tile_map: &TileMap) -> bool {
+        tile_map.non_black_tiles().is_empty()
+    }
+
+    /// Finds the shortest sequence of presses that turns every tile off, by solving the system of
+    /// linear equations over GF(2) that describes the board. Returns `None` if there is no solution.
+    pub fn solve(tile_map: &TileMap, size: usize) -> Option<Vec<Coordinates>> {
+        let n = size * size;
+
+        // Each row is the equation for one tile: the sum of the presses that toggle it, plus its
+        // current state, must be zero.
+        let mut matrix: Vec<Vec<bool>> = (0..n)
+            .map(|i| {
+                let coordinates = index_to_coordinates(i, size);
+                let mut row = vec![false; n + 1];
+                row[i] = true;
+                for offset in ORTHOGONAL_OFFSETS {
+                    if let Some(j) = coordinates_to_index(coordinates + offset.into(), size) {
+                        row[j] = true;
+                    }
+                }
+                row[n] = tile_map.get(&coordinates).is_some_and(|tile| { tile.is_not_black() });
+                row
+            })
+            .collect();
+
+        let mut pivot_columns = vec![];
+        let mut pivot_row = 0;
+        for column in 0..n {
+            let found = (pivot_row..n).find(|&row| { matrix[row][column] });
+            let row = match found {
+                Some(row) => row,
+                None => continue,
+            };
+
+            matrix.swap(pivot_row, row);
+            for other in 0..n {
+                if other != pivot_row && matrix[other][column] {
+                    let pivot = matrix[pivot_row].clone();
+                    for (value, pivot_value) in matrix[other].iter_mut().zip(pivot) {
+                        *value ^= pivot_value;
+                    }
+                }
+            }
+
+            pivot_columns.push(column);
+            pivot_row += 1;
+        }
+
+        if matrix[pivot_row..].iter().any(|row| { row[n] }) {
+            return None;
+        }
+
+        let free_columns: Vec<usize> = (0..n)
+            .filter(|column| { !pivot_columns.contains(column) })
+            .collect();
+
+        // With every free variable fixed, each pivot variable is determined by its row.
+        let solve_for = |free_values: &[bool]| -> Vec<bool> {
+            let mut solution = vec![false; n];
+            for (column, value) in free_columns.iter().zip(free_values) {
+                solution[*column] = *value;
+            }
+            for (row, column) in pivot_columns.iter().enumerate() {
+                solution[*column] = free_columns.iter()
+                    .zip(free_values)
+                    .fold(matrix[row][n], |acc, (free_column, value)| { acc ^ (matrix[row][*free_column] && *value) });
+            }
+            solution
+        };
+
+        let combinations = if free_columns.len() <= MAX_FREE_VARIABLES {
+            1usize << free_columns.len()
+        } else {
+            1
+        };
+
+        (0..combinations)
+            .map(|bits| {
+                let free_values: Vec<bool> = (0..free_columns.len())
+                    .map(|i| { bits & (1 << i) != 0 })
+                    .collect();
+                solve_for(&free_values)
+            })
+            .min_by_key(|solution| { solution.iter().filter(|pressed| { **pressed }).count() })
+            .map(|solution| {
+                solution.into_iter()
+                    .enumerate()
+                    .filter(|(_, pressed)| { *pressed })
+                    .map(|(i, _)| { index_to_coordinates(i, size) })
+                    .collect()
+            })
+    }
+}
+
+fn index_to_coordinates(i: usize, size: usize) -> Coordinates {
+    let offset = (size / 2) as i32;
+
+    ((i % size) as i32 - offset, (i / size) as i32 - offset).into()
+}
+
+fn coordinates_to_index(coordinates: Coordinates, size: usize) -> Option<usize> {
+    let offset = (size / 2) as i32;
+    let (x, y) = (coordinates.x + offset, coordinates.y + offset);
+
+    if x < 0 || y < 0 || x >= size as i32 || y >= size as i32 {
+        return None;
+    }
+
+    Some(y as usize * size + x as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// A dark board with the tiles at `lit` turned on, in grid positions from the bottom left.
+    fn board(size: usize, lit: &[(usize, usize)]) -> TileMap {
+        let mut tile_map = TileMap::from_tiles(
+            (0..size * size).map(|i| { (index_to_coordinates(i, size), Tile::new_black()) })
+        );
+        for (x, y) in lit {
+            tile_map.get_mut(&index_to_coordinates(y * size + x, size)).unwrap().lightness = MAX_LIGHTNESS;
+        }
+
+        tile_map
+    }
+
+    /// Every solution, found by chasing the lights down from each choice of presses on the first
+    /// row. It's slow, but doesn't share anything with `solve`.
+    fn all_solutions(tile_map: &TileMap, size: usize) -> Vec<Vec<Coordinates>> {
+        (0..1usize << size)
+            .filter_map(|first_row| {
+                let mut tile_map = tile_map.clone();
+                let mut presses = vec![];
+                let mut press = |tile_map: &mut TileMap, i| {
+                    let coordinates = index_to_coordinates(i, size);
+                    ClassicPuzzle::toggle(tile_map, coordinates);
+                    presses.push(coordinates);
+                };
+
+                for x in (0..size).filter(|x| { first_row & (1 << x) != 0 }) {
+                    press(&mut tile_map, x);
+                }
+                for y in 1..size {
+                    for x in 0..size {
+                        let above = index_to_coordinates((y - 1) * size + x, size);
+                        if tile_map[&above].is_not_black() {
+                            press(&mut tile_map, y * size + x);
+                        }
+                    }
+                }
+
+                ClassicPuzzle::is_solved(&tile_map).then_some(presses)
+            })
+            .collect()
+    }
+
+    fn apply(tile_map: &mut TileMap, presses: &[Coordinates]) {
+        for coordinates in presses {
+            assert!(ClassicPuzzle::toggle(tile_map, *coordinates));
+        }
+    }
+
+    #[test]
+    fn solutions_clear_generated_boards() {
+        for seed in 0..50 {
+            let mut tile_map = ClassicPuzzle::generate(CLASSIC_SIZE, &mut StdRng::seed_from_u64(seed));
+            assert!(!ClassicPuzzle::is_solved(&tile_map));
+
+            let solution = ClassicPuzzle::solve(&tile_map, CLASSIC_SIZE).unwrap();
+            apply(&mut tile_map, &solution);
+            assert!(ClassicPuzzle::is_solved(&tile_map), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn solutions_are_minimal() {
+        // 5 x 5 boards have four solutions each, since two patterns of presses change nothing.
+        let center = board(CLASSIC_SIZE, &[(2, 2)]);
+        let boards = (0..20)
+            .map(|seed| { ClassicPuzzle::generate(CLASSIC_SIZE, &mut StdRng::seed_from_u64(seed)) })
+            .chain([center]);
+
+        for tile_map in boards {
+            let solutions = all_solutions(&tile_map, CLASSIC_SIZE);
+            assert_eq!(solutions.len(), 4);
+
+            let shortest = solutions.iter().map(|solution| { solution.len() }).min().unwrap();
+            assert_eq!(ClassicPuzzle::solve(&tile_map, CLASSIC_SIZE).unwrap().len(), shortest);
+        }
+    }
+
+    #[test]
+    fn single_lit_corner_has_no_solution() {
+        let tile_map = board(CLASSIC_SIZE, &[(0, 0)]);
+
+        assert!(all_solutions(&tile_map, CLASSIC_SIZE).is_empty());
+        assert_eq!(ClassicPuzzle::solve(&tile_map, CLASSIC_SIZE), None);
+        assert_eq!(ClassicPuzzle::new(CLASSIC_SIZE, &tile_map).minimum_moves, 0);
+    }
+
+    #[test]
+    fn solved_board_needs_no_presses() {
+        assert_eq!(ClassicPuzzle::solve(&board(CLASSIC_SIZE, &[]), CLASSIC_SIZE), Some(vec![]));
+    }
+
+    #[test]
+    fn index_and_coordinates_round_trip() {
+        for size in [1, 2, 5, 6] {
+            for i in 0..size * size {
+                assert_eq!(coordinates_to_index(index_to_coordinates(i, size), size), Some(i));
+            }
+
+            let offset = (size / 2) as i32;
+            assert_eq!(coordinates_to_index((-offset - 1, 0).into(), size), None);
+            assert_eq!(coordinates_to_index((0, size as i32 - offset).into(), size), None);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn generating_an_empty_board_panics() {
+        ClassicPuzzle::generate(0, &mut StdRng::seed_from_u64(0));
+    }
+}
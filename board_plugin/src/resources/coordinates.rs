@@ -10,6 +10,14 @@ pub struct Coordinates {
 
 impl Coordinates {
     pub const ZERO: Self = Coordinates { x: 0, y: 0 };
+
+    /// The smallest and largest of `coordinates` on each axis, or `None` if there are none.
+    pub fn bounds<'a>(coordinates: impl IntoIterator<Item = &'a Coordinates>) -> Option<(Self, Self)> {
+        coordinates.into_iter().fold(None, |bounds, c| {
+            let (min, max) = bounds.unwrap_or((*c, *c));
+            Some(((min.x.min(c.x), min.y.min(c.y)).into(), (max.x.max(c.x), max.y.max(c.y)).into()))
+        })
+    }
 }
 
 impl Add for Coordinates {
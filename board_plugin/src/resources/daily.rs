@@ -0,0 +1,317 @@
+use crate::resources::{Coordinates, Tile, TileKind, TileMap};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A day of the Gregorian calendar.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Today's date in UTC, so every player gets the same challenge on the same day.
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| { duration.as_secs() });
+
+        Self::from_days((seconds / SECONDS_PER_DAY) as i64)
+    }
+
+    /// Converts days since 1970-01-01 to a date.
+    pub fn from_days(days: i64) -> Self {
+        // See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Self {
+            year: year as i32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    /// Converts the date to days since 1970-01-01.
+    pub fn to_days(&self) -> i64 {
+        // See http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let shifted_month = (self.month as i64 + 9) % 12;
+        let day_of_year = (153 * shifted_month + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// The day of the week, from 0 for Monday to 6 for Sunday.
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday.
+        (self.to_days() + 3).rem_euclid(7) as u32
+    }
+
+    pub fn first_of_month(&self) -> Self {
+        Self {
+            day: 1,
+            ..*self
+        }
+    }
+
+    pub fn days_in_month(&self) -> u32 {
+        match self.month {
+            2 if self.is_leap_year() => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    fn is_leap_year(&self) -> bool {
+        (self.year % 4 == 0 && self.year % 100 != 0) || self.year % 400 == 0
+    }
+}
+
+/// The id of a month's name in the locale files, from 1 for January.
+pub fn month_key(month: u32) -> &'static str {
+    const MONTH_KEYS: [&str; 12] = [
+        "month-january", "month-february", "month-march", "month-april", "month-may", "month-june",
+        "month-july", "month-august", "month-september", "month-october", "month-november", "month-december",
+    ];
+
+    MONTH_KEYS[(month as usize + 11) % 12]
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The twist on the rules for a day's challenge.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum DailyVariant {
+    /// An ordinary starting board.
+    Standard,
+    /// Walls are scattered around the starting board.
+    Walled,
+    /// Islands of gray tiles are scattered around the starting board.
+    Scattered,
+    /// The starting board is much darker.
+    Dim,
+}
+
+impl DailyVariant {
+    pub const ALL: [DailyVariant; 4] = [
+        DailyVariant::Standard,
+        DailyVariant::Walled,
+        DailyVariant::Scattered,
+        DailyVariant::Dim,
+    ];
+
+    /// The id of the variant's name in the locale files.
+    pub fn key(&self) -> &'static str {
+        match self {
+            DailyVariant::Standard => "daily-variant-standard",
+            DailyVariant::Walled => "daily-variant-walled",
+            DailyVariant::Scattered => "daily-variant-scattered",
+            DailyVariant::Dim => "daily-variant-dim",
+        }
+    }
+}
+
+/// The challenge for one day. Its seed and variant only depend on the date.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DailyChallenge {
+    pub date: Date,
+    pub seed: u64,
+    pub variant: DailyVariant,
+    /// Whether this attempt counts. Only the first attempt each day does.
+    pub is_scored: bool,
+}
+
+impl DailyChallenge {
+    pub fn new(date: Date, is_scored: bool) -> Self {
+        let seed = mix(date.to_days() as u64);
+        let variant = DailyVariant::ALL[(seed >> 32) as usize % DailyVariant::ALL.len()];
+
+        Self {
+            date,
+            seed,
+            variant,
+            is_scored,
+        }
+    }
+
+    pub fn generate(&self) -> TileMap {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut tile_map = TileMap::generate(&mut rng);
+
+        match self.variant {
+            DailyVariant::Standard => {},
+            DailyVariant::Walled => {
+                for _ in 0..8 {
+                    let coordinates = random_coordinates(&mut rng, 2, 4);
+                    tile_map.insert(coordinates, Tile::new(0, TileKind::Wall));
+                }
+            },
+            DailyVariant::Scattered => {
+                for _ in 0..3 {
+                    let center = random_coordinates(&mut rng, 4, 6);
+                    for coordinates in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                        let lightness = rng.gen_range(1..=u8::MAX / 2);
+                        tile_map.insert(center + coordinates.into(), Tile::new_gray(lightness));
+                    }
+                }
+            },
+            DailyVariant::Dim => {
+                for tile in tile_map.values_mut() {
+                    if !tile.is_white() {
+                        tile.lightness = tile.lightness / 4 + 1;
+                    }
+                }
+            },
+        }
+
+        tile_map
+    }
+}
+
+/// The results of past daily challenges, keyed by date, kept between sessions.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DailyResults {
+    pub results: BTreeMap<String, DailyResult>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub variant: DailyVariant,
+    pub score: u64,
+    /// `false` if the attempt was abandoned before the game ended.
+    pub is_finished: bool,
+}
+
+impl DailyResults {
+    pub const SAVE_NAME: &'static str = "daily_results";
+
+    pub fn get(&self, date: Date) -> Option<&DailyResult> {
+        self.results.get(&date.to_string())
+    }
+
+    pub fn has_attempted(&self, date: Date) -> bool {
+        self.get(date).is_some()
+    }
+
+    /// Records the start of the scored attempt, so leaving the game early still uses it up.
+    pub fn start(&mut self, challenge: &DailyChallenge) {
+        self.results.insert(challenge.date.to_string(), DailyResult {
+            variant: challenge.variant,
+            score: 0,
+            is_finished: false,
+        });
+    }
+
+    pub fn finish(&mut self, date: Date, score: u64) {
+        if let Some(result) = self.results.get_mut(&date.to_string()) {
+            result.score = score;
+            result.is_finished = true;
+        }
+    }
+}
+
+/// Spreads the bits of consecutive days, so neighbouring days get unrelated seeds.
+fn mix(value: u64) -> u64 {
+    // The finalizer of SplitMix64.
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Picks coordinates at least `min` and at most `max` tiles from the centre along either axis.
+fn random_coordinates<R: Rng>(rng: &mut R, min: i32, max: i32) -> Coordinates {
+    loop {
+        let coordinates: Coordinates = (rng.gen_range(-max..=max), rng.gen_range(-max..=max)).into();
+        if coordinates.x.abs().max(coordinates.y.abs()) >= min {
+            return coordinates;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Date {
+        Date { year, month, day }
+    }
+
+    #[test]
+    fn days_round_trip() {
+        let mut previous = Date::from_days(-200_001);
+        for days in -200_000..200_000 {
+            let date = Date::from_days(days);
+            assert_eq!(date.to_days(), days);
+            assert!(date > previous);
+            assert!((1..=12).contains(&date.month));
+            assert!((1..=date.days_in_month()).contains(&date.day));
+
+            previous = date;
+        }
+    }
+
+    #[test]
+    fn epoch_is_a_thursday() {
+        assert_eq!(Date::from_days(0), date(1970, 1, 1));
+        assert_eq!(date(1970, 1, 1).weekday(), 3);
+        assert_eq!(date(1970, 1, 5).weekday(), 0);
+    }
+
+    #[test]
+    fn leap_days_and_century_years() {
+        assert_eq!(date(2000, 2, 29).to_days(), 11_016);
+        assert_eq!(Date::from_days(11_016), date(2000, 2, 29));
+        assert_eq!(Date::from_days(11_017), date(2000, 3, 1));
+        assert_eq!(date(2000, 2, 29).weekday(), 1);
+
+        // 2100 isn't a leap year, so March follows the 28th of February.
+        assert_eq!(date(2100, 3, 1).to_days(), 47_541);
+        assert_eq!(Date::from_days(47_540), date(2100, 2, 28));
+        assert_eq!(date(2100, 3, 1).weekday(), 0);
+        assert_eq!(date(2100, 2, 1).days_in_month(), 28);
+    }
+
+    #[test]
+    fn days_before_the_epoch() {
+        assert_eq!(Date::from_days(-1), date(1969, 12, 31));
+        assert_eq!(date(1969, 12, 31).weekday(), 2);
+        assert_eq!(Date::from_days(-25_508), date(1900, 3, 1));
+        assert_eq!(date(1900, 3, 1).weekday(), 3);
+    }
+
+    #[test]
+    fn daily_challenge_is_stable_for_a_date() {
+        let challenge = DailyChallenge::new(date(2024, 1, 1), true);
+
+        assert_eq!(challenge.seed, 0x47A4_C66C_FE6D_DE15);
+        assert_eq!(challenge.variant, DailyVariant::Standard);
+        assert_eq!(challenge, DailyChallenge::new(date(2024, 1, 1), true));
+        assert_eq!(challenge.generate(), DailyChallenge::new(date(2024, 1, 1), false).generate());
+
+        assert_ne!(challenge.seed, DailyChallenge::new(date(2024, 1, 2), true).seed);
+    }
+}
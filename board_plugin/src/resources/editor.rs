@@ -0,0 +1,206 @@
+use crate::resources::*;
+
+/// Where the editor saves and loads levels, relative to the assets folder. Each save gets the next free
+/// `custom-<n>.level.ron`, and loading picks the highest `n`.
+pub const CUSTOM_LEVEL_DIRECTORY: &str = "levels/custom";
+
+const HISTORY_CAPACITY: usize = 100;
+const MAX_BRUSH_SIZE: u32 = 7;
+
+/// The state of the level editor, kept while the level is being test-played.
+#[derive(Debug, Clone)]
+pub struct Editor {
+    pub name: String,
+    pub brush: Tile,
+    pub brush_size: u32,
+    pub clicks: u32,
+    pub goal: Goal,
+    pub goal_corner: Coordinates,
+    pub tile_map: TileMap,
+    pub history: TileMapHistory,
+    pub status: String,
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Self {
+            name: "Custom Level".to_string(),
+            brush: Tile::new_gray(MAX_LIGHTNESS / 2),
+            brush_size: 1,
+            clicks: 5,
+            goal: Goal::LightRegion { min: (0, 0), max: (0, 0) },
+            goal_corner: Coordinates::ZERO,
+            tile_map: TileMap::from_tiles([(Coordinates::ZERO, Tile::new_white())]),
+            history: TileMapHistory::new(HISTORY_CAPACITY),
+            status: String::new(),
+        }
+    }
+}
+
+impl Editor {
+    /// Paints every tile under the square brush centred on `center`, or erases them if `brush` is
+    /// `None`. Returns `true` if any tile changed.
+    pub fn paint(tile_map: &mut TileMap, center: Coordinates, brush_size: u32, brush: Option<Tile>) -> bool {
+        let radius = (brush_size / 2) as i32;
+        let mut is_changed = false;
+
+        for x in -radius..=radius {
+            for y in -radius..=radius {
+                let coordinates = center + (x, y).into();
+                is_changed |= match brush {
+                    Some(tile) => tile_map.insert(coordinates, tile) != Some(tile),
+                    None => tile_map.remove(&coordinates).is_some(),
+                };
+            }
+        }
+
+        is_changed
+    }
+
+    pub fn grow_brush(&mut self) {
+        self.brush_size = (self.brush_size + 2).min(MAX_BRUSH_SIZE);
+    }
+
+    pub fn shrink_brush(&mut self) {
+        self.brush_size = self.brush_size.saturating_sub(2).max(1);
+    }
+
+    /// Makes the goal the region between `coordinates` and the previously chosen corner.
+    pub fn set_goal_corner(&mut self, coordinates: Coordinates) {
+        let previous = self.goal_corner;
+        self.goal_corner = coordinates;
+        self.goal = Goal::LightRegion {
+            min: (previous.x.min(coordinates.x), previous.y.min(coordinates.y)),
+            max: (previous.x.max(coordinates.x), previous.y.max(coordinates.y)),
+        };
+    }
+
+    pub fn to_level(&self, tile_map: &TileMap) -> Level {
+        let mut tiles: Vec<LevelTile> = tile_map.iter()
+            .map(|(coordinates, tile)| {
+                LevelTile {
+                    x: coordinates.x,
+                    y: coordinates.y,
+                    lightness: tile.lightness,
+                    kind: tile.kind,
+                }
+            })
+            .collect();
+        tiles.sort_by_key(|tile| { (tile.y, tile.x) });
+
+        Level {
+            name: self.name.clone(),
+            clicks: self.clicks,
+            tiles,
+            goal: self.goal,
+        }
+    }
+
+    pub fn load_level(&mut self, level: &Level) {
+        self.name = level.name.clone();
+        self.clicks = level.clicks;
+        self.goal = level.goal;
+        self.tile_map = level.to_tile_map();
+        self.history.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty() -> TileMap {
+        TileMap::from_tiles([])
+    }
+
+    fn painted(coordinates: &[(i32, i32)], tile: Tile) -> TileMap {
+        TileMap::from_tiles(coordinates.iter().map(|c| { (Coordinates::from(*c), tile) }))
+    }
+
+    #[test]
+    fn brush_paints_a_square_of_its_size() {
+        let brush = Tile::new_white();
+        let mut tile_map = empty();
+
+        assert!(Editor::paint(&mut tile_map, (0, 0).into(), 1, Some(brush)));
+        assert_eq!(tile_map, painted(&[(0, 0)], brush));
+
+        let mut tile_map = empty();
+        assert!(Editor::paint(&mut tile_map, (5, 5).into(), 3, Some(brush)));
+        let square: Vec<(i32, i32)> = (4..=6).flat_map(|x| { (4..=6).map(move |y| { (x, y) }) }).collect();
+        assert_eq!(tile_map, painted(&square, brush));
+
+        // Painting the same tiles again changes nothing.
+        assert!(!Editor::paint(&mut tile_map, (5, 5).into(), 3, Some(brush)));
+    }
+
+    #[test]
+    fn brush_erases_a_square_of_its_size() {
+        let brush = Tile::new_white();
+        let square: Vec<(i32, i32)> = (-2..=2).flat_map(|x| { (-2..=2).map(move |y| { (x, y) }) }).collect();
+        let mut tile_map = painted(&square, brush);
+
+        assert!(Editor::paint(&mut tile_map, (0, 0).into(), 3, None));
+        assert_eq!(tile_map.len(), 25 - 9);
+        assert!(tile_map.get(&(1, 1).into()).is_none());
+        assert!(tile_map.get(&(2, 2).into()).is_some());
+
+        assert!(!Editor::paint(&mut tile_map, (0, 0).into(), 1, None));
+    }
+
+    #[test]
+    fn brush_size_stays_odd_and_in_range() {
+        let mut editor = Editor::default();
+
+        editor.shrink_brush();
+        assert_eq!(editor.brush_size, 1);
+
+        for _ in 0..10 {
+            editor.grow_brush();
+        }
+        assert_eq!(editor.brush_size, MAX_BRUSH_SIZE);
+    }
+
+    #[test]
+    fn goal_corners_make_a_region_in_either_order() {
+        let mut editor = Editor::default();
+
+        editor.set_goal_corner((3, -1).into());
+        assert_eq!(editor.goal, Goal::LightRegion { min: (0, -1), max: (3, 0) });
+
+        editor.set_goal_corner((-2, 4).into());
+        assert_eq!(editor.goal, Goal::LightRegion { min: (-2, -1), max: (3, 4) });
+    }
+
+    #[test]
+    fn levels_round_trip_through_the_editor() {
+        let mut tile_map = empty();
+        Editor::paint(&mut tile_map, (0, 0).into(), 3, Some(Tile::new_gray(MAX_LIGHTNESS / 2)));
+        Editor::paint(&mut tile_map, (2, 0).into(), 1, Some(Tile::new(0, TileKind::Wall)));
+        Editor::paint(&mut tile_map, (-1, -1).into(), 1, Some(Tile::new(MAX_LIGHTNESS, TileKind::Lamp)));
+
+        let mut editor = Editor {
+            name: "Round Trip".to_string(),
+            clicks: 3,
+            ..Default::default()
+        };
+        editor.set_goal_corner((1, 1).into());
+        editor.history.push(&tile_map);
+
+        let level = editor.to_level(&tile_map);
+        assert_eq!(level.validate(), Ok(()));
+        assert_eq!(level.tiles.len(), 10);
+        assert!(level.tiles.windows(2).all(|pair| { (pair[0].y, pair[0].x) < (pair[1].y, pair[1].x) }));
+
+        let mut loaded = Editor::default();
+        loaded.history.push(&empty());
+        loaded.load_level(&level);
+
+        assert_eq!(loaded.name, editor.name);
+        assert_eq!(loaded.clicks, editor.clicks);
+        assert_eq!(loaded.goal, editor.goal);
+        assert_eq!(loaded.tile_map, tile_map);
+        assert_eq!(loaded.history.undo(&loaded.tile_map), None);
+        assert_eq!(loaded.to_level(&loaded.tile_map), level);
+    }
+}
@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+/// How many pixels across the generated effect textures are.
+const EFFECT_TEXTURE_SIZE: u32 = 64;
+
+/// Textures for light effects, drawn in code so they don't depend on the assets folder. They're
+/// all white, so sprites and UI nodes can tint them.
+pub struct EffectTextures {
+    /// A soft disc, brightest in the middle, for halos and sparks.
+    pub glow: Handle<Image>,
+    /// A soft ring, for pulses spreading out from a tile.
+    pub ring: Handle<Image>,
+    /// A triangle pointing right, for arrows towards light out of view.
+    pub arrow: Handle<Image>,
+}
+
+impl EffectTextures {
+    pub fn new(images: &mut Assets<Image>) -> Self {
+        Self {
+            glow: images.add(radial_image(|distance| { (1. - distance).powi(2) })),
+            ring: images.add(radial_image(|distance| { (1. - ((distance - 0.8) / 0.2).abs()).max(0.).powi(2) })),
+            arrow: images.add(arrow_image()),
+        }
+    }
+}
+
+/// A white triangle pointing right, with its tip at the right edge and soft sides.
+fn arrow_image() -> Image {
+    let size = EFFECT_TEXTURE_SIZE;
+    let center = (size as f32 - 1.) / 2.;
+
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            // Across from 0 at the left edge to 1 at the right, and up from -1 to 1.
+            let across = x as f32 / (size as f32 - 1.);
+            let up = (y as f32 - center) / center;
+
+            let inside_sides = ((0.9 - across) - up.abs()) * center;
+            let inside_base = (across - 0.2) * center * 2.;
+            let alpha = inside_sides.clamp(0., 1.) * inside_base.clamp(0., 1.);
+            data.extend_from_slice(&[255, 255, 255, (alpha * 255.).round() as u8]);
+        }
+    }
+
+    effect_image(size, data)
+}
+
+/// A white image whose opacity is `alpha` of the distance from its centre, which is 0 at the
+/// centre and 1 at the middle of each edge.
+fn radial_image(alpha: impl Fn(f32) -> f32) -> Image {
+    let size = EFFECT_TEXTURE_SIZE;
+    let center = (size as f32 - 1.) / 2.;
+
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let distance = Vec2::new(x as f32 - center, y as f32 - center).length() / center;
+            let alpha = alpha(distance.min(1.)).clamp(0., 1.);
+            data.extend_from_slice(&[255, 255, 255, (alpha * 255.).round() as u8]);
+        }
+    }
+
+    effect_image(size, data)
+}
+
+fn effect_image(size: u32, data: Vec<u8>) -> Image {
+    Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
@@ -0,0 +1,12 @@
+use crate::materials::FloorMaterial;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// The shader-lit floor drawn under the board in dynamic lighting mode. It's split into chunks,
+/// which are made as the board grows and only sent to the GPU again when their lights change.
+pub struct Floor {
+    /// The square every chunk is drawn on.
+    pub mesh: Handle<Mesh>,
+    /// Each chunk's entity and material, by its position counted in chunks.
+    pub chunks: HashMap<(i32, i32), (Entity, Handle<FloorMaterial>)>,
+}
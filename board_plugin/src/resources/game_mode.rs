@@ -0,0 +1,44 @@
+/// The rules the next game will be played with.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum GameMode {
+    #[default]
+    Endless,
+    /// Endless rules, but unranked so moves can be undone.
+    Practice,
+    Puzzle(usize),
+    Classic(usize),
+    /// Test-play of the level being edited.
+    Custom,
+    /// Endless rules on a board and variant chosen by the date.
+    Daily,
+    /// Two players share a board, each scoring only their own light.
+    Versus,
+    /// Endless rules on a board shared with everyone on the server.
+    Online,
+    /// A read-only view of a live game or a replay.
+    Spectate,
+}
+
+impl GameMode {
+    /// Whether moves can be undone. Ranked modes never allow it.
+    pub fn allows_undo(&self) -> bool {
+        matches!(self, GameMode::Practice | GameMode::Puzzle(_) | GameMode::Custom)
+    }
+
+    /// Whether games end up in lifetime statistics. Practice games don't, since they can be undone,
+    /// versus games are scored per player, and remote games aren't played here.
+    pub fn records_lifetime_stats(&self) -> bool {
+        !matches!(self, GameMode::Practice | GameMode::Versus) && !self.is_remote()
+    }
+
+    /// Whether the board is run somewhere else, so it shouldn't be lit, faded, or scored here.
+    pub fn is_remote(&self) -> bool {
+        matches!(self, GameMode::Online | GameMode::Spectate)
+    }
+
+    /// Whether the board can grow past the edges of the window, so it needs a minimap. Classic
+    /// boards are a fixed size that always fits.
+    pub fn has_minimap(&self) -> bool {
+        !matches!(self, GameMode::Classic(_))
+    }
+}
@@ -0,0 +1,147 @@
+use crate::resources::{Coordinates, TileKind, TileMap};
+
+/// How many ticks ahead each candidate trigger is simulated when looking for a hint.
+pub const HINT_LOOKAHEAD_TICKS: u32 = 32;
+
+/// A suggested trigger, and the score it's expected to earn over the lookahead.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Hint {
+    pub coordinates: Coordinates,
+    pub score: u64,
+}
+
+impl Hint {
+    /// Finds the trigger that earns the highest score over the next `ticks` ticks, by simulating
+    /// every lit tile being triggered. Returns `None` if no trigger would light a tile.
+    pub fn find(tile_map: &TileMap, ticks: u32) -> Option<Hint> {
+        Self::candidates(tile_map)
+            .into_iter()
+            .filter_map(|coordinates| {
+                let mut tile_map = tile_map.clone();
+                if !tile_map.make_tile_white(coordinates.x, coordinates.y) {
+                    return None;
+                }
+
+                Some(Hint {
+                    coordinates,
+                    score: Self::simulate(tile_map, ticks),
+                })
+            })
+            // Ties go to the lowest coordinates, so the same board always gives the same hint.
+            .max_by_key(|hint| { (hint.score, std::cmp::Reverse(hint.coordinates)) })
+    }
+
+    /// Returns the score earned by `tile_map` over the next `ticks` ticks, with no more triggers.
+    pub fn simulate(mut tile_map: TileMap, ticks: u32) -> u64 {
+        let mut score = 0;
+
+        for _ in 0..ticks {
+            let score_increment = tile_map.tick_score();
+            if score_increment == 0 {
+                break;
+            }
+
+            score += score_increment;
+            tile_map.tick_update();
+        }
+
+        score
+    }
+
+    /// Only lit normal tiles can be triggered, so they're the only ones worth simulating. They're
+    /// returned in order, so the same board always gives the same candidates.
+    pub fn candidates(tile_map: &TileMap) -> Vec<Coordinates> {
+        let mut candidates: Vec<Coordinates> = tile_map.iter()
+            .filter(|(_, tile)| { tile.kind == TileKind::Normal && tile.is_not_black() })
+            .map(|(coordinates, _)| { *coordinates })
+            .collect();
+        candidates.sort();
+
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::{Tile, MAX_LIGHTNESS, NEIGHBOUR_OFFSETS};
+
+    /// A dim open tile at the origin, a dim tile boxed in by walls, and some tiles that can't be
+    /// triggered. Only the open tile can light its neighbours, so it's the best trigger.
+    fn board() -> TileMap {
+        let boxed: Coordinates = (10, 0).into();
+        let walls = NEIGHBOUR_OFFSETS.map(|offset| { (boxed + offset.into(), Tile::new(0, TileKind::Wall)) });
+
+        TileMap::from_tiles(
+            [
+                ((0, 0).into(), Tile::new_gray(1)),
+                (boxed, Tile::new_gray(1)),
+                ((-5, 0).into(), Tile::new_black()),
+                ((-5, 5).into(), Tile::new(MAX_LIGHTNESS, TileKind::Lamp)),
+            ]
+            .into_iter()
+            .chain(walls)
+        )
+    }
+
+    /// Steps the board by hand for `ticks` ticks, adding up the score.
+    fn step(mut tile_map: TileMap, ticks: u32) -> u64 {
+        let mut score = 0;
+        for _ in 0..ticks {
+            score += tile_map.tick_score();
+            tile_map.tick_update();
+        }
+
+        score
+    }
+
+    #[test]
+    fn candidates_are_lit_normal_tiles_in_order() {
+        assert_eq!(Hint::candidates(&board()), vec![(0, 0).into(), (10, 0).into()]);
+    }
+
+    #[test]
+    fn simulate_matches_stepping_by_hand() {
+        let mut tile_map = board();
+        tile_map.make_tile_white(0, 0);
+
+        for ticks in [0, 1, 10, HINT_LOOKAHEAD_TICKS, MAX_LIGHTNESS as u32 + 10] {
+            assert_eq!(Hint::simulate(tile_map.clone(), ticks), step(tile_map.clone(), ticks), "{} ticks", ticks);
+        }
+    }
+
+    #[test]
+    fn find_picks_the_trigger_that_lights_the_most() {
+        let tile_map = board();
+        let hint = Hint::find(&tile_map, HINT_LOOKAHEAD_TICKS).unwrap();
+
+        let mut best = tile_map.clone();
+        best.make_tile_white(0, 0);
+        let mut boxed = tile_map;
+        boxed.make_tile_white(10, 0);
+
+        assert_eq!(hint, Hint { coordinates: (0, 0).into(), score: step(best, HINT_LOOKAHEAD_TICKS) });
+        assert!(hint.score > step(boxed, HINT_LOOKAHEAD_TICKS));
+    }
+
+    #[test]
+    fn find_breaks_ties_with_the_lowest_coordinates() {
+        let tile_map = TileMap::from_tiles([
+            ((10, 0).into(), Tile::new_gray(1)),
+            ((0, 0).into(), Tile::new_gray(1)),
+        ]);
+
+        assert_eq!(Hint::find(&tile_map, HINT_LOOKAHEAD_TICKS).unwrap().coordinates, (0, 0).into());
+    }
+
+    #[test]
+    fn find_gives_nothing_without_candidates() {
+        let tile_map = TileMap::from_tiles([
+            ((0, 0).into(), Tile::new_black()),
+            ((1, 0).into(), Tile::new(0, TileKind::Wall)),
+            ((2, 0).into(), Tile::new(MAX_LIGHTNESS, TileKind::Lamp)),
+        ]);
+
+        assert_eq!(Hint::find(&tile_map, HINT_LOOKAHEAD_TICKS), None);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::resources::{GameStats, TileMap};
+use std::collections::VecDeque;
+
+/// The number of snapshots kept while playing. Snapshots are taken every tick, so this bounds how
+/// far back a move can be undone.
+const GAME_HISTORY_CAPACITY: usize = 600;
+
+/// A bounded undo/redo history of snapshots.
+#[derive(Debug, Clone)]
+pub struct History<T: Clone> {
+    undo_stack: VecDeque<T>,
+    redo_stack: Vec<T>,
+    capacity: usize,
+}
+
+pub type TileMapHistory = History<TileMap>;
+
+impl<T: Clone> History<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::with_capacity(capacity),
+            redo_stack: vec![],
+            capacity,
+        }
+    }
+
+    /// Records `state` as the state to return to on the next undo, and forgets any redo history.
+    pub fn push(&mut self, state: &T) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.undo_stack.len() == self.capacity {
+            self.undo_stack.pop_front();
+        }
+
+        self.undo_stack.push_back(state.clone());
+        self.redo_stack.clear();
+    }
+
+    /// Returns the previous state, remembering `current` so the undo can be redone.
+    pub fn undo(&mut self, current: &T) -> Option<T> {
+        let previous = self.undo_stack.pop_back()?;
+        self.redo_stack.push(current.clone());
+
+        Some(previous)
+    }
+
+    /// Returns the state before the last undo, remembering `current` so the redo can be undone.
+    pub fn redo(&mut self, current: &T) -> Option<T> {
+        let next = self.redo_stack.pop()?;
+        self.undo_stack.push_back(current.clone());
+
+        Some(next)
+    }
+
+    /// Removes and returns the most recent state, without remembering anything to redo.
+    pub fn pop(&mut self) -> Option<T> {
+        self.undo_stack.pop_back()
+    }
+
+    /// Removes and returns the most recent state matching `predicate`, discarding every state after
+    /// it. Leaves the history untouched if no state matches.
+    pub fn pop_to(&mut self, predicate: impl Fn(&T) -> bool) -> Option<T> {
+        let i = self.undo_stack.iter().rposition(predicate)?;
+        self.undo_stack.truncate(i + 1);
+        self.redo_stack.clear();
+
+        self.undo_stack.pop_back()
+    }
+
+    /// Whether any remembered state matches `predicate`.
+    pub fn contains(&self, predicate: impl Fn(&T) -> bool) -> bool {
+        self.undo_stack.iter().any(predicate)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+/// Everything a move or tick changes, so it can be put back exactly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameSnapshot {
+    pub tile_map: TileMap,
+    pub score: u64,
+    pub clicks_remaining: Option<u32>,
+    pub stats: GameStats,
+    pub is_move: bool,
+}
+
+/// The history of a game that allows moves to be undone, with a snapshot before every trigger and
+/// every tick.
+#[derive(Debug, Clone)]
+pub struct GameHistory(History<GameSnapshot>);
+
+impl Default for GameHistory {
+    fn default() -> Self {
+        Self(History::new(GAME_HISTORY_CAPACITY))
+    }
+}
+
+impl GameHistory {
+    pub fn record_move(&mut self, tile_map: &TileMap, score: u64, clicks_remaining: Option<u32>, stats: &GameStats) {
+        self.record(tile_map, score, clicks_remaining, stats, true);
+    }
+
+    pub fn record_tick(&mut self, tile_map: &TileMap, score: u64, clicks_remaining: Option<u32>, stats: &GameStats) {
+        self.record(tile_map, score, clicks_remaining, stats, false);
+    }
+
+    /// Returns the snapshot taken just before the last move, discarding every tick since. Returns
+    /// `None` if there are no moves left to undo.
+    pub fn undo_move(&mut self) -> Option<GameSnapshot> {
+        self.0.pop_to(|snapshot| { snapshot.is_move })
+    }
+
+    pub fn can_undo_move(&self) -> bool {
+        self.0.contains(|snapshot| { snapshot.is_move })
+    }
+
+    fn record(
+        &mut self,
+        tile_map: &TileMap,
+        score: u64,
+        clicks_remaining: Option<u32>,
+        stats: &GameStats,
+        is_move: bool,
+    ) {
+        self.0.push(&GameSnapshot {
+            tile_map: tile_map.clone(),
+            score,
+            clicks_remaining,
+            stats: stats.clone(),
+            is_move,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_restores_tile_map_before_trigger() {
+        let mut tile_map = TileMap::new();
+        let mut history = TileMapHistory::new(10);
+
+        let before = tile_map.clone();
+        history.push(&tile_map);
+        assert!(tile_map.make_tile_white(1, 0));
+        assert_ne!(tile_map, before);
+
+        let restored = history.undo(&tile_map).unwrap();
+        assert_eq!(restored, before);
+
+        let redone = history.redo(&restored).unwrap();
+        assert_eq!(redone, tile_map);
+    }
+
+    #[test]
+    fn history_drops_oldest_snapshot_when_full() {
+        let mut history = History::new(2);
+        history.push(&1);
+        history.push(&2);
+        history.push(&3);
+
+        assert_eq!(history.pop(), Some(3));
+        assert_eq!(history.pop(), Some(2));
+        assert_eq!(history.pop(), None);
+    }
+
+    #[test]
+    fn undo_move_restores_exact_state_before_move() {
+        let mut tile_map = TileMap::new();
+        let mut history = GameHistory::default();
+
+        let mut stats = GameStats::default();
+
+        history.record_tick(&tile_map, 0, Some(3), &stats);
+        tile_map.tick_update();
+
+        let before_move = tile_map.clone();
+        let stats_before_move = stats.clone();
+        history.record_move(&tile_map, 8, Some(3), &stats);
+        tile_map.make_tile_white(1, 1);
+        stats.clicks += 1;
+        stats.wasted_clicks += 1;
+
+        for score in [16, 24, 32] {
+            history.record_tick(&tile_map, score, Some(2), &stats);
+            tile_map.tick_update();
+        }
+
+        assert!(history.can_undo_move());
+        let snapshot = history.undo_move().unwrap();
+        assert_eq!(snapshot.tile_map, before_move);
+        assert_eq!(snapshot.score, 8);
+        assert_eq!(snapshot.clicks_remaining, Some(3));
+        assert_eq!(snapshot.stats, stats_before_move);
+
+        assert!(!history.can_undo_move());
+        assert_eq!(history.undo_move(), None);
+    }
+}
@@ -0,0 +1,357 @@
+use crate::resources::{Coordinates, Tile, TileKind, TileMap};
+use anyhow::Context;
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// A handcrafted puzzle, loaded from a `.level.ron` file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeUuid)]
+#[uuid = "5b0c3c7e-8a5f-4b8e-9d0e-3f6f2a1c7d41"]
+pub struct Level {
+    pub name: String,
+    pub clicks: u32,
+    pub tiles: Vec<LevelTile>,
+    pub goal: Goal,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LevelTile {
+    pub x: i32,
+    pub y: i32,
+    #[serde(default)]
+    pub lightness: u8,
+    #[serde(default)]
+    pub kind: TileKind,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Goal {
+    /// Every tile between `min` and `max` (inclusive) must be lit at the same time.
+    LightRegion { min: (i32, i32), max: (i32, i32) },
+    /// The score must reach the given value.
+    Score(u64),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LevelError {
+    MissingName,
+    NoClicks,
+    NoTiles,
+    NoLitTiles,
+    DuplicateTile(Coordinates),
+    LitWall(Coordinates),
+    EmptyGoalRegion(Coordinates, Coordinates),
+    GoalRegionContainsWall(Coordinates),
+}
+
+impl Display for LevelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::MissingName => write!(f, "the level has no name"),
+            LevelError::NoClicks => write!(f, "the click budget must be at least 1"),
+            LevelError::NoTiles => write!(f, "the level has no tiles"),
+            LevelError::NoLitTiles => write!(f, "at least one tile must start lit, or the level is lost immediately"),
+            LevelError::DuplicateTile(c) => write!(f, "tile {} is defined more than once", c),
+            LevelError::LitWall(c) => write!(f, "wall {} must have a lightness of 0", c),
+            LevelError::EmptyGoalRegion(min, max) => write!(f, "goal region {} to {} is empty", min, max),
+            LevelError::GoalRegionContainsWall(c) => write!(f, "goal region contains wall {}, which can never be lit", c),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl Level {
+    pub fn validate(&self) -> Result<(), LevelError> {
+        if self.name.trim().is_empty() {
+            return Err(LevelError::MissingName);
+        }
+
+        if self.clicks == 0 {
+            return Err(LevelError::NoClicks);
+        }
+
+        if self.tiles.is_empty() {
+            return Err(LevelError::NoTiles);
+        }
+
+        let mut seen: HashSet<Coordinates> = HashSet::default();
+        for tile in self.tiles.iter() {
+            let coordinates = tile.coordinates();
+            if !seen.insert(coordinates) {
+                return Err(LevelError::DuplicateTile(coordinates));
+            }
+
+            if tile.kind == TileKind::Wall && tile.lightness != 0 {
+                return Err(LevelError::LitWall(coordinates));
+            }
+        }
+
+        if self.tiles.iter().all(|tile| { tile.lightness == 0 }) {
+            return Err(LevelError::NoLitTiles);
+        }
+
+        if let Goal::LightRegion { min, max } = self.goal {
+            if min.0 > max.0 || min.1 > max.1 {
+                return Err(LevelError::EmptyGoalRegion(min.into(), max.into()));
+            }
+
+            let wall = self.tiles.iter()
+                .filter(|tile| { tile.kind == TileKind::Wall })
+                .map(|tile| { tile.coordinates() })
+                .find(|c| { c.x >= min.0 && c.x <= max.0 && c.y >= min.1 && c.y <= max.1 });
+            if let Some(wall) = wall {
+                return Err(LevelError::GoalRegionContainsWall(wall));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn to_tile_map(&self) -> TileMap {
+        TileMap::from_tiles(
+            self.tiles.iter()
+                .map(|tile| { (tile.coordinates(), Tile::new(tile.lightness, tile.kind)) })
+        )
+    }
+}
+
+impl LevelTile {
+    pub fn coordinates(&self) -> Coordinates {
+        (self.x, self.y).into()
+    }
+}
+
+impl Goal {
+    pub fn is_met(&self, tile_map: &TileMap, score: u64) -> bool {
+        match *self {
+            Goal::LightRegion { min, max } => (min.0..=max.0)
+                .flat_map(|x| { (min.1..=max.1).map(move |y| { Coordinates { x, y } }) })
+                .all(|c| { tile_map.get(&c).is_some_and(|tile| { tile.is_not_black() }) }),
+            Goal::Score(target) => score >= target,
+        }
+    }
+
+    /// Whether the goal can still be met once every click is used. Without clicks tiles only fade,
+    /// so a region that isn't lit never will be, but the score keeps growing while any light lasts.
+    pub fn can_be_met_without_clicks(&self) -> bool {
+        matches!(self, Goal::Score(_))
+    }
+}
+
+/// The ordered list of levels shown on the level select screen, loaded from a `.levels.ron` file.
+#[derive(Debug, Clone, Serialize, Deserialize, TypeUuid)]
+#[uuid = "0e7d6a52-2c1b-4f0a-a3b4-6c3d9e8f1b27"]
+pub struct LevelIndex {
+    pub levels: Vec<String>,
+}
+
+/// Handles to every level in the index, in order.
+#[derive(Debug, Default)]
+pub struct Levels {
+    pub index: Handle<LevelIndex>,
+    pub levels: Vec<(String, Handle<Level>)>,
+}
+
+/// Best scores of completed levels, keyed by level path.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct LevelProgress {
+    pub completed: HashMap<String, u64>,
+}
+
+impl LevelProgress {
+    pub const SAVE_NAME: &'static str = "level_progress";
+
+    pub fn is_completed(&self, path: &str) -> bool {
+        self.completed.contains_key(path)
+    }
+
+    pub fn complete(&mut self, path: &str, score: u64) {
+        let best_score = self.completed.entry(path.to_string()).or_insert(score);
+        *best_score = score.max(*best_score);
+    }
+}
+
+/// The state of the puzzle currently being played.
+#[derive(Debug, Clone)]
+pub struct PuzzleState {
+    /// The index of the level, or `None` if the level is being test-played from the editor.
+    pub level: Option<usize>,
+    pub clicks_remaining: u32,
+    pub goal: Goal,
+    pub is_complete: bool,
+}
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let path = load_context.path().display().to_string();
+            let level: Level = ron::de::from_bytes(bytes)
+                .with_context(|| format!("Can't parse level {}", path))?;
+            level.validate()
+                .with_context(|| format!("Invalid level {}", path))?;
+
+            load_context.set_default_asset(LoadedAsset::new(level));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+#[derive(Default)]
+pub struct LevelIndexLoader;
+
+impl AssetLoader for LevelIndexLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let path = load_context.path().display().to_string();
+            let index: LevelIndex = ron::de::from_bytes(bytes)
+                .with_context(|| format!("Can't parse level index {}", path))?;
+
+            load_context.set_default_asset(LoadedAsset::new(index));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["levels.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIPPED_LEVELS: [&str; 3] = [
+        include_str!("../../../assets/levels/first_light.level.ron"),
+        include_str!("../../../assets/levels/slow_burn.level.ron"),
+        include_str!("../../../assets/levels/walled_garden.level.ron"),
+    ];
+
+    fn tile(x: i32, y: i32, lightness: u8) -> LevelTile {
+        LevelTile {
+            x,
+            y,
+            lightness,
+            kind: TileKind::Normal,
+        }
+    }
+
+    fn wall(x: i32, y: i32, lightness: u8) -> LevelTile {
+        LevelTile {
+            x,
+            y,
+            lightness,
+            kind: TileKind::Wall,
+        }
+    }
+
+    /// A valid level, for each test to break in one way.
+    fn level() -> Level {
+        Level {
+            name: "Test".to_string(),
+            clicks: 2,
+            tiles: vec![tile(0, 0, 128), tile(1, 0, 0), wall(2, 0, 0)],
+            goal: Goal::LightRegion { min: (0, 0), max: (1, 0) },
+        }
+    }
+
+    #[test]
+    fn valid_level_passes() {
+        assert_eq!(level().validate(), Ok(()));
+    }
+
+    #[test]
+    fn blank_name_is_missing() {
+        let level = Level {
+            name: "  ".to_string(),
+            ..level()
+        };
+        assert_eq!(level.validate(), Err(LevelError::MissingName));
+    }
+
+    #[test]
+    fn zero_clicks_is_rejected() {
+        let level = Level {
+            clicks: 0,
+            ..level()
+        };
+        assert_eq!(level.validate(), Err(LevelError::NoClicks));
+    }
+
+    #[test]
+    fn level_without_tiles_is_rejected() {
+        let level = Level {
+            tiles: vec![],
+            ..level()
+        };
+        assert_eq!(level.validate(), Err(LevelError::NoTiles));
+    }
+
+    #[test]
+    fn duplicate_tile_is_reported() {
+        let mut level = level();
+        level.tiles.push(tile(1, 0, 255));
+        assert_eq!(level.validate(), Err(LevelError::DuplicateTile((1, 0).into())));
+    }
+
+    #[test]
+    fn lit_wall_is_reported() {
+        let mut level = level();
+        level.tiles[2] = wall(2, 0, 64);
+        assert_eq!(level.validate(), Err(LevelError::LitWall((2, 0).into())));
+    }
+
+    #[test]
+    fn level_without_light_is_rejected() {
+        let mut level = level();
+        level.tiles[0] = tile(0, 0, 0);
+        assert_eq!(level.validate(), Err(LevelError::NoLitTiles));
+    }
+
+    #[test]
+    fn inverted_goal_region_is_empty() {
+        let level = Level {
+            goal: Goal::LightRegion { min: (1, 0), max: (0, 0) },
+            ..level()
+        };
+        assert_eq!(level.validate(), Err(LevelError::EmptyGoalRegion((1, 0).into(), (0, 0).into())));
+    }
+
+    #[test]
+    fn goal_region_over_a_wall_is_reported() {
+        let level = Level {
+            goal: Goal::LightRegion { min: (0, 0), max: (2, 0) },
+            ..level()
+        };
+        assert_eq!(level.validate(), Err(LevelError::GoalRegionContainsWall((2, 0).into())));
+    }
+
+    #[test]
+    fn shipped_levels_are_valid_and_round_trip() {
+        for source in SHIPPED_LEVELS {
+            let level: Level = ron::from_str(source).unwrap();
+            assert_eq!(level.validate(), Ok(()), "{}", level.name);
+
+            let saved = ron::ser::to_string_pretty(&level, Default::default()).unwrap();
+            assert_eq!(ron::from_str::<Level>(&saved).unwrap(), level);
+        }
+    }
+}
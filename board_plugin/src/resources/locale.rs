@@ -0,0 +1,212 @@
+use crate::utils::format_number;
+use bevy::log;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use serde::{Deserialize, Serialize};
+use unic_langid::LanguageIdentifier;
+
+/// A language the UI can be shown in. Its messages are in `assets/locales/<code>/main.ftl`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    /// The language's name in that language, so it can be found whichever language is shown.
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    /// The language after this one, wrapping around to the first.
+    pub fn next(&self) -> Language {
+        let index = Language::ALL.iter().position(|language| { language == self }).unwrap_or(0);
+        Language::ALL[(index + 1) % Language::ALL.len()]
+    }
+
+    pub fn thousands_separator(&self) -> char {
+        match self {
+            Language::English => ',',
+            Language::German => '.',
+        }
+    }
+
+    /// The Fluent source of the language's messages, built into the game.
+    pub fn source(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("../../../assets/locales/en/main.ftl"),
+            Language::German => include_str!("../../../assets/locales/de/main.ftl"),
+        }
+    }
+}
+
+/// Every UI string, in every language. Messages missing from the current language fall back to
+/// English.
+pub struct Locale {
+    pub language: Language,
+    bundles: Vec<(Language, FluentBundle<FluentResource>)>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::new(Language::default())
+    }
+}
+
+impl Locale {
+    pub fn new(language: Language) -> Self {
+        let bundles = Language::ALL.into_iter()
+            .map(|language| { (language, build_bundle(language)) })
+            .collect();
+
+        Self {
+            language,
+            bundles,
+        }
+    }
+
+    /// The message with the id `key`.
+    pub fn get(&self, key: &str) -> String {
+        self.format(key, &[])
+    }
+
+    /// The message with the id `key`, with each `$name` in it replaced by its value from `args`.
+    pub fn format(&self, key: &str, args: &[(&str, String)]) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+
+        for language in [self.language, Language::English] {
+            let bundle = self.bundle(language);
+            let pattern = match bundle.get_message(key).and_then(|message| { message.value() }) {
+                Some(pattern) => pattern,
+                None => continue,
+            };
+
+            let mut errors = vec![];
+            let value = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+            if !errors.is_empty() {
+                log::warn!("Errors formatting message {} in {:?}: {:?}", key, language, errors);
+            }
+
+            return value.into_owned();
+        }
+
+        log::warn!("No message {} in {:?}", key, self.language);
+        key.to_string()
+    }
+
+    /// Whether the language has a message with the id `key`, without falling back to English.
+    pub fn has_message(&self, language: Language, key: &str) -> bool {
+        self.bundle(language).has_message(key)
+    }
+
+    /// Formats `n` with the language's thousands separator.
+    pub fn format_number(&self, n: u64) -> String {
+        format_number(n, self.language.thousands_separator())
+    }
+
+    fn bundle(&self, language: Language) -> &FluentBundle<FluentResource> {
+        self.bundles.iter()
+            .find(|(bundle_language, _)| { *bundle_language == language })
+            .map(|(_, bundle)| { bundle })
+            .expect("Every language has a bundle")
+    }
+}
+
+fn build_bundle(language: Language) -> FluentBundle<FluentResource> {
+    let language_id: LanguageIdentifier = language.code().parse().expect("Language codes are valid");
+    let mut bundle = FluentBundle::new_concurrent(vec![language_id]);
+
+    // Unicode isolation marks around arguments would be drawn as boxes by the UI font.
+    bundle.set_use_isolating(false);
+
+    let resource = FluentResource::try_new(language.source().to_string()).unwrap_or_else(|(resource, errors)| {
+        log::error!("Errors parsing the {:?} messages: {:?}", language, errors);
+        resource
+    });
+
+    if let Err(errors) = bundle.add_resource(resource) {
+        log::error!("Errors adding the {:?} messages: {:?}", language, errors);
+    }
+
+    bundle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::{Achievement, DailyVariant, Palette, Player};
+
+    /// The ids of every message in a Fluent source.
+    fn message_keys(source: &str) -> Vec<&str> {
+        source.lines()
+            .filter(|line| { line.starts_with(|c: char| { c.is_ascii_alphabetic() }) })
+            .filter_map(|line| { line.split_once(" =").map(|(key, _)| { key }) })
+            .collect()
+    }
+
+    #[test]
+    fn every_key_exists_in_every_locale() {
+        let locale = Locale::default();
+
+        for language in Language::ALL {
+            for other_language in Language::ALL {
+                for key in message_keys(other_language.source()) {
+                    assert!(
+                        locale.has_message(language, key),
+                        "{} is in {:?} but missing from {:?}",
+                        key,
+                        other_language,
+                        language,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_key_used_by_the_game_exists() {
+        let locale = Locale::default();
+
+        let keys = Achievement::ALL.iter().flat_map(|achievement| { [achievement.key(), achievement.description_key()] })
+            .chain(DailyVariant::ALL.iter().map(|variant| { variant.key() }))
+            .chain(Palette::ALL.iter().map(|palette| { palette.key() }))
+            .chain(Player::ALL.iter().map(|player| { player.key() }))
+            .chain((1..=12).map(crate::resources::month_key));
+
+        for language in Language::ALL {
+            for key in keys.clone() {
+                assert!(locale.has_message(language, key), "{} is missing from {:?}", key, language);
+            }
+        }
+    }
+
+    #[test]
+    fn numbers_use_the_language_separator() {
+        assert_eq!(Locale::new(Language::English).format_number(1234567), "1,234,567");
+        assert_eq!(Locale::new(Language::German).format_number(1234567), "1.234.567");
+        assert_eq!(Locale::new(Language::German).format_number(999), "999");
+    }
+
+    #[test]
+    fn arguments_are_filled_in() {
+        let locale = Locale::new(Language::German);
+
+        assert_eq!(locale.format("hud-score", &[("score", locale.format_number(12345))]), "Punkte: 12.345");
+    }
+}
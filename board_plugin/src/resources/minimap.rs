@@ -0,0 +1,85 @@
+use crate::resources::Coordinates;
+use bevy::prelude::*;
+
+/// The fewest tiles across the minimap shows, so a small board isn't blown up to fill it.
+const MIN_MINIMAP_TILES: i32 = 32;
+/// How many tiles are left around the lit tiles on the minimap.
+const MINIMAP_MARGIN: i32 = 4;
+
+/// The square of the board drawn on the minimap, and where the light on it is.
+pub struct Minimap {
+    /// The map, one pixel per tile.
+    pub image: Handle<Image>,
+    /// The bottom left tile shown.
+    pub origin: Coordinates,
+    /// How many tiles across and up the map is.
+    pub size: i32,
+    /// The smallest and largest coordinates of each group of touching lit tiles.
+    pub clusters: Vec<(Coordinates, Coordinates)>,
+}
+
+impl Minimap {
+    pub fn new(image: Handle<Image>) -> Self {
+        Self {
+            image,
+            origin: (-MIN_MINIMAP_TILES / 2, -MIN_MINIMAP_TILES / 2).into(),
+            size: MIN_MINIMAP_TILES,
+            clusters: vec![],
+        }
+    }
+
+    /// Centres the map on the tiles from `min` to `max`, with room around them.
+    pub fn fit(&mut self, (min, max): (Coordinates, Coordinates)) {
+        let width = max.x - min.x + 1;
+        let height = max.y - min.y + 1;
+
+        self.size = (width.max(height) + MINIMAP_MARGIN * 2).max(MIN_MINIMAP_TILES);
+        self.origin = (min.x - (self.size - width) / 2, min.y - (self.size - height) / 2).into();
+    }
+
+    /// Where a point on the board, measured in tiles, falls on the map, from (0, 0) at its bottom
+    /// left to (1, 1) at its top right.
+    pub fn to_map(&self, position: Vec2) -> Vec2 {
+        (position - self.bottom_left()) / self.size as f32
+    }
+
+    /// The point on the board, measured in tiles, under a point on the map.
+    pub fn to_board(&self, point: Vec2) -> Vec2 {
+        self.bottom_left() + point * self.size as f32
+    }
+
+    /// The outer corner of the bottom left tile. Tiles are centred on their coordinates.
+    fn bottom_left(&self) -> Vec2 {
+        Vec2::new(self.origin.x as f32, self.origin.y as f32) - Vec2::splat(0.5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_centres_the_lit_tiles() {
+        let mut minimap = Minimap::new(Handle::default());
+
+        minimap.fit(((0, 0).into(), (3, 1).into()));
+        assert_eq!(minimap.size, MIN_MINIMAP_TILES);
+        assert_eq!(minimap.origin, (-14, -15).into());
+
+        minimap.fit(((-50, 10).into(), (49, 20).into()));
+        assert_eq!(minimap.size, 100 + MINIMAP_MARGIN * 2);
+        assert_eq!(minimap.origin, (-54, -38).into());
+    }
+
+    #[test]
+    fn map_and_board_points_match() {
+        let mut minimap = Minimap::new(Handle::default());
+        minimap.fit(((-50, 10).into(), (49, 20).into()));
+
+        assert_eq!(minimap.to_map(Vec2::new(-54.5, -38.5)), Vec2::ZERO);
+        assert_eq!(minimap.to_map(Vec2::new(53.5, 69.5)), Vec2::ONE);
+
+        let position = Vec2::new(12., -3.);
+        assert!((minimap.to_board(minimap.to_map(position)) - position).length() < 1e-4);
+    }
+}
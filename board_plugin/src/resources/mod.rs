@@ -1,9 +1,49 @@
+pub use achievements::*;
 pub use coordinates::*;
 pub use board::*;
+pub use classic::*;
+pub use daily::*;
+pub use editor::*;
+pub use effects::*;
+pub use floor::*;
+pub use game_mode::*;
+pub use hint::*;
+pub use history::*;
+pub use level::*;
+pub use locale::*;
+pub use minimap::*;
+pub use online::*;
+pub use pool::*;
 pub use scoring::*;
-pub(crate) use tile::*;
+pub use settings::*;
+pub use spectator::*;
+pub use stats::*;
+pub use theme::*;
+pub use tile::*;
+pub use ui_focus::*;
+pub use versus::*;
 
+mod achievements;
 mod coordinates;
 mod board;
+mod classic;
+mod daily;
+mod editor;
+mod effects;
+mod floor;
+mod game_mode;
+mod hint;
+mod history;
+mod level;
+mod locale;
+mod minimap;
+mod online;
+mod pool;
 mod scoring;
-mod tile;
\ No newline at end of file
+mod settings;
+mod spectator;
+mod stats;
+mod theme;
+mod tile;
+mod ui_focus;
+mod versus;
\ No newline at end of file
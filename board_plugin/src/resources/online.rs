@@ -0,0 +1,6 @@
+/// Where to play online games, given by `--connect` on the command line.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OnlineConfig {
+    /// The server's address, such as `127.0.0.1:9001`.
+    pub addr: String,
+}
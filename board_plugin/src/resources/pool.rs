@@ -0,0 +1,30 @@
+use bevy::prelude::Entity;
+use std::marker::PhantomData;
+
+/// Entities spawned once and reused, for effects that come and go too often to spawn each time.
+/// `T` is the component the entities are pooled for, so each kind of effect has its own pool. New
+/// effects take the place of the oldest, so when every entity is in use the ones closest to
+/// finishing go first.
+pub struct EntityPool<T> {
+    entities: Vec<Entity>,
+    next: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> EntityPool<T> {
+    pub fn new(entities: Vec<Entity>) -> Self {
+        Self {
+            entities,
+            next: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// The entity to use next.
+    pub fn take(&mut self) -> Option<Entity> {
+        let entity = *self.entities.get(self.next)?;
+        self.next = (self.next + 1) % self.entities.len();
+
+        Some(entity)
+    }
+}
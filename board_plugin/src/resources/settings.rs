@@ -0,0 +1,56 @@
+use crate::resources::{Language, MAX_LIGHTNESS};
+use serde::{Deserialize, Serialize};
+
+/// The UI scales to choose between, as multiples of the display's own scale.
+pub const UI_SCALES: [f64; 4] = [0.75, 1., 1.25, 1.5];
+
+/// How many bands lightness is split into for high-contrast labels.
+pub const LIGHTNESS_BANDS: u8 = 9;
+
+/// Accessibility and display options, saved between runs.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Scales the menus, text and HUD. The board isn't scaled, since it can be zoomed already.
+    pub ui_scale: f64,
+    /// Labels each tile with its lightness band, so tiles can be told apart without comparing
+    /// shades.
+    pub high_contrast: bool,
+    /// Turns off animations.
+    pub reduced_motion: bool,
+    pub language: Language,
+    /// Draws lit tiles as lights glowing over a dark floor, rather than as flat squares alone.
+    pub dynamic_lighting: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.,
+            high_contrast: false,
+            reduced_motion: false,
+            language: Language::default(),
+            dynamic_lighting: false,
+        }
+    }
+}
+
+impl Settings {
+    pub const SAVE_NAME: &'static str = "settings";
+
+    /// The UI scale after this one, wrapping around to the smallest.
+    pub fn next_ui_scale(&self) -> f64 {
+        let index = UI_SCALES.iter().position(|scale| { *scale == self.ui_scale }).unwrap_or(0);
+        UI_SCALES[(index + 1) % UI_SCALES.len()]
+    }
+}
+
+/// The band from 1 to `LIGHTNESS_BANDS` that a lit tile falls in, or `None` for a dark tile.
+pub fn lightness_band(lightness: u8) -> Option<u8> {
+    if lightness == 0 {
+        return None;
+    }
+
+    let band = (lightness as u32 * LIGHTNESS_BANDS as u32).div_ceil(MAX_LIGHTNESS as u32);
+    Some(band as u8)
+}
@@ -0,0 +1,361 @@
+use crate::network::GameClient;
+use crate::resources::TileMap;
+use crate::simulation::{Replay, ReplayStrategy, Simulation};
+use std::collections::VecDeque;
+
+/// The playback speeds to choose between, as multiples of the game's own speed.
+pub const PLAYBACK_SPEEDS: [f64; 7] = [0.25, 0.5, 1., 2., 4., 8., 16.];
+const NORMAL_SPEED_INDEX: usize = 2;
+
+/// Ticks shown each second at normal speed, matching the game's tick timer.
+pub const PLAYBACK_TICKS_PER_SECOND: f64 = 10.;
+
+/// How many live frames are kept to scrub back through: 10 minutes at normal speed.
+const MAX_LIVE_FRAMES: usize = 6000;
+
+/// What to watch, given by `--spectate` or `--replay` on the command line.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SpectatorConfig {
+    /// A game running on a server, such as `127.0.0.1:9001`.
+    Live { addr: String },
+    /// A RON replay file, such as one recorded by `lights-out-sim --record`.
+    Replay { path: String },
+}
+
+/// The board and score at the start of one tick.
+#[derive(Debug, Clone)]
+pub struct SpectatorFrame {
+    pub tile_map: TileMap,
+    pub score: u64,
+}
+
+/// A read-only view of a game. Every tick is kept as a frame, so both live games and replays can
+/// be paused, scrubbed through, and played back at different speeds.
+pub struct Spectator {
+    /// Where live frames come from, or `None` when playing back a replay.
+    pub client: Option<GameClient>,
+    frames: VecDeque<SpectatorFrame>,
+    /// The frame being shown. It's fractional so slow speeds still move forwards.
+    position: f64,
+    speed_index: usize,
+    pub is_paused: bool,
+    /// Whether live playback keeps up with the newest frame.
+    pub is_following: bool,
+    /// The frame the board was last updated to.
+    pub shown_frame: Option<usize>,
+}
+
+impl Spectator {
+    pub fn live(client: GameClient) -> Self {
+        Self {
+            client: Some(client),
+            frames: VecDeque::new(),
+            position: 0.,
+            speed_index: NORMAL_SPEED_INDEX,
+            is_paused: false,
+            is_following: true,
+            shown_frame: None,
+        }
+    }
+
+    /// Plays the replay through once, up to `max_ticks`, keeping every frame.
+    pub fn replay(replay: Replay, max_ticks: u64) -> Self {
+        let mut simulation = Simulation::new(replay.seed);
+        let mut strategy = ReplayStrategy::new(replay);
+
+        let mut frames = VecDeque::new();
+        frames.push_back(SpectatorFrame {
+            tile_map: simulation.tile_map.clone(),
+            score: simulation.score,
+        });
+
+        while !simulation.is_over && simulation.tick < max_ticks {
+            simulation.step(&mut strategy);
+            frames.push_back(SpectatorFrame {
+                tile_map: simulation.tile_map.clone(),
+                score: simulation.score,
+            });
+        }
+
+        Self {
+            client: None,
+            frames,
+            position: 0.,
+            speed_index: NORMAL_SPEED_INDEX,
+            is_paused: false,
+            is_following: false,
+            shown_frame: None,
+        }
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.client.is_some()
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn frame_index(&self) -> usize {
+        self.position as usize
+    }
+
+    pub fn frame(&self) -> Option<&SpectatorFrame> {
+        self.frames.get(self.frame_index())
+    }
+
+    /// How far through the frames playback is, from 0 to 1.
+    pub fn progress(&self) -> f64 {
+        match self.frames.len() {
+            0 | 1 => 1.,
+            len => self.position / (len - 1) as f64,
+        }
+    }
+
+    pub fn speed(&self) -> f64 {
+        PLAYBACK_SPEEDS[self.speed_index]
+    }
+
+    pub fn speed_up(&mut self) {
+        self.speed_index = (self.speed_index + 1).min(PLAYBACK_SPEEDS.len() - 1);
+    }
+
+    pub fn slow_down(&mut self) {
+        self.speed_index = self.speed_index.saturating_sub(1);
+    }
+
+    /// Adds the newest live frame, dropping the oldest once there are too many to keep.
+    pub fn push_frame(&mut self, frame: SpectatorFrame) {
+        self.frames.push_back(frame);
+
+        if self.frames.len() > MAX_LIVE_FRAMES {
+            self.frames.pop_front();
+            self.position = (self.position - 1.).max(0.);
+            self.shown_frame = self.shown_frame.and_then(|shown_frame| { shown_frame.checked_sub(1) });
+        }
+
+        if self.is_following {
+            self.position = self.last_position();
+        }
+    }
+
+    /// Moves playback on by `seconds` of real time.
+    pub fn advance(&mut self, seconds: f64) {
+        if self.is_paused || self.is_following {
+            return;
+        }
+
+        self.position = (self.position + seconds * PLAYBACK_TICKS_PER_SECOND * self.speed()).min(self.last_position());
+
+        // Catching up with a live game goes back to following it.
+        if self.is_live() && self.position >= self.last_position() {
+            self.is_following = true;
+        }
+    }
+
+    /// Jumps to a point on the timeline, from 0 for the first frame to 1 for the last.
+    pub fn seek(&mut self, progress: f64) {
+        self.position = (progress.clamp(0., 1.) * self.last_position()).round();
+        self.is_following = self.is_live() && progress >= 1.;
+    }
+
+    /// Moves by a number of frames, backwards if negative.
+    pub fn skip(&mut self, frames: i64) {
+        self.position = (self.position.floor() + frames as f64).clamp(0., self.last_position());
+        self.is_following = self.is_live() && self.position >= self.last_position();
+    }
+
+    /// Jumps to the newest frame, following it if the game is live.
+    pub fn skip_to_end(&mut self) {
+        self.position = self.last_position();
+        self.is_following = self.is_live();
+    }
+
+    fn last_position(&self) -> f64 {
+        self.frames.len().saturating_sub(1) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::{GameServer, ServerConfig};
+    use bevy::tasks::TaskPool;
+
+    fn frame(score: u64) -> SpectatorFrame {
+        SpectatorFrame {
+            tile_map: TileMap::from_tiles([]),
+            score,
+        }
+    }
+
+    /// A replay-like spectator with `count` frames, scored by their index.
+    fn recorded(count: u64) -> Spectator {
+        Spectator {
+            frames: (0..count).map(frame).collect(),
+            ..Spectator::replay(Replay::new(0), 0)
+        }
+    }
+
+    /// A spectator of a real local server, with `count` frames already received.
+    fn live(count: u64) -> Spectator {
+        let server = GameServer::bind("127.0.0.1:0", ServerConfig::default())
+            .and_then(|server| { server.spawn() })
+            .unwrap();
+        let client = GameClient::connect(&server.local_addr.to_string(), TaskPool::new()).unwrap();
+
+        let mut spectator = Spectator::live(client);
+        for score in 0..count {
+            spectator.push_frame(frame(score));
+        }
+        server.stop().unwrap();
+
+        spectator
+    }
+
+    #[test]
+    fn playback_moves_with_the_speed() {
+        let mut spectator = recorded(100);
+        assert_eq!(spectator.speed(), 1.);
+
+        spectator.advance(1.);
+        assert_eq!(spectator.frame_index(), 10);
+
+        spectator.speed_up();
+        spectator.advance(1.);
+        assert_eq!(spectator.frame_index(), 30);
+
+        spectator.slow_down();
+        spectator.slow_down();
+        spectator.advance(0.1);
+        assert_eq!(spectator.frame_index(), 30);
+        spectator.advance(0.1);
+        assert_eq!(spectator.frame().unwrap().score, 31);
+
+        spectator.is_paused = true;
+        spectator.advance(10.);
+        assert_eq!(spectator.frame_index(), 31);
+    }
+
+    #[test]
+    fn speed_stops_at_the_fastest_and_slowest() {
+        let mut spectator = recorded(2);
+
+        for _ in 0..20 {
+            spectator.speed_up();
+        }
+        assert_eq!(spectator.speed(), PLAYBACK_SPEEDS[PLAYBACK_SPEEDS.len() - 1]);
+
+        for _ in 0..20 {
+            spectator.slow_down();
+        }
+        assert_eq!(spectator.speed(), PLAYBACK_SPEEDS[0]);
+    }
+
+    #[test]
+    fn playback_stops_at_the_last_frame() {
+        let mut spectator = recorded(11);
+
+        spectator.advance(100.);
+        assert_eq!(spectator.frame_index(), 10);
+        assert_eq!(spectator.progress(), 1.);
+        assert!(!spectator.is_following);
+    }
+
+    #[test]
+    fn seeking_and_skipping_are_clamped_to_both_ends() {
+        let mut spectator = recorded(11);
+
+        spectator.seek(0.5);
+        assert_eq!(spectator.frame_index(), 5);
+        assert_eq!(spectator.progress(), 0.5);
+
+        spectator.seek(-1.);
+        assert_eq!(spectator.frame_index(), 0);
+        assert_eq!(spectator.progress(), 0.);
+
+        spectator.seek(2.);
+        assert_eq!(spectator.frame_index(), 10);
+
+        spectator.skip(-3);
+        assert_eq!(spectator.frame_index(), 7);
+        spectator.skip(-100);
+        assert_eq!(spectator.frame_index(), 0);
+        spectator.skip(100);
+        assert_eq!(spectator.frame_index(), 10);
+
+        spectator.seek(0.);
+        spectator.skip_to_end();
+        assert_eq!(spectator.frame_index(), 10);
+        assert!(!spectator.is_following);
+    }
+
+    #[test]
+    fn progress_is_complete_without_frames_to_move_through() {
+        assert_eq!(recorded(0).progress(), 1.);
+        assert_eq!(recorded(1).progress(), 1.);
+    }
+
+    #[test]
+    fn live_playback_follows_the_newest_frame() {
+        let mut spectator = live(5);
+        assert!(spectator.is_following);
+        assert_eq!(spectator.frame_index(), 4);
+
+        spectator.push_frame(frame(5));
+        assert_eq!(spectator.frame_index(), 5);
+
+        // Following ignores the playback speed.
+        spectator.advance(10.);
+        assert_eq!(spectator.frame_index(), 5);
+    }
+
+    #[test]
+    fn live_frames_arriving_while_scrubbed_back_dont_move_playback() {
+        let mut spectator = live(11);
+
+        spectator.seek(0.5);
+        assert!(!spectator.is_following);
+        spectator.push_frame(frame(11));
+        spectator.push_frame(frame(12));
+        assert_eq!(spectator.frame().unwrap().score, 5);
+        assert_eq!(spectator.frame_count(), 13);
+
+        // Catching up goes back to following.
+        spectator.advance(0.5);
+        assert_eq!(spectator.frame_index(), 10);
+        assert!(!spectator.is_following);
+        spectator.advance(1.);
+        assert_eq!(spectator.frame_index(), 12);
+        assert!(spectator.is_following);
+
+        spectator.skip(-1);
+        assert!(!spectator.is_following);
+        spectator.skip_to_end();
+        assert!(spectator.is_following);
+        spectator.seek(0.);
+        spectator.seek(1.);
+        assert!(spectator.is_following);
+    }
+
+    #[test]
+    fn dropping_old_live_frames_keeps_the_shown_frame_in_place() {
+        let mut spectator = live(MAX_LIVE_FRAMES as u64);
+
+        spectator.seek(0.5);
+        spectator.shown_frame = Some(spectator.frame_index());
+        let score = spectator.frame().unwrap().score;
+
+        spectator.push_frame(frame(MAX_LIVE_FRAMES as u64));
+        assert_eq!(spectator.frame_count(), MAX_LIVE_FRAMES);
+        assert_eq!(spectator.frame().unwrap().score, score);
+        assert_eq!(spectator.shown_frame, Some(spectator.frame_index()));
+
+        // Playback at the oldest frame stays there as it's dropped.
+        spectator.seek(0.);
+        spectator.shown_frame = Some(0);
+        spectator.push_frame(frame(MAX_LIVE_FRAMES as u64 + 1));
+        assert_eq!(spectator.frame_index(), 0);
+        assert_eq!(spectator.shown_frame, None);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::resources::{Coordinates, Locale, TileMap};
+use crate::utils::format_duration;
+use serde::{Deserialize, Serialize};
+
+/// Statistics of the game being played, collected from its events.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GameStats {
+    pub clicks: u64,
+    /// Clicks on black or white tiles, or off the board, which don't light anything new.
+    pub wasted_clicks: u64,
+    /// Moves taken back. Games with any aren't counted in lifetime statistics.
+    pub undos: u64,
+    pub ticks: u64,
+    /// Seconds spent playing, not counting time paused.
+    pub survival_time: f64,
+    pub final_score: u64,
+    pub peak_lit_tiles: usize,
+    pub peak_score_per_tick: u64,
+    /// The smallest and largest coordinates the board has reached.
+    pub extent: Option<(Coordinates, Coordinates)>,
+}
+
+impl GameStats {
+    pub fn record_time(&mut self, seconds: f64) {
+        self.survival_time += seconds;
+    }
+
+    pub fn record_tick(&mut self, tile_map: &TileMap, score: u64, score_increment: u64) {
+        self.ticks += 1;
+        self.final_score = score;
+        self.peak_lit_tiles = self.peak_lit_tiles.max(tile_map.non_black_tiles().len());
+        self.peak_score_per_tick = self.peak_score_per_tick.max(score_increment);
+
+        for coordinates in tile_map.keys() {
+            let (min, max) = self.extent.get_or_insert((*coordinates, *coordinates));
+            *min = (min.x.min(coordinates.x), min.y.min(coordinates.y)).into();
+            *max = (max.x.max(coordinates.x), max.y.max(coordinates.y)).into();
+        }
+    }
+
+    /// The width and height of the board at its largest, in tiles.
+    pub fn board_size(&self) -> (u32, u32) {
+        match self.extent {
+            Some((min, max)) => ((max.x - min.x + 1) as u32, (max.y - min.y + 1) as u32),
+            None => (0, 0),
+        }
+    }
+
+    /// One line per statistic, for the game over panel.
+    pub fn summary(&self, locale: &Locale) -> String {
+        let (width, height) = self.board_size();
+
+        locale.format("stats-game", &[
+            ("clicks", locale.format_number(self.clicks)),
+            ("wasted", locale.format_number(self.wasted_clicks)),
+            ("survived", format_duration(self.survival_time)),
+            ("lit", locale.format_number(self.peak_lit_tiles as u64)),
+            ("peak", locale.format_number(self.peak_score_per_tick)),
+            ("width", width.to_string()),
+            ("height", height.to_string()),
+        ])
+    }
+}
+
+/// Statistics of every game played, kept between sessions.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub games_played: u64,
+    pub clicks: u64,
+    pub wasted_clicks: u64,
+    pub ticks: u64,
+    pub play_time: f64,
+    pub best_score: u64,
+    pub longest_survival_time: f64,
+    pub peak_lit_tiles: usize,
+    pub peak_score_per_tick: u64,
+}
+
+impl LifetimeStats {
+    pub const SAVE_NAME: &'static str = "profile";
+
+    pub fn add(&mut self, game_stats: &GameStats) {
+        self.games_played += 1;
+        self.clicks += game_stats.clicks;
+        self.wasted_clicks += game_stats.wasted_clicks;
+        self.ticks += game_stats.ticks;
+        self.play_time += game_stats.survival_time;
+        self.best_score = self.best_score.max(game_stats.final_score);
+        self.longest_survival_time = self.longest_survival_time.max(game_stats.survival_time);
+        self.peak_lit_tiles = self.peak_lit_tiles.max(game_stats.peak_lit_tiles);
+        self.peak_score_per_tick = self.peak_score_per_tick.max(game_stats.peak_score_per_tick);
+    }
+
+    pub fn summary(&self, locale: &Locale) -> String {
+        locale.format("stats-lifetime", &[
+            ("games", locale.format_number(self.games_played)),
+            ("best", locale.format_number(self.best_score)),
+            ("survived", format_duration(self.longest_survival_time)),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::Tile;
+
+    fn tile_map(tiles: &[((i32, i32), u8)]) -> TileMap {
+        TileMap::from_tiles(tiles.iter().map(|(coordinates, lightness)| {
+            (Coordinates::from(*coordinates), Tile::new_gray(*lightness))
+        }))
+    }
+
+    #[test]
+    fn ticks_keep_the_peaks_and_the_latest_score() {
+        let mut game_stats = GameStats::default();
+
+        game_stats.record_tick(&tile_map(&[((0, 0), 10), ((1, 0), 10), ((2, 0), 0)]), 5, 5);
+        game_stats.record_tick(&tile_map(&[((0, 0), 9), ((1, 0), 0), ((2, 0), 0)]), 8, 3);
+
+        assert_eq!(game_stats.ticks, 2);
+        assert_eq!(game_stats.final_score, 8);
+        assert_eq!(game_stats.peak_lit_tiles, 2);
+        assert_eq!(game_stats.peak_score_per_tick, 5);
+    }
+
+    #[test]
+    fn ticks_grow_the_extent_over_every_tile() {
+        let mut game_stats = GameStats::default();
+        assert_eq!(game_stats.board_size(), (0, 0));
+
+        game_stats.record_tick(&tile_map(&[((0, 0), 10)]), 0, 0);
+        assert_eq!(game_stats.extent, Some(((0, 0).into(), (0, 0).into())));
+        assert_eq!(game_stats.board_size(), (1, 1));
+
+        // Black tiles count, and a smaller board later doesn't shrink the extent.
+        game_stats.record_tick(&tile_map(&[((-2, 1), 0), ((3, -1), 10)]), 0, 0);
+        game_stats.record_tick(&tile_map(&[((0, 0), 10)]), 0, 0);
+        assert_eq!(game_stats.extent, Some(((-2, -1).into(), (3, 1).into())));
+        assert_eq!(game_stats.board_size(), (6, 3));
+    }
+
+    #[test]
+    fn survival_time_adds_up_without_ticks() {
+        let mut game_stats = GameStats::default();
+
+        game_stats.record_time(0.25);
+        game_stats.record_time(0.5);
+        game_stats.record_tick(&tile_map(&[((0, 0), 10)]), 0, 0);
+
+        assert_eq!(game_stats.survival_time, 0.75);
+        assert_eq!(game_stats.ticks, 1);
+    }
+
+    #[test]
+    fn lifetime_stats_add_totals_and_keep_bests() {
+        let mut lifetime_stats = LifetimeStats::default();
+        let first = GameStats {
+            clicks: 10,
+            wasted_clicks: 2,
+            ticks: 100,
+            survival_time: 30.,
+            final_score: 500,
+            peak_lit_tiles: 12,
+            peak_score_per_tick: 40,
+            ..Default::default()
+        };
+        let second = GameStats {
+            clicks: 5,
+            wasted_clicks: 1,
+            ticks: 50,
+            survival_time: 45.,
+            final_score: 200,
+            peak_lit_tiles: 20,
+            peak_score_per_tick: 30,
+            ..Default::default()
+        };
+
+        lifetime_stats.add(&first);
+        lifetime_stats.add(&second);
+
+        assert_eq!(lifetime_stats, LifetimeStats {
+            games_played: 2,
+            clicks: 15,
+            wasted_clicks: 3,
+            ticks: 150,
+            play_time: 75.,
+            best_score: 500,
+            longest_survival_time: 45.,
+            peak_lit_tiles: 20,
+            peak_score_per_tick: 40,
+        });
+    }
+}
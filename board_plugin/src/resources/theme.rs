@@ -0,0 +1,236 @@
+use crate::resources::{Player, TileKind, MAX_LIGHTNESS};
+use bevy::prelude::Color;
+use serde::{Deserialize, Serialize};
+
+/// A named set of colours for the board, the background and the UI.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Palette {
+    /// Black to white, as the game has always looked.
+    #[default]
+    Classic,
+    /// Glowing orange, like an old terminal.
+    Amber,
+    /// Purple through magenta to cyan.
+    Neon,
+    /// Blue to orange to yellow, which stay apart for every common kind of colour blindness.
+    HighContrast,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 4] = [Palette::Classic, Palette::Amber, Palette::Neon, Palette::HighContrast];
+
+    /// The id of the palette's name in the locale files.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Palette::Classic => "theme-classic",
+            Palette::Amber => "theme-amber",
+            Palette::Neon => "theme-neon",
+            Palette::HighContrast => "theme-high-contrast",
+        }
+    }
+
+    /// The palette after this one, wrapping around to the first.
+    pub fn next(&self) -> Palette {
+        let index = Palette::ALL.iter().position(|palette| { palette == self }).unwrap_or(0);
+        Palette::ALL[(index + 1) % Palette::ALL.len()]
+    }
+
+    fn colors(&self) -> &'static PaletteColors {
+        match self {
+            Palette::Classic => &CLASSIC,
+            Palette::Amber => &AMBER,
+            Palette::Neon => &NEON,
+            Palette::HighContrast => &HIGH_CONTRAST,
+        }
+    }
+}
+
+/// The colours of one palette, as RGB.
+struct PaletteColors {
+    /// Evenly spaced colours that lightness is blended across, from dark to fully lit.
+    gradient: &'static [[f32; 3]],
+    wall: [f32; 3],
+    background: [f32; 3],
+    /// Text and button borders.
+    foreground: [f32; 3],
+    /// Secondary text, such as help and locked achievements.
+    dim: [f32; 3],
+    /// Text for things that can't be used yet.
+    faint: [f32; 3],
+}
+
+const CLASSIC: PaletteColors = PaletteColors {
+    gradient: &[[0., 0., 0.], [1., 1., 1.]],
+    wall: [0.15, 0.1, 0.1],
+    background: [0., 0., 0.],
+    foreground: [1., 1., 1.],
+    dim: [0.5, 0.5, 0.5],
+    faint: [0.25, 0.25, 0.25],
+};
+
+const AMBER: PaletteColors = PaletteColors {
+    gradient: &[[0., 0., 0.], [0.3, 0.1, 0.], [1., 0.6, 0.], [1., 0.95, 0.7]],
+    wall: [0.12, 0.1, 0.15],
+    background: [0.04, 0.02, 0.],
+    foreground: [1., 0.75, 0.3],
+    dim: [0.6, 0.4, 0.15],
+    faint: [0.3, 0.2, 0.08],
+};
+
+const NEON: PaletteColors = PaletteColors {
+    gradient: &[[0., 0., 0.], [0.2, 0., 0.4], [1., 0., 0.8], [0., 1., 1.]],
+    wall: [0.15, 0.15, 0.1],
+    background: [0.02, 0., 0.05],
+    foreground: [0., 1., 1.],
+    dim: [0.8, 0.2, 0.8],
+    faint: [0.35, 0.1, 0.4],
+};
+
+const HIGH_CONTRAST: PaletteColors = PaletteColors {
+    gradient: &[[0., 0., 0.], [0., 0.45, 0.7], [0.9, 0.6, 0.], [0.95, 0.9, 0.25], [1., 1., 1.]],
+    wall: [0.8, 0.0, 0.4],
+    background: [0., 0., 0.],
+    foreground: [1., 1., 1.],
+    dim: [0.95, 0.9, 0.25],
+    faint: [0.35, 0.7, 0.9],
+};
+
+/// The palette everything is drawn with. It's saved, and changing it recolours the game at once.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Theme {
+    pub palette: Palette,
+}
+
+impl Theme {
+    pub const SAVE_NAME: &'static str = "theme";
+
+    pub fn background(&self) -> Color {
+        to_color(self.palette.colors().background)
+    }
+
+    pub fn tile_color(&self, lightness: u8, kind: TileKind, owner: Option<Player>) -> Color {
+        self.tile_color_at(lightness as f32, kind, owner)
+    }
+
+    /// The colour of a tile whose lightness is between whole steps, as it is while fading.
+    pub fn tile_color_at(&self, lightness: f32, kind: TileKind, owner: Option<Player>) -> Color {
+        let colors = self.palette.colors();
+
+        if kind == TileKind::Wall {
+            return to_color(colors.wall);
+        }
+
+        let c = lightness / (MAX_LIGHTNESS as f32);
+
+        // Players keep their own colours in every palette, so they can always be told apart.
+        if let Some(owner) = owner {
+            return Color::hsl(owner.hue(), 0.8, c * 0.7);
+        }
+
+        sample_gradient(colors.gradient, c)
+    }
+
+    /// Converts a UI colour from the classic palette, which the UI is built with, into this
+    /// palette. Colours other than black, white and grays are left alone.
+    pub fn ui_color(&self, classic_color: Color) -> Color {
+        let colors = self.palette.colors();
+
+        let rgb = match classic_color.as_rgba_f32() {
+            [r, g, b, _] if [r, g, b] == CLASSIC.foreground => colors.foreground,
+            [r, g, b, _] if [r, g, b] == CLASSIC.dim => colors.dim,
+            [r, g, b, _] if [r, g, b] == CLASSIC.faint => colors.faint,
+            [r, g, b, _] if [r, g, b] == CLASSIC.background => colors.background,
+            _ => return classic_color,
+        };
+
+        let [r, g, b] = rgb;
+        Color::rgba(r, g, b, classic_color.a())
+    }
+}
+
+/// Blends between the two gradient stops either side of `t`, which runs from 0 to 1.
+fn sample_gradient(gradient: &[[f32; 3]], t: f32) -> Color {
+    let position = t.clamp(0., 1.) * (gradient.len() - 1) as f32;
+    let index = (position as usize).min(gradient.len() - 2);
+    let blend = position - index as f32;
+
+    let [r0, g0, b0] = gradient[index];
+    let [r1, g1, b1] = gradient[index + 1];
+
+    Color::rgb(
+        r0 + (r1 - r0) * blend,
+        g0 + (g1 - g0) * blend,
+        b0 + (b1 - b0) * blend,
+    )
+}
+
+fn to_color([r, g, b]: [f32; 3]) -> Color {
+    Color::rgb(r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_rgb(color: Color, [r, g, b]: [f32; 3]) {
+        let [actual_r, actual_g, actual_b, _] = color.as_rgba_f32();
+        let is_close = [(actual_r, r), (actual_g, g), (actual_b, b)]
+            .iter()
+            .all(|(actual, expected)| { (actual - expected).abs() < 1e-5 });
+
+        assert!(is_close, "{:?} isn't {:?}", color, [r, g, b]);
+    }
+
+    #[test]
+    fn gradients_start_and_end_on_their_stops() {
+        for palette in Palette::ALL {
+            let gradient = palette.colors().gradient;
+
+            assert_rgb(sample_gradient(gradient, 0.), gradient[0]);
+            assert_rgb(sample_gradient(gradient, 1.), gradient[gradient.len() - 1]);
+            assert_rgb(sample_gradient(gradient, -1.), gradient[0]);
+            assert_rgb(sample_gradient(gradient, 2.), gradient[gradient.len() - 1]);
+        }
+    }
+
+    #[test]
+    fn gradients_blend_between_neighbouring_stops() {
+        // Amber has four stops, a third apart.
+        let gradient = AMBER.gradient;
+
+        assert_rgb(sample_gradient(gradient, 1. / 3.), gradient[1]);
+        assert_rgb(sample_gradient(gradient, 0.5), [0.65, 0.35, 0.]);
+        assert_rgb(sample_gradient(gradient, 1. / 6.), [0.15, 0.05, 0.]);
+    }
+
+    #[test]
+    fn classic_ui_colors_take_the_palettes_colors() {
+        let theme = Theme { palette: Palette::Neon };
+
+        assert_rgb(theme.ui_color(Color::WHITE), NEON.foreground);
+        assert_rgb(theme.ui_color(Color::rgb(0.5, 0.5, 0.5)), NEON.dim);
+        assert_rgb(theme.ui_color(Color::rgb(0.25, 0.25, 0.25)), NEON.faint);
+        assert_rgb(theme.ui_color(Color::BLACK), NEON.background);
+
+        // Transparency is kept.
+        assert_eq!(theme.ui_color(Color::rgba(1., 1., 1., 0.5)).a(), 0.5);
+    }
+
+    #[test]
+    fn other_ui_colors_are_left_alone() {
+        let theme = Theme { palette: Palette::Amber };
+
+        for color in [Color::RED, Color::rgb(0.75, 0.75, 0.75), Color::rgba(0.2, 0.4, 0.6, 0.8)] {
+            assert_eq!(theme.ui_color(color), color);
+        }
+    }
+
+    #[test]
+    fn the_classic_palette_changes_nothing() {
+        let theme = Theme::default();
+
+        for color in [Color::WHITE, Color::BLACK, Color::rgb(0.5, 0.5, 0.5), Color::rgb(0.25, 0.25, 0.25)] {
+            assert_eq!(theme.ui_color(color), color);
+        }
+    }
+}
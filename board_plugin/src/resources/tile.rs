@@ -1,5 +1,8 @@
 use crate::resources::coordinates::Coordinates;
+use crate::resources::Player;
 use bevy::utils::{HashMap, HashSet};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::ops::{Deref, DerefMut};
 
 #[cfg(feature = "debug")]
@@ -19,7 +22,7 @@ pub const NEIGHBOUR_OFFSETS: [(i32, i32); 8] = [
     (1, 1),
 ];
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TileMap {
     active_tiles: HashMap<Coordinates, Tile>
 }
@@ -43,35 +46,191 @@ impl TileMap {
         }
     }
 
+    /// Creates a starting board like `new`, with the lightness of each gray tile chosen by `rng`.
+    pub fn generate<R: Rng>(rng: &mut R) -> Self {
+        let mut tile_map = Self::new();
+
+        // Hash map order changes between runs, so tiles are visited in order to keep seeds stable.
+        let mut coordinates: Vec<Coordinates> = tile_map.active_tiles.keys().copied().collect();
+        coordinates.sort();
+
+        for coordinates in coordinates {
+            let tile = tile_map.active_tiles.get_mut(&coordinates).unwrap();
+            if !tile.is_white() {
+                tile.lightness = rng.gen_range(1..MAX_LIGHTNESS);
+            }
+        }
+
+        tile_map
+    }
+
+    pub fn from_tiles(tiles: impl IntoIterator<Item = (Coordinates, Tile)>) -> Self {
+        Self {
+            active_tiles: HashMap::from_iter(tiles),
+        }
+    }
+
+    /// The score earned by the board in one tick.
+    pub fn tick_score(&self) -> u64 {
+        self.non_black_tiles()
+            .into_iter()
+            .map(|tile| { tile.tick_score() })
+            .sum()
+    }
+
+    /// Whether any tile is still lit and fading. Lamps never fade, so a board lit only by lamps
+    /// can't change without a trigger, and the game is over.
+    pub fn has_fading_light(&self) -> bool {
+        self.active_tiles.values().any(|tile| { tile.is_not_black() && tile.kind != TileKind::Lamp })
+    }
+
+    /// The score earned in one tick by the tiles `player` owns.
+    pub fn tick_score_for(&self, player: Player) -> u64 {
+        self.non_black_tiles()
+            .into_iter()
+            .filter(|tile| { tile.owner == Some(player) })
+            .map(|tile| { tile.tick_score() })
+            .sum()
+    }
+
+    pub fn lit_tile_count_for(&self, player: Player) -> usize {
+        self.non_black_tiles()
+            .into_iter()
+            .filter(|tile| { tile.owner == Some(player) })
+            .count()
+    }
+
     pub fn tick_update(&mut self) {
         for tile in self.non_black_tiles_mut() {
             tile.lightness -= 1;
+
+            // Light that has gone out belongs to nobody.
+            if tile.is_black() {
+                tile.owner = None;
+            }
         }
     }
 
-    pub fn make_tile_white(&mut self, x: i32, y: i32) {
+    /// Returns `true` if the tile was lit, or `false` if the trigger had no effect.
+    pub fn make_tile_white(&mut self, x: i32, y: i32) -> bool {
+        self.make_tile_white_as(x, y, None)
+    }
+
+    /// Like `make_tile_white`, but the tile and the neighbours it lights are taken by `owner`.
+    /// Tiles another player owns can't be triggered, only taken by lighting next to them.
+    pub fn make_tile_white_as(&mut self, x: i32, y: i32, owner: Option<Player>) -> bool {
         let coordinates: Coordinates = (x, y).into();
 
         if let Some(tile) = self.active_tiles.get_mut(&coordinates) {
-            if tile.is_black() {
-                return;
+            if tile.is_black() || tile.kind != TileKind::Normal {
+                return false;
+            }
+
+            if tile.owner.is_some() && tile.owner != owner {
+                return false;
             }
 
             tile.lightness = MAX_LIGHTNESS;
+            tile.owner = owner;
 
             for offset in NEIGHBOUR_OFFSETS {
                 let neighbour_coords = coordinates + offset.into();
                 let neighbour = self.get_or_create_tile(neighbour_coords);
+                if neighbour.kind != TileKind::Normal {
+                    continue;
+                }
 
                 neighbour.lightness = ((neighbour.lightness as u16 + MAX_LIGHTNESS as u16) / 2) as u8;
+                neighbour.owner = owner;
+            }
+
+            return true;
+        }
+
+        false
+    }
+
+    /// Returns `true` if lit tiles completely surround at least one unlit space, so it can't reach
+    /// the edge of the board without crossing a lit tile.
+    pub fn has_closed_loop(&self) -> bool {
+        !self.enclosed_tiles().is_empty()
+    }
+
+    /// The unlit spaces that lit tiles completely surround, whether or not a tile has been created
+    /// there yet.
+    pub fn enclosed_tiles(&self) -> HashSet<Coordinates> {
+        let lit = self.lit_coordinates();
+
+        // The smallest loop is four tiles touching diagonally around a single space.
+        if lit.len() < 4 {
+            return HashSet::default();
+        }
+
+        let min_x = lit.iter().map(|c| { c.x }).min().unwrap() - 1;
+        let max_x = lit.iter().map(|c| { c.x }).max().unwrap() + 1;
+        let min_y = lit.iter().map(|c| { c.y }).min().unwrap() - 1;
+        let max_y = lit.iter().map(|c| { c.y }).max().unwrap() + 1;
+
+        // Flood the unlit space from a corner outside the lit tiles. Light can block diagonally, so
+        // the flood only moves orthogonally.
+        let start: Coordinates = (min_x, min_y).into();
+        let mut reached = HashSet::default();
+        reached.insert(start);
+        let mut stack = vec![start];
+        while let Some(coordinates) = stack.pop() {
+            for offset in [(0, -1), (-1, 0), (1, 0), (0, 1)] {
+                let next = coordinates + offset.into();
+                if next.x < min_x || next.x > max_x || next.y < min_y || next.y > max_y {
+                    continue;
+                }
+
+                if !lit.contains(&next) && reached.insert(next) {
+                    stack.push(next);
+                }
             }
         }
+
+        (min_x..=max_x)
+            .flat_map(|x| { (min_y..=max_y).map(move |y| { Coordinates::from((x, y)) }) })
+            .filter(|coordinates| { !lit.contains(coordinates) && !reached.contains(coordinates) })
+            .collect()
+    }
+
+    /// The smallest and largest coordinates of lit tiles, or `None` if every tile is dark.
+    pub fn lit_bounds(&self) -> Option<(Coordinates, Coordinates)> {
+        Coordinates::bounds(&self.lit_coordinates())
+    }
+
+    /// Groups of lit tiles that touch, including diagonally.
+    pub fn lit_clusters(&self) -> Vec<Vec<Coordinates>> {
+        let mut unvisited = self.lit_coordinates();
+        let mut clusters = vec![];
+
+        while let Some(start) = unvisited.iter().next().copied() {
+            unvisited.remove(&start);
+
+            let mut cluster = vec![start];
+            let mut stack = vec![start];
+            while let Some(coordinates) = stack.pop() {
+                for offset in NEIGHBOUR_OFFSETS {
+                    let next = coordinates + offset.into();
+                    if unvisited.remove(&next) {
+                        cluster.push(next);
+                        stack.push(next);
+                    }
+                }
+            }
+
+            clusters.push(cluster);
+        }
+
+        clusters
     }
 
     pub fn get_new_tiles(&self, existing_tile_coords: HashSet<Coordinates>) -> Vec<&Coordinates> {
         let tiles = &self.active_tiles;
         
-        tiles.into_iter()
+        tiles.iter()
             .filter(|(coord, _)| { !existing_tile_coords.contains(coord) })
             .map(|(coord, _)| { coord })
             .collect()
@@ -84,20 +243,24 @@ impl TileMap {
             .collect()
     }
 
+    fn lit_coordinates(&self) -> HashSet<Coordinates> {
+        self.active_tiles.iter()
+            .filter(|(_, tile)| { tile.is_not_black() })
+            .map(|(coordinates, _)| { *coordinates })
+            .collect()
+    }
+
     fn non_black_tiles_mut(&mut self) -> Vec<&mut Tile> {
         let tiles = &mut self.active_tiles;
         tiles.values_mut()
-            .filter(|tile| { tile.is_not_black() })
+            .filter(|tile| { tile.is_not_black() && tile.kind != TileKind::Lamp })
             .collect()
     }
 
     fn get_or_create_tile(&mut self, coordinates: Coordinates) -> &mut Tile {
-        if !self.active_tiles.contains_key(&coordinates) {
-            let tile = &mut Tile::new_black();
-            self.active_tiles.insert(coordinates, *tile);
-        }
-
-        self.active_tiles.get_mut(&coordinates).unwrap()
+        self.active_tiles
+            .entry(coordinates)
+            .or_insert_with(Tile::new_black)
     }
 
     #[cfg(feature = "debug")]
@@ -111,6 +274,12 @@ impl TileMap {
     }
 }
 
+impl Default for TileMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Deref for TileMap {
     type Target = HashMap<Coordinates, Tile>;
 
@@ -125,9 +294,24 @@ impl DerefMut for TileMap {
     }
 }
 
+#[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum TileKind {
+    /// Fades over time and can be clicked.
+    #[default]
+    Normal,
+    /// Never lit, and blocks light from its neighbours.
+    Wall,
+    /// Always lit, and never fades.
+    Lamp,
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Tile {
     pub lightness: u8,
+    pub kind: TileKind,
+    /// The player whose light this is, in versus games.
+    pub owner: Option<Player>,
 }
 
 impl Tile {
@@ -140,17 +324,28 @@ impl Tile {
     }
 
     pub fn new_gray(lightness: u8) -> Self {
+        Tile::new(lightness, TileKind::Normal)
+    }
+
+    pub fn new(lightness: u8, kind: TileKind) -> Self {
         Self {
-            lightness: lightness,
+            lightness,
+            kind,
+            owner: None,
         }
     }
 
+    /// The score the tile earns in one tick. Lighter tiles are worth more than dark ones.
+    pub fn tick_score(&self) -> u64 {
+        f64::log(self.lightness as f64 + 1., 2.) as u64
+    }
+
     pub fn is_white(&self) -> bool {
-        self.lightness >= MAX_LIGHTNESS
+        self.lightness == MAX_LIGHTNESS
     }
 
     pub fn is_black(&self) -> bool {
-        self.lightness <= 0
+        self.lightness == 0
     }
 
     pub fn is_not_black(&self) -> bool {
@@ -186,4 +381,76 @@ impl Tile {
                 .on_color(get_bg_color(self.lightness))
         )
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit_tile_map(coordinates: &[(i32, i32)]) -> TileMap {
+        let mut tile_map = TileMap::new();
+        tile_map.clear();
+        for coordinates in coordinates {
+            tile_map.insert((*coordinates).into(), Tile::new_white());
+        }
+
+        tile_map
+    }
+
+    fn ring(size: i32) -> Vec<(i32, i32)> {
+        (0..size)
+            .flat_map(|x| { (0..size).map(move |y| { (x, y) }) })
+            .filter(|(x, y)| { *x == 0 || *y == 0 || *x == size - 1 || *y == size - 1 })
+            .collect()
+    }
+
+    fn coordinates(coordinates: &[(i32, i32)]) -> HashSet<Coordinates> {
+        coordinates.iter().map(|coordinates| { (*coordinates).into() }).collect()
+    }
+
+    #[test]
+    fn ring_of_eight_tiles_is_closed() {
+        let tile_map = lit_tile_map(&ring(3));
+
+        assert!(tile_map.has_closed_loop());
+        assert_eq!(tile_map.enclosed_tiles(), coordinates(&[(1, 1)]));
+    }
+
+    #[test]
+    fn ring_with_diagonal_gap_is_closed() {
+        let mut tiles = ring(4);
+        tiles.retain(|tile| { *tile != (3, 3) });
+        let tile_map = lit_tile_map(&tiles);
+
+        assert_eq!(tile_map.enclosed_tiles(), coordinates(&[(1, 1), (1, 2), (2, 1), (2, 2)]));
+    }
+
+    #[test]
+    fn ring_with_orthogonal_gap_is_open() {
+        let mut tiles = ring(4);
+        tiles.retain(|tile| { *tile != (1, 3) });
+        let tile_map = lit_tile_map(&tiles);
+
+        assert!(!tile_map.has_closed_loop());
+    }
+
+    #[test]
+    fn fewer_than_eight_tiles_can_close_a_loop_diagonally() {
+        let diamond = [(0, 1), (1, 0), (1, 2), (2, 1)];
+        assert_eq!(lit_tile_map(&diamond).enclosed_tiles(), coordinates(&[(1, 1)]));
+        assert!(!lit_tile_map(&diamond[..3]).has_closed_loop());
+
+        let mut tiles = ring(3);
+        tiles.retain(|tile| { *tile != (2, 2) });
+        assert_eq!(tiles.len(), 7);
+        assert_eq!(lit_tile_map(&tiles).enclosed_tiles(), coordinates(&[(1, 1)]));
+    }
+
+    #[test]
+    fn dark_tiles_dont_close_a_loop() {
+        let mut tile_map = lit_tile_map(&ring(3));
+        tile_map.insert((1, 0).into(), Tile::new_black());
+
+        assert!(!tile_map.has_closed_loop());
+    }
+}
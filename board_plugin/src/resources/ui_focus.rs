@@ -0,0 +1,47 @@
+use bevy::prelude::{Entity, Vec2};
+use std::cmp::Ordering;
+
+/// Which button keyboard focus is on, as its place in reading order among the buttons on screen.
+/// It's kept as a place rather than an entity so it survives a menu being rebuilt.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct UiFocus(pub Option<usize>);
+
+impl UiFocus {
+    /// Moves focus `offset` buttons along, wrapping around at either end. Focus starts from the
+    /// first button going forwards, or the last going backwards.
+    pub fn move_by(&mut self, offset: isize, button_count: usize) {
+        if button_count == 0 {
+            self.0 = None;
+            return;
+        }
+
+        let count = button_count as isize;
+        let index = match self.0 {
+            Some(index) => (index as isize).min(count - 1) + offset,
+            None if offset < 0 => count + offset,
+            None => offset - 1,
+        };
+
+        self.0 = Some(index.rem_euclid(count) as usize);
+    }
+
+    /// The focused button, from the buttons on screen and their positions.
+    pub fn focused(&self, buttons: impl Iterator<Item = (Entity, Vec2)>) -> Option<Entity> {
+        let index = self.0?;
+        let buttons = reading_order(buttons);
+
+        buttons.get(index.min(buttons.len().checked_sub(1)?)).copied()
+    }
+}
+
+/// Sorts buttons top to bottom, then left to right. UI positions have y pointing up.
+fn reading_order(buttons: impl Iterator<Item = (Entity, Vec2)>) -> Vec<Entity> {
+    let mut buttons: Vec<_> = buttons.collect();
+    buttons.sort_by(|(_, a), (_, b)| {
+        b.y.partial_cmp(&a.y)
+            .unwrap_or(Ordering::Equal)
+            .then(a.x.partial_cmp(&b.x).unwrap_or(Ordering::Equal))
+    });
+
+    buttons.into_iter().map(|(entity, _)| { entity }).collect()
+}
@@ -0,0 +1,195 @@
+use crate::resources::{Coordinates, Tile, TileMap, MAX_LIGHTNESS};
+
+/// How far each player's starting light is from the centre of the board.
+pub const VERSUS_START_OFFSET: i32 = 3;
+
+/// One side of a versus game. Each player owns the light they make.
+#[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Player {
+    /// Plays with the mouse.
+    One,
+    /// Plays with a cursor moved by the arrow keys or a gamepad.
+    Two,
+}
+
+impl Player {
+    pub const ALL: [Player; 2] = [Player::One, Player::Two];
+
+    /// The id of the player's name in the locale files.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Player::One => "player-one",
+            Player::Two => "player-two",
+        }
+    }
+
+    /// The hue of the player's light in degrees: warm for player one, cool for player two.
+    pub fn hue(&self) -> f32 {
+        match self {
+            Player::One => 30.,
+            Player::Two => 200.,
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Player::One => 0,
+            Player::Two => 1,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum VersusOutcome {
+    Winner(Player),
+    /// Both players' light went out on the same tick.
+    Draw,
+}
+
+/// The state of a two-player game on a shared board.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Versus {
+    scores: [u64; 2],
+    /// The tile under player two's cursor.
+    pub cursor: Coordinates,
+    pub outcome: Option<VersusOutcome>,
+}
+
+impl Versus {
+    /// Creates a board with a patch of light for each player, either side of the centre.
+    pub fn generate_tile_map() -> TileMap {
+        let mut tiles = Vec::new();
+
+        for (player, centre_x) in [(Player::One, -VERSUS_START_OFFSET), (Player::Two, VERSUS_START_OFFSET)] {
+            for x in -1..=1 {
+                for y in -1..=1 {
+                    let lightness = if x == 0 && y == 0 { MAX_LIGHTNESS } else { MAX_LIGHTNESS / 2 };
+                    let tile = Tile {
+                        owner: Some(player),
+                        ..Tile::new_gray(lightness)
+                    };
+                    tiles.push(((centre_x + x, y).into(), tile));
+                }
+            }
+        }
+
+        TileMap::from_tiles(tiles)
+    }
+
+    pub fn score(&self, player: Player) -> u64 {
+        self.scores[player.index()]
+    }
+
+    /// Scores one tick for both players, and ends the game once either player's light is gone.
+    pub fn tick(&mut self, tile_map: &TileMap) -> Option<VersusOutcome> {
+        for player in Player::ALL {
+            self.scores[player.index()] += tile_map.tick_score_for(player);
+        }
+
+        let is_out = |player: Player| { tile_map.lit_tile_count_for(player) == 0 };
+
+        self.outcome = match (is_out(Player::One), is_out(Player::Two)) {
+            (true, true) => Some(VersusOutcome::Draw),
+            (true, false) => Some(VersusOutcome::Winner(Player::Two)),
+            (false, true) => Some(VersusOutcome::Winner(Player::One)),
+            (false, false) => None,
+        };
+
+        self.outcome
+    }
+}
+
+impl Default for Versus {
+    fn default() -> Self {
+        Self {
+            scores: [0, 0],
+            cursor: (VERSUS_START_OFFSET, 0).into(),
+            outcome: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owned(lightness: u8, owner: Option<Player>) -> Tile {
+        Tile {
+            owner,
+            ..Tile::new_gray(lightness)
+        }
+    }
+
+    #[test]
+    fn players_only_score_their_own_light() {
+        let tile_map = TileMap::from_tiles([
+            ((0, 0).into(), owned(MAX_LIGHTNESS, Some(Player::One))),
+            ((1, 0).into(), owned(MAX_LIGHTNESS, Some(Player::One))),
+            ((5, 0).into(), owned(3, Some(Player::Two))),
+            ((9, 0).into(), owned(MAX_LIGHTNESS, None)),
+        ]);
+        let mut versus = Versus::default();
+
+        assert_eq!(versus.tick(&tile_map), None);
+        assert_eq!(versus.tick(&tile_map), None);
+
+        let white = Tile::new_white().tick_score();
+        assert_eq!(versus.score(Player::One), 2 * 2 * white);
+        assert_eq!(versus.score(Player::Two), 2 * Tile::new_gray(3).tick_score());
+    }
+
+    #[test]
+    fn owned_tiles_can_only_be_taken_by_lighting_next_to_them() {
+        let mut tile_map = TileMap::from_tiles([
+            ((0, 0).into(), owned(10, Some(Player::One))),
+            ((1, 0).into(), owned(10, Some(Player::Two))),
+            ((5, 0).into(), owned(10, None)),
+        ]);
+
+        // Neither player can trigger the other's tile.
+        assert!(!tile_map.make_tile_white_as(0, 0, Some(Player::Two)));
+        assert!(!tile_map.make_tile_white_as(1, 0, Some(Player::One)));
+        assert_eq!(tile_map[&(0, 0).into()], owned(10, Some(Player::One)));
+
+        // Anyone can trigger unowned light.
+        assert!(tile_map.make_tile_white_as(5, 0, Some(Player::Two)));
+        assert_eq!(tile_map[&(5, 0).into()].owner, Some(Player::Two));
+
+        // Triggering their own tile takes the opponent's neighbour.
+        assert!(tile_map.make_tile_white_as(1, 0, Some(Player::Two)));
+        assert_eq!(tile_map[&(1, 0).into()], owned(MAX_LIGHTNESS, Some(Player::Two)));
+        assert_eq!(tile_map[&(0, 0).into()].owner, Some(Player::Two));
+        assert_eq!(tile_map.lit_tile_count_for(Player::One), 0);
+    }
+
+    #[test]
+    fn the_player_with_light_left_wins() {
+        let mut tile_map = Versus::generate_tile_map();
+        let mut versus = Versus::default();
+        assert_eq!(versus.tick(&tile_map), None);
+
+        // Player two takes all of player one's light.
+        for tile in tile_map.values_mut() {
+            tile.owner = Some(Player::Two);
+        }
+
+        assert_eq!(versus.tick(&tile_map), Some(VersusOutcome::Winner(Player::Two)));
+        assert_eq!(versus.outcome, Some(VersusOutcome::Winner(Player::Two)));
+    }
+
+    #[test]
+    fn light_going_out_together_is_a_draw() {
+        let mut tile_map = TileMap::from_tiles([
+            ((0, 0).into(), owned(1, Some(Player::One))),
+            ((5, 0).into(), owned(1, Some(Player::Two))),
+        ]);
+        let mut versus = Versus::default();
+
+        assert_eq!(versus.tick(&tile_map), None);
+        tile_map.tick_update();
+
+        assert_eq!(versus.tick(&tile_map), Some(VersusOutcome::Draw));
+        assert_eq!(versus.score(Player::One), versus.score(Player::Two));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::resources::HINT_LOOKAHEAD_TICKS;
+use crate::simulation::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The built-in bots, by name.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StrategyKind {
+    Idle,
+    Random,
+    #[default]
+    Greedy,
+    Lookahead,
+}
+
+impl FromStr for StrategyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "idle" => Ok(StrategyKind::Idle),
+            "random" => Ok(StrategyKind::Random),
+            "greedy" => Ok(StrategyKind::Greedy),
+            "lookahead" => Ok(StrategyKind::Lookahead),
+            _ => Err(format!("Unknown strategy \"{}\", expected idle, random, greedy, or lookahead", s)),
+        }
+    }
+}
+
+impl fmt::Display for StrategyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            StrategyKind::Idle => "idle",
+            StrategyKind::Random => "random",
+            StrategyKind::Greedy => "greedy",
+            StrategyKind::Lookahead => "lookahead",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// How to run headless games. Every field can be left out of a config file.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+    /// The seed of the first game. Each further game uses the next seed.
+    pub seed: u64,
+    pub games: u64,
+    pub strategy: StrategyKind,
+    /// Games still going after this many ticks are stopped.
+    pub ticks: u64,
+    /// The number of ticks between triggers for the built-in bots.
+    pub interval: u64,
+    /// How many ticks ahead the lookahead bot simulates.
+    pub lookahead_ticks: u32,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            games: 1,
+            strategy: StrategyKind::default(),
+            ticks: 3000,
+            interval: DEFAULT_TRIGGER_INTERVAL,
+            lookahead_ticks: HINT_LOOKAHEAD_TICKS,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Creates the configured bot for the game with `seed`.
+    pub fn build_strategy(&self, seed: u64) -> Box<dyn Strategy> {
+        match self.strategy {
+            StrategyKind::Idle => Box::new(IdleStrategy),
+            StrategyKind::Random => Box::new(RandomStrategy::new(self.interval, seed)),
+            StrategyKind::Greedy => Box::new(GreedyStrategy {
+                interval: self.interval,
+            }),
+            StrategyKind::Lookahead => Box::new(LookaheadStrategy {
+                interval: self.interval,
+                ticks: self.lookahead_ticks,
+            }),
+        }
+    }
+
+    pub fn seeds(&self) -> Vec<u64> {
+        (0..self.games).map(|i| { self.seed.wrapping_add(i) }).collect()
+    }
+}
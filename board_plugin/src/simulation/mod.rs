@@ -0,0 +1,9 @@
+pub use config::*;
+pub use replay::*;
+pub use runner::*;
+pub use strategy::*;
+
+mod config;
+mod replay;
+mod runner;
+mod strategy;
\ No newline at end of file
@@ -0,0 +1,103 @@
+use crate::resources::{Coordinates, TileMap};
+use crate::simulation::Strategy;
+use serde::{Deserialize, Serialize};
+
+/// A recorded game: the seed of its starting board, and every trigger in order.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub triggers: Vec<ReplayTrigger>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReplayTrigger {
+    pub tick: u64,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Replay {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            triggers: vec![],
+        }
+    }
+
+    pub fn record(&mut self, tick: u64, coordinates: Coordinates) {
+        self.triggers.push(ReplayTrigger {
+            tick,
+            x: coordinates.x,
+            y: coordinates.y,
+        });
+    }
+}
+
+/// Wraps another strategy, recording every trigger it makes so the game can be replayed.
+pub struct RecordingStrategy<'a> {
+    strategy: &'a mut dyn Strategy,
+    pub replay: Replay,
+}
+
+impl<'a> RecordingStrategy<'a> {
+    pub fn new(strategy: &'a mut dyn Strategy, seed: u64) -> Self {
+        Self {
+            strategy,
+            replay: Replay::new(seed),
+        }
+    }
+}
+
+impl Strategy for RecordingStrategy<'_> {
+    fn name(&self) -> &str {
+        self.strategy.name()
+    }
+
+    fn choose(&mut self, tile_map: &TileMap, tick: u64) -> Vec<Coordinates> {
+        let triggers = self.strategy.choose(tile_map, tick);
+        for coordinates in triggers.iter() {
+            self.replay.record(tick, *coordinates);
+        }
+
+        triggers
+    }
+}
+
+/// Plays back the triggers of a replay on the ticks they were recorded.
+#[derive(Debug, Clone)]
+pub struct ReplayStrategy {
+    replay: Replay,
+    next: usize,
+}
+
+impl ReplayStrategy {
+    pub fn new(mut replay: Replay) -> Self {
+        replay.triggers.sort_by_key(|trigger| { trigger.tick });
+
+        Self {
+            replay,
+            next: 0,
+        }
+    }
+}
+
+impl Strategy for ReplayStrategy {
+    fn name(&self) -> &str {
+        "replay"
+    }
+
+    fn choose(&mut self, _tile_map: &TileMap, tick: u64) -> Vec<Coordinates> {
+        let mut triggers = vec![];
+
+        while let Some(trigger) = self.replay.triggers.get(self.next) {
+            if trigger.tick > tick {
+                break;
+            }
+
+            triggers.push((trigger.x, trigger.y).into());
+            self.next += 1;
+        }
+
+        triggers
+    }
+}
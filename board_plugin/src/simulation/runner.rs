@@ -0,0 +1,318 @@
+use crate::resources::TileMap;
+use crate::simulation::Strategy;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::Serialize;
+use std::fmt;
+use std::thread;
+
+/// A game of endless mode played without a window, one tick at a time.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    pub seed: u64,
+    pub tile_map: TileMap,
+    pub score: u64,
+    pub tick: u64,
+    pub triggers: u64,
+    pub wasted_triggers: u64,
+    pub is_over: bool,
+}
+
+/// What happened during one tick of a simulation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+pub struct TickStats {
+    pub tick: u64,
+    pub triggers: u64,
+    pub score_increment: u64,
+    pub score: u64,
+    pub lit_tiles: usize,
+}
+
+/// How a simulated game ended.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct SimulationResult {
+    pub seed: u64,
+    pub strategy: String,
+    pub score: u64,
+    pub ticks: u64,
+    pub triggers: u64,
+    pub wasted_triggers: u64,
+    /// Whether the board was still lit when the tick limit was reached.
+    pub survived: bool,
+}
+
+impl Simulation {
+    /// Starts a game on a board generated from `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self::with_tile_map(seed, TileMap::generate(&mut StdRng::seed_from_u64(seed)))
+    }
+
+    pub fn with_tile_map(seed: u64, tile_map: TileMap) -> Self {
+        Self {
+            seed,
+            tile_map,
+            score: 0,
+            tick: 0,
+            triggers: 0,
+            wasted_triggers: 0,
+            is_over: false,
+        }
+    }
+
+    /// Plays one tick: triggers the tiles chosen by `strategy`, then scores the board and lets it
+    /// fade, as the game does. The game is over once a tick scores nothing.
+    pub fn step(&mut self, strategy: &mut dyn Strategy) -> TickStats {
+        let mut triggers = 0;
+        for coordinates in strategy.choose(&self.tile_map, self.tick) {
+            triggers += 1;
+            if !self.tile_map.make_tile_white(coordinates.x, coordinates.y) {
+                self.wasted_triggers += 1;
+            }
+        }
+        self.triggers += triggers;
+
+        let score_increment = self.tile_map.tick_score();
+        self.score += score_increment;
+        self.is_over = !self.tile_map.has_fading_light();
+
+        let stats = TickStats {
+            tick: self.tick,
+            triggers,
+            score_increment,
+            score: self.score,
+            lit_tiles: self.tile_map.non_black_tiles().len(),
+        };
+
+        self.tile_map.tick_update();
+        self.tick += 1;
+
+        stats
+    }
+
+    /// Plays until the game is over or `max_ticks` ticks have passed.
+    pub fn run(&mut self, strategy: &mut dyn Strategy, max_ticks: u64) -> SimulationResult {
+        while !self.is_over && self.tick < max_ticks {
+            self.step(strategy);
+        }
+
+        self.result(strategy)
+    }
+
+    pub fn result(&self, strategy: &dyn Strategy) -> SimulationResult {
+        SimulationResult {
+            seed: self.seed,
+            strategy: strategy.name().to_string(),
+            score: self.score,
+            ticks: self.tick,
+            triggers: self.triggers,
+            wasted_triggers: self.wasted_triggers,
+            survived: !self.is_over,
+        }
+    }
+}
+
+/// Plays a game for each seed, spread over every available core. `make_strategy` creates a fresh
+/// bot for each game, given its seed. Results are in the same order as `seeds`.
+pub fn run_batch<F>(seeds: &[u64], max_ticks: u64, make_strategy: F) -> Vec<SimulationResult>
+where
+    F: Fn(u64) -> Box<dyn Strategy> + Sync,
+{
+    let threads = thread::available_parallelism().map_or(1, |threads| { threads.get() });
+    let chunk_size = seeds.len().div_ceil(threads).max(1);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = seeds.chunks(chunk_size)
+            .map(|chunk| {
+                let make_strategy = &make_strategy;
+                scope.spawn(move || {
+                    chunk.iter()
+                        .map(|seed| {
+                            let mut strategy = make_strategy(*seed);
+                            Simulation::new(*seed).run(strategy.as_mut(), max_ticks)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        handles.into_iter()
+            .flat_map(|handle| { handle.join().expect("Simulation thread panicked") })
+            .collect()
+    })
+}
+
+/// A summary of how a value, such as the score, varied over a batch of games.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Distribution {
+    pub count: usize,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub p10: u64,
+    pub median: u64,
+    pub p90: u64,
+}
+
+impl Distribution {
+    /// Returns `None` if there are no values.
+    pub fn new(values: impl IntoIterator<Item = u64>) -> Option<Self> {
+        let mut values: Vec<u64> = values.into_iter().collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_unstable();
+
+        let count = values.len();
+        let mean = values.iter().map(|value| { *value as f64 }).sum::<f64>() / count as f64;
+        let variance = values.iter()
+            .map(|value| { (*value as f64 - mean).powi(2) })
+            .sum::<f64>() / count as f64;
+        let percentile = |p: usize| { values[(count - 1) * p / 100] };
+
+        Some(Self {
+            count,
+            min: values[0],
+            max: values[count - 1],
+            mean,
+            std_dev: variance.sqrt(),
+            p10: percentile(10),
+            median: percentile(50),
+            p90: percentile(90),
+        })
+    }
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {}, p10 {}, median {}, p90 {}, max {}, mean {:.1} ± {:.1} (n = {})",
+            self.min,
+            self.p10,
+            self.median,
+            self.p90,
+            self.max,
+            self.mean,
+            self.std_dev,
+            self.count,
+        )
+    }
+}
+
+/// The score and survival distributions of a batch of games played by one strategy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchReport {
+    pub strategy: String,
+    pub scores: Distribution,
+    pub ticks: Distribution,
+    pub triggers: Distribution,
+    pub survival_rate: f64,
+}
+
+impl BatchReport {
+    /// Returns `None` if there are no results.
+    pub fn new(results: &[SimulationResult]) -> Option<Self> {
+        let first = results.first()?;
+
+        Some(Self {
+            strategy: first.strategy.clone(),
+            scores: Distribution::new(results.iter().map(|result| { result.score }))?,
+            ticks: Distribution::new(results.iter().map(|result| { result.ticks }))?,
+            triggers: Distribution::new(results.iter().map(|result| { result.triggers }))?,
+            survival_rate: results.iter().filter(|result| { result.survived }).count() as f64 / results.len() as f64,
+        })
+    }
+}
+
+impl fmt::Display for BatchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Strategy: {}", self.strategy)?;
+        writeln!(f, "  Score:    {}", self.scores)?;
+        writeln!(f, "  Ticks:    {}", self.ticks)?;
+        writeln!(f, "  Triggers: {}", self.triggers)?;
+        write!(f, "  Survived: {:.1}%", self.survival_rate * 100.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{SimulationConfig, StrategyKind, DEFAULT_TRIGGER_INTERVAL};
+
+    const SEEDS: [u64; 6] = [0, 1, 2, 42, 1234, u64::MAX];
+    const MAX_TICKS: u64 = 200;
+
+    const STRATEGIES: [StrategyKind; 4] = [
+        StrategyKind::Idle,
+        StrategyKind::Random,
+        StrategyKind::Greedy,
+        StrategyKind::Lookahead,
+    ];
+
+    fn run(strategy: StrategyKind) -> Vec<SimulationResult> {
+        let config = SimulationConfig {
+            strategy,
+            ..Default::default()
+        };
+
+        run_batch(&SEEDS, MAX_TICKS, |seed| { config.build_strategy(seed) })
+    }
+
+    #[test]
+    fn strategies_are_deterministic_for_a_seed() {
+        for strategy in STRATEGIES {
+            let results = run(strategy);
+            assert_eq!(results, run(strategy), "{} isn't deterministic", strategy);
+
+            let seeds: Vec<_> = results.iter().map(|result| { result.seed }).collect();
+            assert_eq!(seeds, SEEDS);
+        }
+    }
+
+    #[test]
+    fn strategy_results_are_in_range() {
+        for strategy in STRATEGIES {
+            let results = run(strategy);
+
+            for result in &results {
+                assert_eq!(result.strategy, strategy.to_string());
+                assert!(result.ticks <= MAX_TICKS);
+                assert!(result.wasted_triggers <= result.triggers);
+                assert!(result.triggers <= result.ticks.div_ceil(DEFAULT_TRIGGER_INTERVAL));
+                assert_eq!(result.survived, result.ticks == MAX_TICKS);
+            }
+
+            let report = BatchReport::new(&results).unwrap();
+            for distribution in [report.scores, report.ticks, report.triggers] {
+                assert_eq!(distribution.count, SEEDS.len());
+                assert!(distribution.min <= distribution.p10);
+                assert!(distribution.p10 <= distribution.median);
+                assert!(distribution.median <= distribution.p90);
+                assert!(distribution.p90 <= distribution.max);
+                assert!(distribution.min as f64 <= distribution.mean && distribution.mean <= distribution.max as f64);
+            }
+            assert!((0. ..=1.).contains(&report.survival_rate));
+        }
+    }
+
+    #[test]
+    fn idle_strategy_never_triggers() {
+        for result in run(StrategyKind::Idle) {
+            assert_eq!(result.triggers, 0);
+        }
+    }
+
+    #[test]
+    fn distribution_percentiles() {
+        let distribution = Distribution::new((1..=10).rev()).unwrap();
+
+        assert_eq!(distribution.count, 10);
+        assert_eq!((distribution.min, distribution.max), (1, 10));
+        assert_eq!((distribution.p10, distribution.median, distribution.p90), (1, 5, 9));
+        assert_eq!(distribution.mean, 5.5);
+
+        assert_eq!(Distribution::new([]), None);
+        assert_eq!(BatchReport::new(&[]), None);
+    }
+}
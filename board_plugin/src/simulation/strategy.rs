@@ -0,0 +1,131 @@
+use crate::resources::{Coordinates, Hint, TileMap, HINT_LOOKAHEAD_TICKS};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// The number of ticks between triggers for the built-in bots, about once a second in the game.
+pub const DEFAULT_TRIGGER_INTERVAL: u64 = 10;
+
+/// A bot that plays the game. Each tick it's shown the board, and chooses the tiles to trigger
+/// before the board is scored and fades.
+pub trait Strategy {
+    fn name(&self) -> &str;
+
+    fn choose(&mut self, tile_map: &TileMap, tick: u64) -> Vec<Coordinates>;
+}
+
+/// Never triggers anything, to show how long the starting board lasts on its own.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct IdleStrategy;
+
+impl Strategy for IdleStrategy {
+    fn name(&self) -> &str {
+        "idle"
+    }
+
+    fn choose(&mut self, _tile_map: &TileMap, _tick: u64) -> Vec<Coordinates> {
+        vec![]
+    }
+}
+
+/// Triggers a random lit tile every `interval` ticks.
+#[derive(Debug, Clone)]
+pub struct RandomStrategy {
+    pub interval: u64,
+    rng: StdRng,
+}
+
+impl RandomStrategy {
+    pub fn new(interval: u64, seed: u64) -> Self {
+        Self {
+            interval,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn choose(&mut self, tile_map: &TileMap, tick: u64) -> Vec<Coordinates> {
+        if !is_turn(tick, self.interval) {
+            return vec![];
+        }
+
+        let candidates = Hint::candidates(tile_map);
+        if candidates.is_empty() {
+            return vec![];
+        }
+
+        vec![candidates[self.rng.gen_range(0..candidates.len())]]
+    }
+}
+
+/// Every `interval` ticks, triggers the tile that earns the most on the very next tick.
+#[derive(Debug, Copy, Clone)]
+pub struct GreedyStrategy {
+    pub interval: u64,
+}
+
+impl Default for GreedyStrategy {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_TRIGGER_INTERVAL,
+        }
+    }
+}
+
+impl Strategy for GreedyStrategy {
+    fn name(&self) -> &str {
+        "greedy"
+    }
+
+    fn choose(&mut self, tile_map: &TileMap, tick: u64) -> Vec<Coordinates> {
+        if !is_turn(tick, self.interval) {
+            return vec![];
+        }
+
+        Hint::find(tile_map, 1)
+            .map(|hint| { vec![hint.coordinates] })
+            .unwrap_or_default()
+    }
+}
+
+/// Every `interval` ticks, triggers the tile that earns the most over the next `ticks` ticks, as the
+/// in-game hint does.
+#[derive(Debug, Copy, Clone)]
+pub struct LookaheadStrategy {
+    pub interval: u64,
+    pub ticks: u32,
+}
+
+impl Default for LookaheadStrategy {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_TRIGGER_INTERVAL,
+            ticks: HINT_LOOKAHEAD_TICKS,
+        }
+    }
+}
+
+impl Strategy for LookaheadStrategy {
+    fn name(&self) -> &str {
+        "lookahead"
+    }
+
+    fn choose(&mut self, tile_map: &TileMap, tick: u64) -> Vec<Coordinates> {
+        if !is_turn(tick, self.interval) {
+            return vec![];
+        }
+
+        Hint::find(tile_map, self.ticks)
+            .map(|hint| { vec![hint.coordinates] })
+            .unwrap_or_default()
+    }
+}
+
+/// Whether a bot that triggers every `interval` ticks gets to trigger on `tick`.
+fn is_turn(tick: u64, interval: u64) -> bool {
+    tick.is_multiple_of(interval.max(1))
+}
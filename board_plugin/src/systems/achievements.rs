@@ -0,0 +1,127 @@
+use crate::components::{AchievementToast, UiRoot};
+use crate::events::*;
+use crate::persistence;
+use crate::resources::{Achievement, AchievementProgress, Board, GameMode, GameStats, Locale};
+use bevy::log;
+use bevy::prelude::*;
+
+const TOAST_SECONDS: f32 = 4.;
+const TOAST_HEIGHT: f32 = 64.;
+
+pub fn load_achievements(
+    mut commands: Commands,
+) {
+    commands.insert_resource(persistence::load::<AchievementProgress>(AchievementProgress::SAVE_NAME));
+}
+
+pub fn check_achievements(
+    game_mode: Res<GameMode>,
+    board: Res<Board>,
+    game_stats: Res<GameStats>,
+    mut achievement_progress: ResMut<AchievementProgress>,
+    mut tick_evr: EventReader<TickEvent>,
+    mut achievement_unlocked_evw: EventWriter<AchievementUnlockedEvent>,
+) {
+    // Remote games are shared, so they aren't any one player's achievement.
+    if tick_evr.iter().count() == 0 || game_mode.is_remote() {
+        return;
+    }
+
+    let unlocked: Vec<Achievement> = Achievement::ALL.into_iter()
+        .filter(|achievement| { !achievement_progress.is_unlocked(*achievement) })
+        .filter(|achievement| { achievement.is_met(&game_stats, &board.tile_map) })
+        .collect();
+
+    if unlocked.is_empty() {
+        return;
+    }
+
+    for achievement in unlocked {
+        log::info!("Unlocked achievement {:?}", achievement);
+        achievement_progress.unlock(achievement);
+        achievement_unlocked_evw.send(AchievementUnlockedEvent(achievement));
+    }
+
+    persistence::save(AchievementProgress::SAVE_NAME, &*achievement_progress);
+}
+
+pub fn show_toasts(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    ui_root_query: Query<&UiRoot>,
+    toast_query: Query<&AchievementToast>,
+    mut achievement_unlocked_evr: EventReader<AchievementUnlockedEvent>,
+) {
+    let ui_root = ui_root_query.single();
+    let font = asset_server.load("fonts/Lato/Lato-Light.ttf");
+
+    // New toasts stack above the ones still showing.
+    let toast_count = toast_query.iter().count();
+
+    for (i, event) in (toast_count..).zip(achievement_unlocked_evr.iter()) {
+        let achievement = event.0;
+
+        commands.entity(ui_root.0).with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        position: Rect {
+                            right: Val::Px(16.),
+                            bottom: Val::Px(16. + i as f32 * (TOAST_HEIGHT + 8.)),
+                            ..Default::default()
+                        },
+                        size: Size::new(Val::Px(320.), Val::Px(TOAST_HEIGHT)),
+                        padding: Rect::all(Val::Px(2.)),
+                        ..Default::default()
+                    },
+                    color: Color::WHITE.into(),
+                    ..Default::default()
+                })
+                .insert(Name::new(format!("{:?} Toast", achievement)))
+                .insert(AchievementToast(Timer::from_seconds(TOAST_SECONDS, false)))
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            color: Color::BLACK.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn_bundle(TextBundle {
+                                text: Text::with_section(
+                                    locale.format("achievement-unlocked", &[
+                                        ("title", achievement.title(&locale).to_uppercase()),
+                                    ]),
+                                    TextStyle {
+                                        font: font.clone(),
+                                        font_size: 24.,
+                                        color: Color::WHITE,
+                                    },
+                                    Default::default()
+                                ),
+                                ..Default::default()
+                            });
+                        });
+                });
+        });
+    }
+}
+
+pub fn update_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut toast_query: Query<(Entity, &mut AchievementToast)>,
+) {
+    for (entity, mut toast) in toast_query.iter_mut() {
+        if toast.0.tick(time.delta()).just_finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
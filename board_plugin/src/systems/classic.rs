@@ -0,0 +1,60 @@
+use crate::components::HintMarker;
+use crate::events::*;
+use crate::resources::*;
+use crate::systems::hint::spawn_hint_marker;
+use bevy::log;
+use bevy::prelude::*;
+
+pub fn check_solved(
+    mut classic_puzzle: Option<ResMut<ClassicPuzzle>>,
+    board: Res<Board>,
+    mut level_complete_evw: EventWriter<LevelCompleteEvent>,
+) {
+    let classic_puzzle = match classic_puzzle.as_mut() {
+        Some(classic_puzzle) if !classic_puzzle.is_solved => classic_puzzle,
+        _ => return,
+    };
+
+    if ClassicPuzzle::is_solved(&board.tile_map) {
+        classic_puzzle.is_solved = true;
+        level_complete_evw.send(LevelCompleteEvent(classic_puzzle.moves as u64));
+    }
+}
+
+pub fn show_hints(
+    mut commands: Commands,
+    classic_puzzle: Option<Res<ClassicPuzzle>>,
+    board: Res<Board>,
+    hint_marker_query: Query<Entity, With<HintMarker>>,
+    mut show_hint_evr: EventReader<ShowHintEvent>,
+    mut show_solution_evr: EventReader<ShowSolutionEvent>,
+) {
+    let show_hint = show_hint_evr.iter().count() > 0;
+    let show_solution = show_solution_evr.iter().count() > 0;
+
+    let classic_puzzle = match classic_puzzle {
+        Some(classic_puzzle) if show_hint || show_solution => classic_puzzle,
+        _ => return,
+    };
+
+    for entity in hint_marker_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let solution = match ClassicPuzzle::solve(&board.tile_map, classic_puzzle.size) {
+        Some(solution) => solution,
+        None => {
+            log::warn!("This board has no solution");
+            return;
+        },
+    };
+
+    let hint_count = if show_solution { solution.len() } else { 1 };
+    log::info!("Showing {} of {} moves", hint_count.min(solution.len()), solution.len());
+
+    commands.entity(board.entity).with_children(|parent| {
+        for coordinates in solution.iter().take(hint_count) {
+            spawn_hint_marker(parent, coordinates, board.tile_size);
+        }
+    });
+}
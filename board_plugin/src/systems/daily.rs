@@ -0,0 +1,61 @@
+use crate::events::*;
+use crate::persistence;
+use crate::resources::{DailyChallenge, DailyResults, Date, GameMode};
+use crate::systems::game_state::GameState;
+use bevy::log;
+use bevy::prelude::*;
+
+pub fn load_daily_results(
+    mut commands: Commands,
+) {
+    commands.insert_resource(persistence::load::<DailyResults>(DailyResults::SAVE_NAME));
+}
+
+pub fn handle_start_daily_event(
+    mut commands: Commands,
+    mut game_state: ResMut<State<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut daily_results: ResMut<DailyResults>,
+    mut start_daily_evr: EventReader<StartDailyEvent>,
+) {
+    for _ in start_daily_evr.iter() {
+        let date = Date::today();
+        let challenge = DailyChallenge::new(date, !daily_results.has_attempted(date));
+
+        if challenge.is_scored {
+            daily_results.start(&challenge);
+            persistence::save(DailyResults::SAVE_NAME, &*daily_results);
+        }
+
+        log::info!(
+            "Starting the {:?} daily challenge for {} ({})",
+            challenge.variant,
+            date,
+            if challenge.is_scored { "scored" } else { "unscored" },
+        );
+
+        commands.insert_resource(challenge);
+        *game_mode = GameMode::Daily;
+        GameState::InGame.transition(&mut game_state);
+    }
+}
+
+pub fn handle_game_over_event(
+    game_mode: Res<GameMode>,
+    mut daily_challenge: Option<ResMut<DailyChallenge>>,
+    mut daily_results: ResMut<DailyResults>,
+    mut game_over_evr: EventReader<GameOverEvent>,
+) {
+    for event in game_over_evr.iter() {
+        let challenge = match daily_challenge.as_mut() {
+            Some(challenge) if *game_mode == GameMode::Daily && challenge.is_scored => challenge,
+            _ => continue,
+        };
+
+        daily_results.finish(challenge.date, event.0);
+        persistence::save(DailyResults::SAVE_NAME, &*daily_results);
+
+        // Restarting from the game over menu is just for fun.
+        challenge.is_scored = false;
+    }
+}
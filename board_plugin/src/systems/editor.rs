@@ -0,0 +1,376 @@
+use crate::components::*;
+use crate::events::*;
+use crate::resources::*;
+use crate::systems::input::get_cursor_coordinates;
+use crate::systems::tile::spawn_board;
+use bevy::input::ElementState;
+use bevy::input::keyboard::{KeyCode, KeyboardInput};
+use bevy::asset::{AssetServerSettings, FileAssetIo};
+use bevy::log;
+use bevy::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TILE_SIZE: f32 = 32.;
+const TILE_PADDING: f32 = 2.;
+
+pub fn setup_editor(
+    mut commands: Commands,
+    editor: Res<Editor>,
+    mut tile_create_evw: EventWriter<TileCreateEvent>,
+) {
+    spawn_board(&mut commands, editor.tile_map.clone(), TILE_SIZE, TILE_PADDING, &mut tile_create_evw);
+}
+
+pub fn setup_editor_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    mut ui_root_query: Query<&mut UiRoot>,
+) {
+    let ui_root = ui_root_query.single_mut();
+    let font = asset_server.load("fonts/Lato/Lato-Light.ttf");
+
+    commands.entity(ui_root.0)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                        position_type: PositionType::Absolute,
+                        flex_direction: FlexDirection::ColumnReverse,
+                        justify_content: JustifyContent::SpaceBetween,
+                        ..Default::default()
+                    },
+                    color: Color::NONE.into(),
+                    ..Default::default()
+                })
+                .insert(Name::new("Editor Panel"))
+                .insert(EditorPanel)
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(TextBundle {
+                            style: Style {
+                                margin: Rect::all(Val::Px(16.)),
+                                ..Default::default()
+                            },
+                            text: Text::with_section(
+                                "",
+                                TextStyle {
+                                    font: font.clone(),
+                                    font_size: 24.,
+                                    color: Color::WHITE,
+                                },
+                                Default::default()
+                            ),
+                            ..Default::default()
+                        })
+                        .insert(Name::new("Editor Text"))
+                        .insert(EditorText);
+                    parent
+                        .spawn_bundle(TextBundle {
+                            style: Style {
+                                margin: Rect::all(Val::Px(16.)),
+                                ..Default::default()
+                            },
+                            text: Text::with_section(
+                                locale.get("editor-help"),
+                                TextStyle {
+                                    font: font.clone(),
+                                    font_size: 16.,
+                                    color: Color::GRAY,
+                                },
+                                Default::default()
+                            ),
+                            ..Default::default()
+                        })
+                        .insert(Name::new("Editor Help Text"));
+                });
+        });
+}
+
+pub fn teardown_editor(
+    mut commands: Commands,
+    board: Res<Board>,
+    mut editor: ResMut<Editor>,
+    editor_panel_query: Query<Entity, With<EditorPanel>>,
+) {
+    editor.tile_map = board.tile_map.clone();
+
+    for entity in editor_panel_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn paint_tiles(
+    windows: Res<Windows>,
+    mut board: ResMut<Board>,
+    mut editor: ResMut<Editor>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut tile_update_evw: EventWriter<TileUpdateEvent>,
+) {
+    // Keep the sprites in sync with the tile map, as the game does every frame.
+    tile_update_evw.send(TileUpdateEvent);
+
+    let brush = if mouse_buttons.pressed(MouseButton::Left) {
+        Some(editor.brush)
+    } else if mouse_buttons.pressed(MouseButton::Right) {
+        None
+    } else {
+        return;
+    };
+
+    let (camera, camera_transform) = camera_query.single();
+    let window = windows.get(camera.window).unwrap();
+
+    let coordinates = match get_cursor_coordinates(window, camera, camera_transform, board.tile_size) {
+        Some(coordinates) => coordinates,
+        None => return,
+    };
+
+    // Each stroke is undone as a whole.
+    if mouse_buttons.just_pressed(MouseButton::Left) || mouse_buttons.just_pressed(MouseButton::Right) {
+        editor.history.push(&board.tile_map);
+    }
+
+    Editor::paint(&mut board.tile_map, coordinates, editor.brush_size, brush);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle_editor_keys(
+    windows: Res<Windows>,
+    mut board: ResMut<Board>,
+    mut editor: ResMut<Editor>,
+    locale: Res<Locale>,
+    asset_server_settings: Res<AssetServerSettings>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut keyboard_evr: EventReader<KeyboardInput>,
+    mut start_custom_level_evw: EventWriter<StartCustomLevelEvent>,
+    mut go_to_main_menu_evw: EventWriter<GoToMainMenuEvent>,
+) {
+    for event in keyboard_evr.iter() {
+        if event.state != ElementState::Pressed {
+            continue;
+        }
+
+        let key_code = match event.key_code {
+            Some(key_code) => key_code,
+            None => continue,
+        };
+
+        if let Some(lightness) = get_brush_lightness(key_code) {
+            editor.brush = Tile::new_gray(lightness);
+            continue;
+        }
+
+        match key_code {
+            KeyCode::W => editor.brush = Tile::new(0, TileKind::Wall),
+            KeyCode::L => editor.brush = Tile::new(MAX_LIGHTNESS, TileKind::Lamp),
+            KeyCode::N => editor.brush = Tile::new_gray(editor.brush.lightness.max(1)),
+            KeyCode::LBracket => editor.shrink_brush(),
+            KeyCode::RBracket => editor.grow_brush(),
+            KeyCode::Up => editor.clicks += 1,
+            KeyCode::Down => editor.clicks = editor.clicks.saturating_sub(1),
+            KeyCode::G => {
+                let (camera, camera_transform) = camera_query.single();
+                let window = windows.get(camera.window).unwrap();
+                if let Some(coordinates) = get_cursor_coordinates(window, camera, camera_transform, board.tile_size) {
+                    editor.set_goal_corner(coordinates);
+                }
+            },
+            KeyCode::Z => {
+                if let Some(tile_map) = editor.history.undo(&board.tile_map) {
+                    board.tile_map = tile_map;
+                }
+            },
+            KeyCode::Y => {
+                if let Some(tile_map) = editor.history.redo(&board.tile_map) {
+                    board.tile_map = tile_map;
+                }
+            },
+            KeyCode::Delete => {
+                editor.history.push(&board.tile_map);
+                board.tile_map.clear();
+            },
+            KeyCode::Return => {
+                match editor.to_level(&board.tile_map).validate() {
+                    Ok(_) => start_custom_level_evw.send(StartCustomLevelEvent),
+                    Err(e) => {
+                        editor.status = locale.format("editor-cant-test", &[("error", e.to_string())]);
+                        log::warn!("{}", editor.status);
+                    },
+                }
+            },
+            KeyCode::F5 => {
+                let directory = get_custom_level_directory(&asset_server_settings);
+                editor.status = match save_level(&editor.to_level(&board.tile_map), &directory) {
+                    Ok(path) => locale.format("editor-saved", &[("path", path.display().to_string())]),
+                    Err(e) => locale.format("editor-cant-save", &[("error", e.to_string())]),
+                };
+                log::info!("{}", editor.status);
+            },
+            KeyCode::F9 => {
+                editor.status = match load_level(&get_custom_level_directory(&asset_server_settings)) {
+                    Ok((level, path)) => {
+                        editor.load_level(&level);
+                        board.tile_map = editor.tile_map.clone();
+                        locale.format("editor-loaded", &[("path", path.display().to_string())])
+                    },
+                    Err(e) => locale.format("editor-cant-load", &[("error", e.to_string())]),
+                };
+                log::info!("{}", editor.status);
+            },
+            KeyCode::Escape => go_to_main_menu_evw.send(GoToMainMenuEvent),
+            _ => {},
+        }
+    }
+}
+
+pub fn update_editor_text(
+    editor: Res<Editor>,
+    locale: Res<Locale>,
+    mut editor_text_query: Query<&mut Text, With<EditorText>>,
+) {
+    if !editor.is_changed() {
+        return;
+    }
+
+    let brush = match editor.brush.kind {
+        TileKind::Normal => {
+            locale.format("editor-brush-lightness", &[("lightness", editor.brush.lightness.to_string())])
+        },
+        TileKind::Wall => locale.get("editor-brush-wall"),
+        TileKind::Lamp => locale.get("editor-brush-lamp"),
+    };
+
+    let goal = match editor.goal {
+        Goal::LightRegion { min, max } => locale.format("editor-goal-region", &[
+            ("min", Coordinates::from(min).to_string()),
+            ("max", Coordinates::from(max).to_string()),
+        ]),
+        Goal::Score(score) => locale.format("editor-goal-score", &[("score", locale.format_number(score))]),
+    };
+
+    for mut text in editor_text_query.iter_mut() {
+        text.sections[0].value = locale.format("editor-text", &[
+            ("brush", brush.clone()),
+            ("size", editor.brush_size.to_string()),
+            ("clicks", editor.clicks.to_string()),
+            ("goal", goal.clone()),
+            ("status", editor.status.clone()),
+        ]);
+    }
+}
+
+pub fn update_goal_marker(
+    mut commands: Commands,
+    board: Res<Board>,
+    editor: Res<Editor>,
+    goal_marker_query: Query<Entity, With<GoalRegionMarker>>,
+) {
+    if !editor.is_changed() && !board.is_added() {
+        return;
+    }
+
+    for entity in goal_marker_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    if let Goal::LightRegion { min, max } = editor.goal {
+        let size = Vec2::new((max.0 - min.0 + 1) as f32, (max.1 - min.1 + 1) as f32) * board.tile_size;
+        let center = Vec2::new((min.0 + max.0) as f32, (min.1 + max.1) as f32) * board.tile_size / 2.;
+
+        commands.entity(board.entity).with_children(|parent| {
+            parent
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgba(0.2, 0.6, 1., 0.3),
+                        custom_size: Some(size),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(center.x, center.y, 2.),
+                    ..Default::default()
+                })
+                .insert(Name::new("Goal Region"))
+                .insert(GoalRegionMarker);
+        });
+    }
+}
+
+fn get_brush_lightness(key_code: KeyCode) -> Option<u8> {
+    let digit = match key_code {
+        KeyCode::Key0 => 0,
+        KeyCode::Key1 => 1,
+        KeyCode::Key2 => 2,
+        KeyCode::Key3 => 3,
+        KeyCode::Key4 => 4,
+        KeyCode::Key5 => 5,
+        KeyCode::Key6 => 6,
+        KeyCode::Key7 => 7,
+        KeyCode::Key8 => 8,
+        KeyCode::Key9 => 9,
+        _ => return None,
+    };
+
+    Some((digit * MAX_LIGHTNESS as u32 / 9) as u8)
+}
+
+/// The custom level folder inside the asset folder, found the same way the asset server finds it, so it doesn't
+/// depend on the working directory.
+fn get_custom_level_directory(asset_server_settings: &AssetServerSettings) -> PathBuf {
+    FileAssetIo::get_root_path()
+        .join(&asset_server_settings.asset_folder)
+        .join(CUSTOM_LEVEL_DIRECTORY)
+}
+
+fn get_custom_level_number(path: &Path) -> Option<u32> {
+    path.file_name()?
+        .to_str()?
+        .strip_prefix("custom-")?
+        .strip_suffix(".level.ron")?
+        .parse()
+        .ok()
+}
+
+fn get_latest_custom_level_number(directory: &Path) -> Result<Option<u32>, anyhow::Error> {
+    if !directory.exists() {
+        return Ok(None);
+    }
+
+    let mut latest = None;
+    for entry in fs::read_dir(directory)? {
+        latest = latest.max(get_custom_level_number(&entry?.path()));
+    }
+
+    Ok(latest)
+}
+
+fn get_custom_level_path(directory: &Path, number: u32) -> PathBuf {
+    directory.join(format!("custom-{}.level.ron", number))
+}
+
+/// Saves the level next to the previously saved ones and returns where it went.
+fn save_level(level: &Level, directory: &Path) -> Result<PathBuf, anyhow::Error> {
+    level.validate()?;
+
+    let number = get_latest_custom_level_number(directory)?.map_or(1, |number| { number + 1 });
+    let path = get_custom_level_path(directory, number);
+    let contents = ron::ser::to_string_pretty(level, Default::default())?;
+    fs::create_dir_all(directory)?;
+    fs::write(&path, contents)?;
+
+    Ok(path)
+}
+
+/// Loads the most recently saved level.
+fn load_level(directory: &Path) -> Result<(Level, PathBuf), anyhow::Error> {
+    let number = get_latest_custom_level_number(directory)?
+        .ok_or_else(|| { anyhow::anyhow!("No levels saved in {}", directory.display()) })?;
+    let path = get_custom_level_path(directory, number);
+    let contents = fs::read_to_string(&path)?;
+    let level: Level = ron::from_str(&contents)?;
+    level.validate()?;
+
+    Ok((level, path))
+}
@@ -0,0 +1,76 @@
+use crate::components::TriggerPulse;
+use crate::events::TileLitEvent;
+use crate::resources::{Board, EffectTextures, Settings, Theme, TileKind, MAX_LIGHTNESS};
+use crate::systems::tile::tile_translation;
+use bevy::prelude::*;
+
+/// How long a trigger's pulse takes to spread out and fade.
+const PULSE_SECONDS: f32 = 0.4;
+/// How many tiles across a pulse is when it fades out.
+const PULSE_TILES: f32 = 5.;
+/// How opaque a pulse is when it starts.
+const PULSE_ALPHA: f32 = 0.8;
+
+pub fn setup_effect_textures(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+) {
+    commands.insert_resource(EffectTextures::new(&mut images));
+}
+
+/// Sends a ring of light out from every tile a trigger lights, unless motion is reduced.
+pub fn spawn_trigger_pulses(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    theme: Res<Theme>,
+    board: Res<Board>,
+    effect_textures: Res<EffectTextures>,
+    mut tile_lit_evr: EventReader<TileLitEvent>,
+) {
+    if settings.reduced_motion {
+        return;
+    }
+
+    let color = theme.tile_color(MAX_LIGHTNESS, TileKind::Normal, None);
+
+    for event in tile_lit_evr.iter() {
+        commands.entity(board.entity).with_children(|parent| {
+            parent
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color,
+                        custom_size: Some(Vec2::splat(board.tile_size)),
+                        ..Default::default()
+                    },
+                    texture: effect_textures.ring.clone(),
+                    transform: Transform::from_translation(tile_translation(&event.0, board.tile_size, 3.)),
+                    ..Default::default()
+                })
+                .insert(Name::new(format!("Pulse {}", event.0)))
+                .insert(TriggerPulse {
+                    elapsed: 0.,
+                });
+        });
+    }
+}
+
+/// Grows and fades each pulse, easing out so it starts fast, and removes it once it's gone.
+pub fn animate_trigger_pulses(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut pulse_query: Query<(Entity, &mut TriggerPulse, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut pulse, mut transform, mut sprite) in pulse_query.iter_mut() {
+        pulse.elapsed += time.delta_seconds();
+
+        let t = pulse.elapsed / PULSE_SECONDS;
+        if t >= 1. {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let eased = 1. - (1. - t).powi(3);
+        transform.scale = Vec3::splat(1. + (PULSE_TILES - 1.) * eased);
+        sprite.color.set_a(PULSE_ALPHA * (1. - t));
+    }
+}
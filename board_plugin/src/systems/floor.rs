@@ -0,0 +1,137 @@
+use crate::components::{FloorChunk, TileFade};
+use crate::events::TileUpdateEvent;
+use crate::materials::{FloorMaterial, FLOOR_CHUNK_SIZE, FLOOR_GRID_SIZE, LIGHT_RADIUS};
+use crate::resources::{Board, Floor, Settings, Theme, TileKind, MAX_LIGHTNESS};
+use bevy::prelude::*;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+use bevy::utils::{HashMap, HashSet};
+
+/// Builds the lit floor when dynamic lighting is turned on, relights it as the board changes, and
+/// removes it again when it's turned off, falling back to drawing tiles as sprites alone.
+#[allow(clippy::too_many_arguments)]
+pub fn update_floor(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    theme: Res<Theme>,
+    board: Res<Board>,
+    floor: Option<ResMut<Floor>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<FloorMaterial>>,
+    mut tile_fade_query: Query<&mut TileFade>,
+    mut tile_update_evr: EventReader<TileUpdateEvent>,
+) {
+    let is_board_updated = tile_update_evr.iter().count() > 0;
+
+    // Dark tiles and halos are shown or hidden for the mode by `animate_tiles`.
+    if settings.is_changed() {
+        for mut tile_fade in tile_fade_query.iter_mut() {
+            tile_fade.set_changed();
+        }
+    }
+
+    if !settings.dynamic_lighting {
+        if let Some(floor) = floor {
+            for (entity, _) in floor.chunks.values() {
+                commands.entity(*entity).despawn_recursive();
+            }
+            commands.remove_resource::<Floor>();
+        }
+        return;
+    }
+
+    let mut floor = match floor {
+        Some(floor) => floor,
+        None => {
+            let chunk_width = FLOOR_CHUNK_SIZE as f32 * board.tile_size;
+            commands.insert_resource(Floor {
+                mesh: meshes.add(Mesh::from(shape::Quad::new(Vec2::splat(chunk_width)))),
+                chunks: HashMap::default(),
+            });
+            return;
+        }
+    };
+
+    if !is_board_updated && !floor.is_added() && !settings.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    let chunks: HashSet<(i32, i32)> = board.tile_map.keys()
+        .map(|coordinates| {
+            (coordinates.x.div_euclid(FLOOR_CHUNK_SIZE), coordinates.y.div_euclid(FLOOR_CHUNK_SIZE))
+        })
+        .collect();
+
+    floor.chunks.retain(|chunk, (entity, _)| {
+        if !chunks.contains(chunk) {
+            commands.entity(*entity).despawn_recursive();
+        }
+        chunks.contains(chunk)
+    });
+
+    for chunk in chunks {
+        let material = floor_material(&board, &theme, chunk);
+
+        if let Some((_, handle)) = floor.chunks.get(&chunk) {
+            if let Some(existing_material) = materials.get_mut(handle) {
+                // Only changed chunks are marked as modified, so unchanged ones aren't uploaded.
+                if *existing_material != material {
+                    *existing_material = material;
+                }
+            }
+            continue;
+        }
+
+        let handle = materials.add(material);
+        let center = (Vec2::new(chunk.0 as f32, chunk.1 as f32) * FLOOR_CHUNK_SIZE as f32
+            + Vec2::splat((FLOOR_CHUNK_SIZE - 1) as f32 / 2.))
+            * board.tile_size;
+
+        // Under the tiles, so lit tiles are still drawn as cells on top of their own light.
+        let entity = commands
+            .spawn_bundle(MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(floor.mesh.clone()),
+                material: handle.clone(),
+                transform: Transform::from_translation(center.extend(0.5)),
+                ..Default::default()
+            })
+            .insert(Name::new(format!("Floor Chunk ({}, {})", chunk.0, chunk.1)))
+            .insert(FloorChunk)
+            .id();
+        commands.entity(board.entity).push_children(&[entity]);
+
+        floor.chunks.insert(chunk, (entity, handle));
+    }
+}
+
+/// The floor goes with the board, so only the resource is left to remove.
+pub fn teardown_floor(
+    mut commands: Commands,
+) {
+    commands.remove_resource::<Floor>();
+}
+
+/// The lights for the chunk at `chunk`, from the tiles on it and close enough around it to reach it.
+fn floor_material(board: &Board, theme: &Theme, chunk: (i32, i32)) -> FloorMaterial {
+    let first_x = chunk.0 * FLOOR_CHUNK_SIZE - LIGHT_RADIUS;
+    let first_y = chunk.1 * FLOOR_CHUNK_SIZE - LIGHT_RADIUS;
+
+    let lights = (0..FLOOR_GRID_SIZE)
+        .flat_map(|y| { (0..FLOOR_GRID_SIZE).map(move |x| { (first_x + x, first_y + y) }) })
+        .map(|coordinates| {
+            match board.tile_map.get(&coordinates.into()) {
+                Some(tile) if tile.is_not_black() && tile.kind != TileKind::Wall => {
+                    let brightness = tile.lightness as f32 / MAX_LIGHTNESS as f32;
+                    (theme.tile_color(tile.lightness, tile.kind, tile.owner), brightness)
+                },
+                _ => (Color::BLACK, 0.),
+            }
+        })
+        .collect();
+
+    FloorMaterial {
+        ambient: theme.background(),
+        origin: Vec2::new(first_x as f32, first_y as f32) * board.tile_size,
+        tile_size: board.tile_size,
+        lights,
+    }
+}
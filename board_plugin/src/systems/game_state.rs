@@ -1,5 +1,5 @@
 use crate::events::*;
-use crate::resources::FinalScore;
+use crate::resources::{FinalScore, GameMode, CLASSIC_SIZE};
 use bevy::app::AppExit;
 use bevy::prelude::*;
 use bevy::log;
@@ -8,6 +8,11 @@ use bevy::log;
 pub enum GameState {
     Init,
     MainMenu,
+    LevelSelect,
+    Achievements,
+    Daily,
+    Settings,
+    Editor,
     InGame,
     Paused,
     GameOver,
@@ -31,6 +36,106 @@ pub fn handle_start_game_event(
     }
 }
 
+pub fn handle_start_practice_event(
+    mut game_state: ResMut<State<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut start_practice_evr: EventReader<StartPracticeEvent>,
+) {
+    for _ in start_practice_evr.iter() {
+        *game_mode = GameMode::Practice;
+        GameState::InGame.transition(&mut game_state);
+    }
+}
+
+pub fn handle_start_versus_event(
+    mut game_state: ResMut<State<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut start_versus_evr: EventReader<StartVersusEvent>,
+) {
+    for _ in start_versus_evr.iter() {
+        *game_mode = GameMode::Versus;
+        GameState::InGame.transition(&mut game_state);
+    }
+}
+
+pub fn handle_open_level_select_event(
+    mut game_state: ResMut<State<GameState>>,
+    mut open_level_select_evr: EventReader<OpenLevelSelectEvent>,
+) {
+    for _ in open_level_select_evr.iter() {
+        GameState::LevelSelect.transition(&mut game_state);
+    }
+}
+
+pub fn handle_open_achievements_event(
+    mut game_state: ResMut<State<GameState>>,
+    mut open_achievements_evr: EventReader<OpenAchievementsEvent>,
+) {
+    for _ in open_achievements_evr.iter() {
+        GameState::Achievements.transition(&mut game_state);
+    }
+}
+
+pub fn handle_open_settings_event(
+    mut game_state: ResMut<State<GameState>>,
+    mut open_settings_evr: EventReader<OpenSettingsEvent>,
+) {
+    for _ in open_settings_evr.iter() {
+        GameState::Settings.transition(&mut game_state);
+    }
+}
+
+pub fn handle_open_daily_event(
+    mut game_state: ResMut<State<GameState>>,
+    mut open_daily_evr: EventReader<OpenDailyEvent>,
+) {
+    for _ in open_daily_evr.iter() {
+        GameState::Daily.transition(&mut game_state);
+    }
+}
+
+pub fn handle_start_level_event(
+    mut game_state: ResMut<State<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut start_level_evr: EventReader<StartLevelEvent>,
+) {
+    for event in start_level_evr.iter() {
+        *game_mode = GameMode::Puzzle(event.0);
+        GameState::InGame.transition(&mut game_state);
+    }
+}
+
+pub fn handle_start_classic_event(
+    mut game_state: ResMut<State<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut start_classic_evr: EventReader<StartClassicEvent>,
+) {
+    for _ in start_classic_evr.iter() {
+        *game_mode = GameMode::Classic(CLASSIC_SIZE);
+        GameState::InGame.transition(&mut game_state);
+    }
+}
+
+pub fn handle_open_editor_event(
+    mut game_state: ResMut<State<GameState>>,
+    mut open_editor_evr: EventReader<OpenEditorEvent>,
+) {
+    for _ in open_editor_evr.iter() {
+        GameState::Editor.transition(&mut game_state);
+    }
+}
+
+pub fn handle_start_custom_level_event(
+    mut game_state: ResMut<State<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut start_custom_level_evr: EventReader<StartCustomLevelEvent>,
+) {
+    for _ in start_custom_level_evr.iter() {
+        *game_mode = GameMode::Custom;
+        GameState::InGame.transition(&mut game_state);
+    }
+}
+
 pub fn handle_exit_game_event(
     mut exit_game_evr: EventReader<ExitGameEvent>,
     mut exit_evw: EventWriter<AppExit>,
@@ -42,9 +147,11 @@ pub fn handle_exit_game_event(
 
 pub fn handle_go_to_main_menu_event(
     mut game_state: ResMut<State<GameState>>,
+    mut game_mode: ResMut<GameMode>,
     mut got_to_main_menu_evr: EventReader<GoToMainMenuEvent>,
 ) {
     for _ in got_to_main_menu_evr.iter() {
+        *game_mode = GameMode::Endless;
         GameState::MainMenu.transition(&mut game_state);
     }
 }
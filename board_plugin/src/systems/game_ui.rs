@@ -1,12 +1,33 @@
 use crate::components::*;
 use crate::events::*;
-use crate::resources::FinalScore;
-use crate::utils::format_number;
-use bevy::input::ElementState;
-use bevy::input::mouse::*;
-use bevy::log;
+use crate::resources::{
+    Achievement, AchievementProgress, ClassicPuzzle, DailyChallenge, DailyResults, Date, FinalScore, GameHistory, GameMode,
+    GameStats, Level, LevelProgress, Levels, LifetimeStats, Locale, OnlineConfig, Player, PuzzleState, Settings,
+    SpectatorConfig, Theme, UiFocus, Versus, VersusOutcome, month_key,
+};
+use crate::systems::menu::{Menu, MenuButton, MenuText, spawn_menu};
+use bevy::ecs::system::Command;
+use bevy::input::touch::Touches;
 use bevy::prelude::*;
 
+/// Buttons that can be clicked and focused.
+type EnabledButton = (With<UiButton>, Without<Disabled>);
+
+/// The line under the game over title.
+enum Summary {
+    /// The final score, counted up as the menu opens.
+    Score(String),
+    Text(String),
+}
+
+/// What the main menu's first button does, chosen by the command line.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum StartKind {
+    Local,
+    Online,
+    Spectate,
+}
+
 pub fn setup_ui(
     mut commands: Commands,
 ) {
@@ -27,41 +48,223 @@ pub fn setup_ui(
 pub fn setup_main_menu_ui(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    mut ui_root_query: Query<&mut UiRoot>,
+    locale: Res<Locale>,
+    online_config: Option<Res<OnlineConfig>>,
+    spectator_config: Option<Res<SpectatorConfig>>,
+    ui_root_query: Query<&UiRoot>,
 ) {
-    let ui_root = ui_root_query.single_mut();
+    let start_kind = if spectator_config.is_some() {
+        StartKind::Spectate
+    } else if online_config.is_some() {
+        StartKind::Online
+    } else {
+        StartKind::Local
+    };
 
     let font = &asset_server.load("fonts/Lato/Lato-Light.ttf");
-    let menu_panel_entity = build_main_menu_panel(&mut commands, font);
-
-    commands.entity(ui_root.0).push_children(&[menu_panel_entity]);
+    spawn_menu(&mut commands, ui_root_query.single(), font, main_menu(start_kind, font, &locale));
 }
 
 pub fn setup_pause_menu_ui(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    mut ui_root_query: Query<&mut UiRoot>,
+    locale: Res<Locale>,
+    game_mode: Res<GameMode>,
+    game_history: Option<Res<GameHistory>>,
+    ui_root_query: Query<&UiRoot>,
+) {
+    let font = &asset_server.load("fonts/Lato/Lato-Light.ttf");
+    let menu = pause_menu(*game_mode, game_history.as_deref(), &locale);
+
+    spawn_menu(&mut commands, ui_root_query.single(), font, menu);
+}
+
+/// Rebuilds the pause menu after an undo, so UNDO is disabled once there are no moves left.
+#[allow(clippy::too_many_arguments)]
+pub fn refresh_pause_menu_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    game_mode: Res<GameMode>,
+    game_history: Option<Res<GameHistory>>,
+    ui_root_query: Query<&UiRoot>,
+    menu_query: Query<Entity, With<MenuPanel>>,
+) {
+    if !game_history.as_ref().is_some_and(|game_history| { game_history.is_changed() }) {
+        return;
+    }
+
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let font = &asset_server.load("fonts/Lato/Lato-Light.ttf");
+    let menu = pause_menu(*game_mode, game_history.as_deref(), &locale);
+
+    spawn_menu(&mut commands, ui_root_query.single(), font, menu);
+}
+
+pub fn setup_level_select_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    levels: Res<Levels>,
+    level_assets: Res<Assets<Level>>,
+    level_progress: Res<LevelProgress>,
+    ui_root_query: Query<&UiRoot>,
 ) {
-    let ui_root = ui_root_query.single_mut();
+    let font = &asset_server.load("fonts/Lato/Lato-Light.ttf");
+    let menu = level_select_menu(&levels, &level_assets, &level_progress, &locale);
+
+    spawn_menu(&mut commands, ui_root_query.single(), font, menu);
+}
 
+pub fn setup_achievements_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    achievement_progress: Res<AchievementProgress>,
+    ui_root_query: Query<&UiRoot>,
+) {
     let font = &asset_server.load("fonts/Lato/Lato-Light.ttf");
-    let menu_panel_entity = build_pause_menu_panel(&mut commands, font);
+    spawn_menu(&mut commands, ui_root_query.single(), font, achievements_menu(&achievement_progress, &locale));
+}
 
-    commands.entity(ui_root.0).push_children(&[menu_panel_entity]);
+pub fn setup_settings_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    settings: Res<Settings>,
+    theme: Res<Theme>,
+    ui_root_query: Query<&UiRoot>,
+) {
+    let font = &asset_server.load("fonts/Lato/Lato-Light.ttf");
+    spawn_menu(&mut commands, ui_root_query.single(), font, settings_menu(&settings, &theme, &locale));
 }
 
+/// Rebuilds the settings screen so its buttons show the options just chosen.
+pub fn refresh_settings_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    settings: Res<Settings>,
+    theme: Res<Theme>,
+    ui_root_query: Query<&UiRoot>,
+    menu_query: Query<Entity, With<MenuPanel>>,
+) {
+    if !settings.is_changed() && !theme.is_changed() && !locale.is_changed() {
+        return;
+    }
+
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let font = &asset_server.load("fonts/Lato/Lato-Light.ttf");
+    spawn_menu(&mut commands, ui_root_query.single(), font, settings_menu(&settings, &theme, &locale));
+}
+
+pub fn setup_daily_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    daily_results: Res<DailyResults>,
+    ui_root_query: Query<&UiRoot>,
+) {
+    let font = &asset_server.load("fonts/Lato/Lato-Light.ttf");
+    let menu = daily_menu(Date::today(), &daily_results, font, &locale);
+
+    spawn_menu(&mut commands, ui_root_query.single(), font, menu);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn refresh_level_select_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    levels: Res<Levels>,
+    level_assets: Res<Assets<Level>>,
+    level_progress: Res<LevelProgress>,
+    ui_root_query: Query<&UiRoot>,
+    menu_query: Query<Entity, With<MenuPanel>>,
+    mut level_evr: EventReader<AssetEvent<Level>>,
+) {
+    if level_evr.iter().count() == 0 {
+        return;
+    }
+
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let font = &asset_server.load("fonts/Lato/Lato-Light.ttf");
+    let menu = level_select_menu(&levels, &level_assets, &level_progress, &locale);
+
+    spawn_menu(&mut commands, ui_root_query.single(), font, menu);
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn setup_game_over_menu_ui(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
     final_score: Res<FinalScore>,
-    mut ui_root_query: Query<&mut UiRoot>,
+    game_mode: Res<GameMode>,
+    puzzle_state: Option<Res<PuzzleState>>,
+    classic_puzzle: Option<Res<ClassicPuzzle>>,
+    versus: Option<Res<Versus>>,
+    game_stats: Option<Res<GameStats>>,
+    lifetime_stats: Res<LifetimeStats>,
+    settings: Res<Settings>,
+    ui_root_query: Query<&UiRoot>,
 ) {
-    let ui_root = ui_root_query.single_mut();
+    // The score counts up from zero, unless motion is reduced.
+    let shown_score = if settings.reduced_motion { final_score.0 } else { 0 };
+    let score = locale.format("hud-score", &[("score", locale.format_number(shown_score))]);
+
+    let (title, summary) = match (puzzle_state, classic_puzzle, versus) {
+        (Some(puzzle_state), _, _) if puzzle_state.is_complete => {
+            (locale.get("title-level-complete"), Summary::Score(score))
+        },
+        (_, Some(classic_puzzle), _) => {
+            let summary = locale.format("game-over-moves", &[
+                ("moves", classic_puzzle.moves.to_string()),
+                ("best", classic_puzzle.minimum_moves.to_string()),
+            ]);
+
+            (locale.get("title-solved"), Summary::Text(summary))
+        },
+        (_, _, Some(versus)) => {
+            let title = match versus.outcome {
+                Some(VersusOutcome::Winner(player)) => {
+                    locale.format("title-winner", &[("player", locale.get(player.key()).to_uppercase())])
+                },
+                _ => locale.get("title-draw"),
+            };
+            let summary = Player::ALL.into_iter()
+                .map(|player| {
+                    locale.format("game-over-player-score", &[
+                        ("player", locale.get(player.key())),
+                        ("score", locale.format_number(versus.score(player))),
+                    ])
+                })
+                .collect::<Vec<String>>()
+                .join("\n");
+
+            (title, Summary::Text(summary))
+        },
+        _ => (locale.get("title-game-over"), Summary::Score(score)),
+    };
+
+    let stats = match game_stats {
+        Some(game_stats) => format!("{}\n\n{}", game_stats.summary(&locale), lifetime_stats.summary(&locale)),
+        None => lifetime_stats.summary(&locale),
+    };
 
     let font = &asset_server.load("fonts/Lato/Lato-Light.ttf");
-    let menu_panel_entity = build_game_over_menu_panel(&mut commands, *final_score, font);
+    let menu = game_over_menu(*game_mode, title, summary, stats, font, &locale);
 
-    commands.entity(ui_root.0).push_children(&[menu_panel_entity]);
+    spawn_menu(&mut commands, ui_root_query.single(), font, menu);
 }
 
 fn ui_style_fill_screen() -> Style {
@@ -77,173 +280,353 @@ fn ui_fill_space() -> Size<Val> {
     Size::new(Val::Percent(100.), Val::Percent(100.))
 }
 
-fn build_main_menu_panel(
-    commands: &mut Commands,
-    font: &Handle<Font>,
-) -> Entity {
-    let menu_buttons_entity = build_main_menu_buttons(commands, font);
+fn main_menu<'a>(
+    start_kind: StartKind,
+    font: &'a Handle<Font>,
+    locale: &'a Locale,
+) -> Menu<'a> {
+    // Starts an online game or spectates instead when a server or replay was given on the command
+    // line.
+    let start_button = match start_kind {
+        StartKind::Local => MenuButton::new(locale.get("menu-start"), "Start Game", StartGameEvent),
+        StartKind::Online => MenuButton::new(locale.get("menu-start-online"), "Start Online Game", StartOnlineEvent),
+        StartKind::Spectate => MenuButton::new(locale.get("menu-spectate"), "Spectate", StartSpectateEvent),
+    };
 
-    let menu_entity = commands
-        .spawn_bundle(NodeBundle {
-            style: Style {
-                size: ui_fill_space(),
-                margin: Rect {
-                    left: Val::Px(128.),
-                    right: Val::Px(128.),
-                    top: Val::Px(96.),
-                    bottom: Val::Px(96.),
-                },
-                flex_direction: FlexDirection::ColumnReverse,
-                ..Default::default()
-            },
-            color: Color::NONE.into(),
-            ..Default::default()
-        })
-        .insert(Name::new("Main Menu Panel"))
-        .with_children(|parent| {
-            build_logo_panel(parent, font);
-            build_help_text_panel(parent, font);
-        })
-        .id();
+    Menu::new("Main Menu")
+        .with_margin(16.)
+        .custom(move |parent| { build_logo_panel(parent, font) })
+        .custom(move |parent| { build_help_text_panel(parent, font, locale) })
+        .buttons(256., vec![
+            start_button,
+            MenuButton::new(locale.get("menu-practice"), "Practice", StartPracticeEvent),
+            MenuButton::new(locale.get("menu-versus"), "Versus", StartVersusEvent),
+            MenuButton::new(locale.get("menu-daily"), "Daily Challenge", OpenDailyEvent),
+            MenuButton::new(locale.get("menu-puzzles"), "Level Select", OpenLevelSelectEvent),
+            MenuButton::new(locale.get("menu-classic"), "Classic", StartClassicEvent),
+            MenuButton::new(locale.get("menu-editor"), "Editor", OpenEditorEvent),
+            MenuButton::new(locale.get("menu-achievements"), "Achievements", OpenAchievementsEvent),
+            MenuButton::new(locale.get("menu-quit"), "Exit Game", ExitGameEvent),
+        ])
+        // The column has no room left for another full-size button.
+        .corner_button(
+            MenuButton::new(locale.get("menu-settings"), "Settings", OpenSettingsEvent).with_font_size(24.),
+        )
+}
 
-    commands.entity(menu_entity).push_children(&[menu_buttons_entity]);
+fn pause_menu(
+    game_mode: GameMode,
+    game_history: Option<&GameHistory>,
+    locale: &Locale,
+) -> Menu<'static> {
+    let mut buttons = vec![MenuButton::new(locale.get("menu-resume"), "Resume Game", TogglePauseEvent)];
+    if game_mode.allows_undo() {
+        let can_undo = game_history.is_some_and(|game_history| { game_history.can_undo_move() });
+        buttons.push(MenuButton::new(locale.get("menu-undo"), "Undo", UndoEvent).disabled(!can_undo));
+    }
+    if game_mode == GameMode::Custom {
+        buttons.push(MenuButton::new(locale.get("menu-editor"), "Return to Editor", OpenEditorEvent));
+    }
+    buttons.push(MenuButton::new(locale.get("menu-main-menu"), "Main Menu", GoToMainMenuEvent));
+    buttons.push(MenuButton::new(locale.get("menu-quit"), "Exit Game", ExitGameEvent));
 
-    menu_entity
+    Menu::new("Pause Menu")
+        .overlay()
+        .title(locale.get("title-paused"))
+        .buttons(256., buttons)
 }
 
-fn build_pause_menu_panel(
-    commands: &mut Commands,
-    font: &Handle<Font>,
-) -> Entity {
-    let menu_buttons_entity = build_pause_menu_buttons(commands, font);
+fn level_select_menu(
+    levels: &Levels,
+    level_assets: &Assets<Level>,
+    level_progress: &LevelProgress,
+    locale: &Locale,
+) -> Menu<'static> {
+    let mut buttons = vec![];
 
-    let menu_entity = commands
-        .spawn_bundle(NodeBundle {
+    for (i, (path, handle)) in levels.levels.iter().enumerate() {
+        if let Some(level) = level_assets.get(handle) {
+            let text = if level_progress.is_completed(path) {
+                format!("{}. {} *", i + 1, level.name.to_uppercase())
+            } else {
+                format!("{}. {}", i + 1, level.name.to_uppercase())
+            };
+
+            buttons.push(MenuButton::new(text, &level.name, StartLevelEvent(i)).with_font_size(32.));
+        }
+    }
+
+    buttons.push(MenuButton::new(locale.get("menu-main-menu"), "Main Menu", GoToMainMenuEvent));
+
+    Menu::new("Level Select")
+        .title(locale.get("title-select-level"))
+        .buttons(384., buttons)
+}
+
+fn settings_menu(
+    settings: &Settings,
+    theme: &Theme,
+    locale: &Locale,
+) -> Menu<'static> {
+    let on_off = |is_on: bool| { locale.get(if is_on { "settings-on" } else { "settings-off" }) };
+
+    let theme_name = locale.get(theme.palette.key()).to_uppercase();
+    let ui_scale = (settings.ui_scale * 100.).round().to_string();
+    let language = settings.language.native_name().to_uppercase();
+
+    Menu::new("Settings")
+        .title(locale.get("title-settings"))
+        .buttons(256., vec![
+            MenuButton::new(locale.format("settings-theme", &[("theme", theme_name)]), "Theme", CycleThemeEvent),
+            MenuButton::new(
+                locale.format("settings-ui-scale", &[("scale", ui_scale)]),
+                "UI Scale",
+                ChangeSettingsEvent(Settings { ui_scale: settings.next_ui_scale(), ..*settings }),
+            ),
+            MenuButton::new(
+                locale.format("settings-high-contrast", &[("state", on_off(settings.high_contrast))]),
+                "High Contrast",
+                ChangeSettingsEvent(Settings { high_contrast: !settings.high_contrast, ..*settings }),
+            ),
+            MenuButton::new(
+                locale.format("settings-reduced-motion", &[("state", on_off(settings.reduced_motion))]),
+                "Reduced Motion",
+                ChangeSettingsEvent(Settings { reduced_motion: !settings.reduced_motion, ..*settings }),
+            ),
+            MenuButton::new(
+                locale.format("settings-dynamic-lighting", &[("state", on_off(settings.dynamic_lighting))]),
+                "Dynamic Lighting",
+                ChangeSettingsEvent(Settings { dynamic_lighting: !settings.dynamic_lighting, ..*settings }),
+            ),
+            MenuButton::new(
+                locale.format("settings-language", &[("language", language)]),
+                "Language",
+                ChangeSettingsEvent(Settings { language: settings.language.next(), ..*settings }),
+            ),
+            MenuButton::new(locale.get("menu-main-menu"), "Main Menu", GoToMainMenuEvent),
+        ])
+}
+
+fn achievements_menu(
+    achievement_progress: &AchievementProgress,
+    locale: &Locale,
+) -> Menu<'static> {
+    let mut menu = Menu::new("Achievements").title(locale.get("title-achievements"));
+
+    for achievement in Achievement::ALL {
+        let color = if achievement_progress.is_unlocked(achievement) {
+            Color::WHITE
+        } else {
+            Color::DARK_GRAY
+        };
+
+        let text = MenuText::new(format!("{}\n", achievement.title(locale).to_uppercase()), 32., color)
+            .with_section(achievement.description(locale), 20., Color::GRAY)
+            .with_margin(8.);
+
+        menu = menu.text(text);
+    }
+
+    menu.buttons(256., vec![MenuButton::new(locale.get("menu-main-menu"), "Main Menu", GoToMainMenuEvent)])
+}
+
+fn daily_menu<'a>(
+    today: Date,
+    daily_results: &'a DailyResults,
+    font: &'a Handle<Font>,
+    locale: &'a Locale,
+) -> Menu<'a> {
+    let challenge = DailyChallenge::new(today, !daily_results.has_attempted(today));
+
+    let status = match daily_results.get(today) {
+        Some(result) if result.is_finished => {
+            locale.format("daily-status-score", &[("score", locale.format_number(result.score))])
+        },
+        Some(_) => locale.get("daily-status-abandoned"),
+        None => locale.get("daily-status-new"),
+    };
+
+    let heading = locale.format("daily-heading", &[
+        ("date", today.to_string()),
+        ("variant", locale.get(challenge.variant.key())),
+        ("status", status),
+    ]);
+    let play_text = locale.get(if challenge.is_scored { "menu-play" } else { "menu-play-unscored" });
+
+    Menu::new("Daily")
+        .with_margin(48.)
+        .text(MenuText::new(heading, 24., Color::WHITE).with_margin(8.))
+        .custom(move |parent| { build_calendar(parent, today, daily_results, font, locale) })
+        .buttons(256., vec![
+            MenuButton::new(play_text, "Play Daily Challenge", StartDailyEvent),
+            MenuButton::new(locale.get("menu-main-menu"), "Main Menu", GoToMainMenuEvent),
+        ])
+}
+
+fn game_over_menu<'a>(
+    game_mode: GameMode,
+    title: String,
+    summary: Summary,
+    stats: String,
+    font: &'a Handle<Font>,
+    locale: &Locale,
+) -> Menu<'a> {
+    let mut buttons = vec![MenuButton::new(locale.get("menu-restart"), "Restart", StartGameEvent)];
+    if game_mode == GameMode::Custom {
+        buttons.push(MenuButton::new(locale.get("menu-editor"), "Return to Editor", OpenEditorEvent));
+    }
+    buttons.push(MenuButton::new(locale.get("menu-main-menu"), "Main Menu", GoToMainMenuEvent));
+    buttons.push(MenuButton::new(locale.get("menu-quit"), "Exit Game", ExitGameEvent));
+
+    let menu = Menu::new("Game Over Menu")
+        .overlay()
+        .title(title);
+
+    let menu = match summary {
+        Summary::Text(summary) => menu.text(MenuText::new(summary, 32., Color::WHITE)),
+        Summary::Score(score) => menu.custom(move |parent| { build_final_score(parent, font, score) }),
+    };
+
+    menu
+        .text(MenuText::new(stats, 16., Color::GRAY).with_margin(8.))
+        .buttons(256., buttons)
+}
+
+/// The final score, which `count_up_final_score` counts up from zero.
+fn build_final_score(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    score: String,
+) {
+    parent
+        .spawn_bundle(TextBundle {
             style: Style {
-                size: ui_fill_space(),
-                position_type: PositionType::Absolute,
-                margin: Rect {
-                    left: Val::Px(128.),
-                    right: Val::Px(128.),
-                    top: Val::Px(96.),
-                    bottom: Val::Px(96.),
-                },
-                flex_direction: FlexDirection::ColumnReverse,
+                align_self: AlignSelf::Center,
+                margin: Rect::all(Val::Px(16.)),
                 ..Default::default()
             },
-            color: Color::NONE.into(),
+            text: Text::with_section(
+                score,
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 32.,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            ),
             ..Default::default()
         })
-        .insert(Name::new("Pause Menu Panel"))
-        .insert(PauseMenu)
-        .with_children(|parent| {
-            parent
-                .spawn_bundle(TextBundle {
-                    style: Style {
-                        align_self: AlignSelf::Center,
-                        margin: Rect::all(Val::Px(16.)),
-                        ..Default::default()
-                    },
-                    text: Text::with_section(
-                        "PAUSED",
-                        TextStyle {
-                            font: font.clone(),
-                            font_size: 32.,
-                            color: Color::WHITE,
-                        },
-                        TextAlignment {
-                            vertical: VerticalAlign::Center,
-                            horizontal: HorizontalAlign::Center,
-                        },
-                    ),
-                    ..Default::default()
-                })
-                .insert(Name::new("Paused Text"));
-        })
-        .id();
-
-    commands.entity(menu_entity).push_children(&[menu_buttons_entity]);
-
-    menu_entity
+        .insert(Name::new("Final Score Text"))
+        .insert(FinalScoreCountUp { elapsed: 0. });
 }
 
-fn build_game_over_menu_panel(
-    commands: &mut Commands,
-    final_score: FinalScore,
+/// A grid of the days of this month, each with its daily result.
+fn build_calendar(
+    parent: &mut ChildBuilder,
+    today: Date,
+    daily_results: &DailyResults,
     font: &Handle<Font>,
-) -> Entity {
-    let menu_buttons_entity = build_game_over_menu_buttons(commands, font);
+    locale: &Locale,
+) {
+    let first = today.first_of_month();
+    let week_count = (first.weekday() + first.days_in_month()).div_ceil(7);
 
-    let menu_entity = commands
+    let cell_style = Style {
+        size: Size::new(Val::Px(64.), Val::Px(40.)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..Default::default()
+    };
+
+    parent
         .spawn_bundle(NodeBundle {
             style: Style {
-                size: ui_fill_space(),
-                position_type: PositionType::Absolute,
-                margin: Rect {
-                    left: Val::Px(128.),
-                    right: Val::Px(128.),
-                    top: Val::Px(96.),
-                    bottom: Val::Px(96.),
-                },
+                align_self: AlignSelf::Center,
                 flex_direction: FlexDirection::ColumnReverse,
+                margin: Rect::all(Val::Px(8.)),
                 ..Default::default()
             },
             color: Color::NONE.into(),
             ..Default::default()
         })
-        .insert(Name::new("Game Over Menu Panel"))
-        .insert(PauseMenu)
+        .insert(Name::new("Calendar"))
         .with_children(|parent| {
-            parent
-                .spawn_bundle(TextBundle {
-                    style: Style {
-                        align_self: AlignSelf::Center,
-                        margin: Rect::all(Val::Px(16.)),
-                        ..Default::default()
-                    },
-                    text: Text::with_section(
-                        "GAME OVER",
-                        TextStyle {
-                            font: font.clone(),
-                            font_size: 32.,
-                            color: Color::WHITE,
-                        },
-                        TextAlignment {
-                            vertical: VerticalAlign::Center,
-                            horizontal: HorizontalAlign::Center,
-                        },
-                    ),
+            parent.spawn_bundle(TextBundle {
+                style: Style {
+                    align_self: AlignSelf::Center,
                     ..Default::default()
-                })
-                .insert(Name::new("Game Over Text"));
-            parent
-                .spawn_bundle(TextBundle {
-                    style: Style {
-                        align_self: AlignSelf::Center,
-                        margin: Rect::all(Val::Px(16.)),
-                        ..Default::default()
+                },
+                text: Text::with_section(
+                    format!("{} {}", locale.get(month_key(today.month)).to_uppercase(), today.year),
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 20.,
+                        color: Color::GRAY,
                     },
-                    text: Text::with_section(
-                        format!("Score: {}", format_number(final_score.0)),
-                        TextStyle {
-                            font: font.clone(),
-                            font_size: 32.,
-                            color: Color::WHITE,
-                        },
-                        TextAlignment {
-                            vertical: VerticalAlign::Center,
-                            horizontal: HorizontalAlign::Center,
+                    Default::default()
+                ),
+                ..Default::default()
+            });
+
+            for week in 0..week_count {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            ..Default::default()
                         },
-                    ),
-                    ..Default::default()
-                })
-                .insert(Name::new("Score Text"));
-        })
-        .id();
+                        color: Color::NONE.into(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for weekday in 0..7 {
+                            let day = (week * 7 + weekday + 1) as i64 - first.weekday() as i64;
+                            let is_in_month = day >= 1 && day <= first.days_in_month() as i64;
 
-    commands.entity(menu_entity).push_children(&[menu_buttons_entity]);
+                            let (text, color) = if is_in_month {
+                                let date = Date::from_days(first.to_days() + day - 1);
+                                match daily_results.get(date) {
+                                    Some(result) if result.is_finished => {
+                                        (format!("{}\n{}", day, locale.format_number(result.score)), Color::WHITE)
+                                    },
+                                    Some(_) => (format!("{}\n-", day), Color::GRAY),
+                                    None if date > today => (day.to_string(), Color::DARK_GRAY),
+                                    None => (day.to_string(), Color::GRAY),
+                                }
+                            } else {
+                                (String::new(), Color::NONE)
+                            };
 
-    menu_entity
+                            parent
+                                .spawn_bundle(NodeBundle {
+                                    style: cell_style.clone(),
+                                    color: Color::NONE.into(),
+                                    ..Default::default()
+                                })
+                                .with_children(|parent| {
+                                    parent.spawn_bundle(TextBundle {
+                                        text: Text::with_section(
+                                            text,
+                                            TextStyle {
+                                                font: font.clone(),
+                                                font_size: 14.,
+                                                color,
+                                            },
+                                            TextAlignment {
+                                                vertical: VerticalAlign::Center,
+                                                horizontal: HorizontalAlign::Center,
+                                            },
+                                        ),
+                                        ..Default::default()
+                                    });
+                                });
+                        }
+                    });
+            }
+        });
 }
 
 fn build_logo_panel(
@@ -336,6 +719,7 @@ fn build_logo_panel(
 fn build_help_text_panel(
     parent: &mut ChildBuilder,
     font: &Handle<Font>,
+    locale: &Locale,
 ) {
     parent
         .spawn_bundle(NodeBundle {
@@ -361,10 +745,7 @@ fn build_help_text_panel(
                         ..Default::default()
                     },
                     text: Text::with_section(
-                        "Click gray squares to turn them white.\n\
-                        The lighter the square, the more points it will give.\n\
-                        If all squares turn black, the game ends.\n\n\
-                        Press Esc or Space to pause.",
+                        locale.get("menu-help"),
                         TextStyle {
                             font: font.clone(),
                             font_size: 24.,
@@ -382,328 +763,359 @@ fn build_help_text_panel(
         .insert(Name::new("Help Text Panel"));
 }
 
-fn build_main_menu_buttons(
-    commands: &mut Commands,
-    font: &Handle<Font>,
-) -> Entity {
-    let start_game_button_entity = build_start_game_button(commands, font);
-    let exit_game_button_entity = build_exit_game_button(commands, font);
+/// Colours each button for whether it's pressed, hovered or has keyboard focus. Disabled
+/// buttons keep their default colours.
+pub fn handle_ui_highlight(
+    ui_focus: Res<UiFocus>,
+    button_query: Query<(Entity, &Interaction, &GlobalTransform), EnabledButton>,
+    mut highlightable_node_query: Query<(&mut UiColor, &UiHighlightable)>,
+    mut highlightable_text_query: Query<(&mut Text, &UiHighlightable)>,
+) {
+    let focused = ui_focus.focused(button_query.iter().map(|(entity, _, transform)| {
+        (entity, transform.translation.truncate())
+    }));
 
-    let menu_buttons_entity = commands
-        .spawn_bundle(NodeBundle {
-            style: Style {
-                size: ui_fill_space(),
-                max_size: Size {
-                    width: Val::Px(256.),
-                    ..Default::default()
-                },
-                margin: Rect::all(Val::Auto),
-                justify_content: JustifyContent::Center,
-                flex_direction: FlexDirection::ColumnReverse,
-                ..Default::default()
-            },
-            color: Color::NONE.into(),
-            ..Default::default()
-        })
-        .insert(Name::new("Main Menu Buttons"))
-        .id();
+    let highlight_color = |highlightable: &UiHighlightable| {
+        match button_query.get(highlightable.root_entity) {
+            Ok((_, Interaction::Clicked, _)) => highlightable.pressed_color,
+            Ok((_, Interaction::Hovered, _)) => highlightable.hover_color,
+            Ok((entity, Interaction::None, _)) if Some(entity) == focused => highlightable.hover_color,
+            _ => highlightable.default_color,
+        }
+    };
 
-    commands.entity(menu_buttons_entity)
-        .push_children(&[start_game_button_entity, exit_game_button_entity]);
+    for (mut ui_color, highlightable) in highlightable_node_query.iter_mut() {
+        let color = highlight_color(highlightable);
+        if ui_color.0 != color {
+            ui_color.0 = color;
+        }
+    }
 
-    menu_buttons_entity
+    for (mut text, highlightable) in highlightable_text_query.iter_mut() {
+        let color = highlight_color(highlightable);
+        if text.sections.iter().any(|section| { section.style.color != color }) {
+            for section in text.sections.iter_mut() {
+                section.style.color = color;
+            }
+        }
+    }
 }
 
-fn build_pause_menu_buttons(
-    commands: &mut Commands,
-    font: &Handle<Font>,
-) -> Entity {
-    let resume_game_button_entity = build_resume_game_button(commands, font);
-    let return_main_menu_button_entity = build_return_main_menu_button(commands, font);
-    let exit_game_button_entity = build_exit_game_button(commands, font);
+/// Sends a button's event when the mouse is pressed and released over that same button, or a
+/// touch ends on it. Every button is handled here, whatever type of event it sends. Which node is
+/// under the pointer comes from Bevy's `Interaction`, which already allows for the UI scale and
+/// for nodes drawn over one another.
+pub fn handle_ui_button_click(
+    mut commands: Commands,
+    mut pressed_button: Local<Option<Entity>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    touches: Res<Touches>,
+    button_query: Query<(Entity, &UiButton, &Interaction), Without<Disabled>>,
+) {
+    // A touch presses and releases in the same frame, as far as `Interaction` is concerned.
+    if mouse_buttons.just_pressed(MouseButton::Left) || touches.just_released(0) {
+        *pressed_button = button_query.iter()
+            .find(|(_, _, interaction)| { **interaction == Interaction::Clicked })
+            .map(|(entity, _, _)| { entity });
+    }
 
-    let menu_buttons_entity = commands
-        .spawn_bundle(NodeBundle {
-            style: Style {
-                size: ui_fill_space(),
-                max_size: Size {
-                    width: Val::Px(256.),
-                    ..Default::default()
-                },
-                margin: Rect::all(Val::Auto),
-                justify_content: JustifyContent::Center,
-                flex_direction: FlexDirection::ColumnReverse,
-                ..Default::default()
-            },
-            color: Color::NONE.into(),
-            ..Default::default()
-        })
-        .insert(Name::new("Pause Menu Buttons"))
-        .id();
+    if !mouse_buttons.just_released(MouseButton::Left) && !touches.just_released(0) {
+        return;
+    }
 
-    commands.entity(menu_buttons_entity)
-        .push_children(&[resume_game_button_entity, return_main_menu_button_entity, exit_game_button_entity]);
+    let pressed_button = match pressed_button.take() {
+        Some(entity) => entity,
+        None => return,
+    };
 
-    menu_buttons_entity
+    // The button is `Hovered` once released over it, or still `Clicked` if it was pressed in the
+    // same frame. Released anywhere else, it goes back to `None`.
+    if let Ok((_, ui_button, interaction)) = button_query.get(pressed_button) {
+        if *interaction != Interaction::None {
+            commands.add(SendButtonEvent(ui_button.clone()));
+        }
+    }
 }
 
-fn build_game_over_menu_buttons(
-    commands: &mut Commands,
-    font: &Handle<Font>,
-) -> Entity {
-    let restart_game_button_entity = build_restart_game_button(commands, font);
-    let return_main_menu_button_entity = build_return_main_menu_button(commands, font);
-    let exit_game_button_entity = build_exit_game_button(commands, font);
+/// Moves keyboard focus between buttons with Tab, Shift+Tab and the up and down arrows, and
+/// clicks the focused button with Enter.
+pub fn handle_ui_focus_keys(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    mut ui_focus: ResMut<UiFocus>,
+    button_query: Query<(Entity, &UiButton, &GlobalTransform), Without<Disabled>>,
+) {
+    let is_shift_pressed = keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let button_count = button_query.iter().count();
 
-    let menu_buttons_entity = commands
-        .spawn_bundle(NodeBundle {
-            style: Style {
-                size: ui_fill_space(),
-                max_size: Size {
-                    width: Val::Px(256.),
-                    ..Default::default()
-                },
-                margin: Rect::all(Val::Auto),
-                justify_content: JustifyContent::Center,
-                flex_direction: FlexDirection::ColumnReverse,
-                ..Default::default()
-            },
-            color: Color::NONE.into(),
-            ..Default::default()
-        })
-        .insert(Name::new("Game Over Menu Buttons"))
-        .id();
+    if keys.just_pressed(KeyCode::Tab) {
+        ui_focus.move_by(if is_shift_pressed { -1 } else { 1 }, button_count);
+    }
+    if keys.just_pressed(KeyCode::Down) {
+        ui_focus.move_by(1, button_count);
+    }
+    if keys.just_pressed(KeyCode::Up) {
+        ui_focus.move_by(-1, button_count);
+    }
 
-    commands.entity(menu_buttons_entity)
-        .push_children(&[restart_game_button_entity, return_main_menu_button_entity, exit_game_button_entity]);
+    if keys.any_just_pressed([KeyCode::Return, KeyCode::NumpadEnter]) {
+        let focused = ui_focus.focused(button_query.iter().map(|(entity, _, transform)| {
+            (entity, transform.translation.truncate())
+        }));
 
-    menu_buttons_entity
+        if let Some((_, ui_button, _)) = focused.and_then(|entity| { button_query.get(entity).ok() }) {
+            commands.add(SendButtonEvent(ui_button.clone()));
+        }
+    }
 }
 
+/// Sends a button's event, which can be of any type, once the world can be reached.
+struct SendButtonEvent(UiButton);
 
-fn build_start_game_button(
-    commands: &mut Commands,
-    font: &Handle<Font>,
-) -> Entity {
-    build_button(commands, font, "START", "Start Game", &StartGameEvent)
+impl Command for SendButtonEvent {
+    fn write(self, world: &mut World) {
+        self.0.send(world);
+    }
 }
 
-fn build_exit_game_button(
-    commands: &mut Commands,
-    font: &Handle<Font>,
-) -> Entity {
-    build_button(commands, font, "QUIT", "Exit Game", &ExitGameEvent)
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::app::Events;
+    use bevy::ecs::schedule::SystemStage;
+    use bevy::math::{DVec2, const_vec2};
+    use bevy::ui::{FocusPolicy, ui_focus_system};
+    use bevy::window::{WindowDescriptor, WindowId};
+    use raw_window_handle::{RawWindowHandle, WebHandle};
 
-fn build_resume_game_button(
-    commands: &mut Commands,
-    font: &Handle<Font>,
-) -> Entity {
-    build_button(commands, font, "RESUME", "Resume Game", &TogglePauseEvent)
-}
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct TestEvent(u32);
 
-fn build_return_main_menu_button(
-    commands: &mut Commands,
-    font: &Handle<Font>,
-) -> Entity {
-    build_button(commands, font, "MAIN MENU", "Main Menu", &GoToMainMenuEvent)
-}
+    /// A window and nodes laid out by hand, with the systems that turn the mouse into button
+    /// events. Positions are in logical pixels, with y pointing up like the UI's.
+    struct TestUi {
+        world: World,
+        stage: SystemStage,
+    }
 
-fn build_restart_game_button(
-    commands: &mut Commands,
-    font: &Handle<Font>,
-) -> Entity {
-    build_button(commands, font, "RESTART", "Restart", &StartGameEvent)
-}
+    impl TestUi {
+        fn new(backend_scale_factor: f64, ui_scale: f64) -> Self {
+            let mut window = Window::new(
+                WindowId::primary(),
+                &WindowDescriptor::default(),
+                1280,
+                720,
+                backend_scale_factor,
+                None,
+                RawWindowHandle::Web(WebHandle::empty()),
+            );
+            window.set_scale_factor_override(Some(backend_scale_factor * ui_scale));
 
-fn build_button<T: Send + Sync + Copy>(
-    commands: &mut Commands,
-    font: &Handle<Font>,
-    text: &str,
-    name: &str,
-    on_click_event: &'static T,
-) -> Entity {
-    let root_entity = commands
-        .spawn_bundle(NodeBundle {
-            style: Style {
-                size: Size::new(Val::Percent(100.), Val::Px(64.)),
-                padding: Rect::all(Val::Px(2.)),
-                margin: Rect {
-                    bottom: Val::Px(8.),
-                    ..Default::default()
-                },
-                ..Default::default()
-            },
-            color: Color::WHITE.into(),
-            ..Default::default()
-        })
-        .insert(Name::new(format!("{} Button", name)))
-        .insert(UiButton(on_click_event))
-        .id();
-    
-    commands.entity(root_entity)
-        .with_children(|parent| {
-            parent
-                .spawn_bundle(NodeBundle {
-                    style: Style {
-                        size: ui_fill_space(),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::FlexEnd,
-                        ..Default::default()
-                    },
-                    color: Color::BLACK.into(),
-                    ..Default::default()
-                })
-                .insert(Name::new(format!("{} Button Interior", name)))
-                .insert(UiHighlightable {
-                    default_color: Color::BLACK,
-                    hover_color: Color::WHITE,
-                    pressed_color: Color::WHITE,
-                    root_entity,
-                })
-                .with_children(|parent| {
-                    parent
-                        .spawn_bundle(TextBundle {
-                            style: Style {
-                                align_self: AlignSelf::Center,
-                                ..Default::default()
-                            },
-                            text: Text::with_section(
-                                text,
-                                TextStyle {
-                                    font: font.clone(),
-                                    font_size: 48.,
-                                    color: Color::WHITE,
-                                },
-                                TextAlignment {
-                                    vertical: VerticalAlign::Center,
-                                    horizontal: HorizontalAlign::Center,
-                                },
-                            ),
-                            ..Default::default()
-                        })
-                        .insert(Name::new(format!("{} Button Text", name)))
-                        .insert(UiHighlightable {
-                            default_color: Color::WHITE,
-                            hover_color: Color::BLACK,
-                            pressed_color: Color::BLACK,
-                            root_entity,
-                        });
-                });
-        });
+            let mut windows = Windows::default();
+            windows.add(window);
 
-    root_entity
-}
+            let mut world = World::new();
+            world.insert_resource(windows);
+            world.insert_resource(Input::<MouseButton>::default());
+            world.insert_resource(Touches::default());
+            world.insert_resource(Events::<TestEvent>::default());
 
-pub fn teardown_main_menu_ui(
-    mut commands: Commands,
-    child_ui_items_query: Query<&Children, With<UiRoot>>,
-) {
-    for entity in child_ui_items_query.single().iter() {
-        commands.entity(*entity).despawn_recursive();
+            let stage = SystemStage::single_threaded()
+                .with_system(ui_focus_system.label("Focus"))
+                .with_system(handle_ui_button_click.after("Focus"));
+
+            Self {
+                world,
+                stage,
+            }
+        }
+
+        fn spawn_node(&mut self, center: Vec2, size: Vec2, z: f32) -> Entity {
+            self.world.spawn()
+                .insert(Node { size })
+                .insert(GlobalTransform::from_translation(center.extend(z)))
+                .id()
+        }
+
+        fn spawn_button(&mut self, id: u32, center: Vec2, size: Vec2, z: f32) -> Entity {
+            let entity = self.spawn_node(center, size, z);
+            self.world.entity_mut(entity)
+                .insert(Interaction::default())
+                .insert(UiButton::new(TestEvent(id)));
+
+            entity
+        }
+
+        /// Puts the pointer at `position`, reported in physical pixels as the window backend would.
+        fn move_pointer(&mut self, position: Vec2) {
+            let mut windows = self.world.get_resource_mut::<Windows>().unwrap();
+            let window = windows.get_primary_mut().unwrap();
+            let physical = DVec2::new(position.x as f64, position.y as f64) * window.scale_factor();
+            window.update_cursor_physical_position_from_backend(Some(physical));
+        }
+
+        fn press(&mut self) {
+            self.world.get_resource_mut::<Input<MouseButton>>().unwrap().press(MouseButton::Left);
+            self.update();
+        }
+
+        fn release(&mut self) {
+            self.world.get_resource_mut::<Input<MouseButton>>().unwrap().release(MouseButton::Left);
+            self.update();
+        }
+
+        fn update(&mut self) {
+            self.stage.run(&mut self.world);
+            self.world.get_resource_mut::<Input<MouseButton>>().unwrap().clear();
+        }
+
+        fn click(&mut self, press_at: Vec2, release_at: Vec2) {
+            self.move_pointer(press_at);
+            self.update();
+            self.press();
+            self.move_pointer(release_at);
+            self.update();
+            self.release();
+        }
+
+        /// The events sent since this was last called.
+        fn sent(&mut self) -> Vec<TestEvent> {
+            self.world.get_resource_mut::<Events<TestEvent>>().unwrap().drain().collect()
+        }
     }
-}
 
-pub fn teardown_pause_menu_ui(
-    mut commands: Commands,
-    pause_menu_ui_query: Query<Entity, With<PauseMenu>>,
-) {
-    for entity in pause_menu_ui_query.iter() {
-        commands.entity(entity).despawn_recursive();
+    const BUTTON_SIZE: Vec2 = const_vec2!([200., 60.]);
+
+    #[test]
+    fn pressing_and_releasing_on_a_button_clicks_it() {
+        let mut ui = TestUi::new(1., 1.);
+        ui.spawn_button(1, Vec2::new(300., 300.), BUTTON_SIZE, 1.);
+
+        ui.click(Vec2::new(250., 290.), Vec2::new(350., 320.));
+        assert_eq!(ui.sent(), vec![TestEvent(1)]);
     }
-}
 
-pub fn teardown_game_over_menu_ui(
-    mut commands: Commands,
-    child_ui_items_query: Query<&Children, With<UiRoot>>,
-) {
-    for entity in child_ui_items_query.single().iter() {
-        commands.entity(*entity).despawn_recursive();
+    #[test]
+    fn releasing_on_another_button_clicks_neither() {
+        let mut ui = TestUi::new(1., 1.);
+        ui.spawn_button(1, Vec2::new(300., 300.), BUTTON_SIZE, 1.);
+        ui.spawn_button(2, Vec2::new(300., 200.), BUTTON_SIZE, 1.);
+
+        ui.click(Vec2::new(300., 300.), Vec2::new(300., 200.));
+        assert_eq!(ui.sent(), vec![]);
     }
-}
 
-pub fn handle_ui_highlight(
-    windows: Res<Windows>,
-    mut highlightable_node_query: Query<(&mut UiColor, &UiHighlightable)>,
-    mut highlightable_text_query: Query<(&mut Text, &UiHighlightable)>,
-    node_transform_query: Query<(&Node, &GlobalTransform)>,
-    mut button_evr: EventReader<MouseButtonInput>,
-) {
-    let window = windows.get_primary().unwrap();
-    let position = window.cursor_position();
-    if let Some(position) = position {
-        let mut is_hover = true;
-        for event in button_evr.iter() {
-            if let ElementState::Pressed = event.state {
-                is_hover = false;
-            }
-        }
+    #[test]
+    fn pressing_elsewhere_and_releasing_on_a_button_does_not_click_it() {
+        let mut ui = TestUi::new(1., 1.);
+        ui.spawn_button(1, Vec2::new(300., 300.), BUTTON_SIZE, 1.);
 
-        for (mut ui_color, highlightable) in highlightable_node_query.iter_mut() {
-            if let Ok((node, transform)) = node_transform_query.get(highlightable.root_entity) {
-                if is_position_in_node(position, node, transform) {
-                    if is_hover {
-                        ui_color.0 = highlightable.hover_color.into();
-                    } else {
-                        ui_color.0 = highlightable.pressed_color.into();
-                    };
-                } else {
-                    ui_color.0 = highlightable.default_color.into();
-                }
-            }
-        }
+        ui.click(Vec2::new(600., 600.), Vec2::new(300., 300.));
+        assert_eq!(ui.sent(), vec![]);
+    }
 
-        for (mut text, highlightable) in highlightable_text_query.iter_mut() {
-            if let Ok((node, transform)) = node_transform_query.get(highlightable.root_entity) {
-                for mut section in text.sections.iter_mut() {
-                    if is_position_in_node(position, node, transform) {
-                        if is_hover {
-                            section.style.color = highlightable.hover_color;
-                        } else {
-                            section.style.color = highlightable.pressed_color;
-                        }
-                    } else {
-                        section.style.color = highlightable.default_color;
-                    }
-                }
-            }
+    #[test]
+    fn dragging_off_a_button_and_back_still_clicks_it() {
+        let mut ui = TestUi::new(1., 1.);
+        ui.spawn_button(1, Vec2::new(300., 300.), BUTTON_SIZE, 1.);
+
+        ui.move_pointer(Vec2::new(300., 300.));
+        ui.update();
+        ui.press();
+        ui.move_pointer(Vec2::new(600., 600.));
+        ui.update();
+        ui.move_pointer(Vec2::new(300., 300.));
+        ui.update();
+        ui.release();
+        assert_eq!(ui.sent(), vec![TestEvent(1)]);
+    }
+
+    #[test]
+    fn only_the_topmost_of_overlapping_buttons_is_clicked() {
+        let mut ui = TestUi::new(1., 1.);
+        ui.spawn_button(1, Vec2::new(300., 300.), BUTTON_SIZE, 1.);
+        ui.spawn_button(2, Vec2::new(350., 300.), BUTTON_SIZE, 2.);
+
+        ui.click(Vec2::new(320., 300.), Vec2::new(320., 300.));
+        assert_eq!(ui.sent(), vec![TestEvent(2)]);
+
+        ui.click(Vec2::new(220., 300.), Vec2::new(220., 300.));
+        assert_eq!(ui.sent(), vec![TestEvent(1)]);
+    }
+
+    #[test]
+    fn a_button_is_clicked_through_nodes_that_let_the_pointer_pass() {
+        let mut ui = TestUi::new(1., 1.);
+        ui.spawn_button(1, Vec2::new(300., 300.), BUTTON_SIZE, 1.);
+
+        // The interior of a button, drawn over it.
+        let interior = ui.spawn_node(Vec2::new(300., 300.), BUTTON_SIZE - Vec2::splat(4.), 2.);
+        ui.world.entity_mut(interior).insert(FocusPolicy::Pass);
+
+        ui.click(Vec2::new(300., 300.), Vec2::new(300., 300.));
+        assert_eq!(ui.sent(), vec![TestEvent(1)]);
+
+        // A panel drawn over the button hides it.
+        ui.spawn_node(Vec2::new(300., 300.), BUTTON_SIZE, 3.);
+
+        ui.click(Vec2::new(300., 300.), Vec2::new(300., 300.));
+        assert_eq!(ui.sent(), vec![]);
+    }
+
+    #[test]
+    fn disabled_buttons_are_not_clicked() {
+        let mut ui = TestUi::new(1., 1.);
+        let button = ui.spawn_button(1, Vec2::new(300., 300.), BUTTON_SIZE, 1.);
+        ui.world.entity_mut(button).insert(Disabled);
+
+        ui.click(Vec2::new(300., 300.), Vec2::new(300., 300.));
+        assert_eq!(ui.sent(), vec![]);
+    }
+
+    #[test]
+    fn hit_testing_uses_logical_pixels_under_ui_scaling() {
+        for (backend_scale_factor, ui_scale) in [(1., 1.5), (2., 0.75), (1.25, 1.25)] {
+            let mut ui = TestUi::new(backend_scale_factor, ui_scale);
+            ui.spawn_button(1, Vec2::new(300., 300.), BUTTON_SIZE, 1.);
+
+            // Just inside the right edge.
+            ui.click(Vec2::new(395., 300.), Vec2::new(395., 300.));
+            assert_eq!(ui.sent(), vec![TestEvent(1)], "scale {} x {}", backend_scale_factor, ui_scale);
+
+            // Just outside it, where the button would be if physical pixels were mistaken for
+            // logical ones at a scale above 1.
+            ui.click(Vec2::new(405., 300.), Vec2::new(405., 300.));
+            assert_eq!(ui.sent(), vec![], "scale {} x {}", backend_scale_factor, ui_scale);
         }
     }
-}
 
-pub fn handle_ui_button_click<T: Send + Sync + Copy>(
-    windows: Res<Windows>,
-    button_query: Query<(&UiButton<'static, T>, &Node, &GlobalTransform)>,
-    mut button_evr: EventReader<MouseButtonInput>,
-    mut evw: EventWriter<T>,
-) {
-    let window = windows.get_primary().unwrap();
-
-    for event in button_evr.iter() {
-        if let ElementState::Released = event.state {
-            let position = window.cursor_position();
-            if let Some(position) = position {
-                log::info!("LMB released at {}", position);
-                for (ui_button, node, transform) in button_query.iter() {
-                    if is_position_in_node(position, node, transform) {
-                        let event = *ui_button.0;
-                        evw.send(event);
-                        log::info!("Sending {}", std::any::type_name::<T>());
-                    }
-                }
-            }
+    #[test]
+    fn focus_moves_through_buttons_in_reading_order_and_wraps() {
+        let mut world = World::new();
+        let top_right = world.spawn().id();
+        let top_left = world.spawn().id();
+        let bottom = world.spawn().id();
+        let buttons = [
+            (bottom, Vec2::new(0., 100.)),
+            (top_right, Vec2::new(200., 500.)),
+            (top_left, Vec2::new(100., 500.)),
+        ];
+
+        let mut ui_focus = UiFocus::default();
+        assert_eq!(ui_focus.focused(buttons.into_iter()), None);
+
+        let mut order = vec![];
+        for _ in 0..4 {
+            ui_focus.move_by(1, buttons.len());
+            order.push(ui_focus.focused(buttons.into_iter()).unwrap());
         }
+        assert_eq!(order, vec![top_left, top_right, bottom, top_left]);
+
+        ui_focus = UiFocus::default();
+        ui_focus.move_by(-1, buttons.len());
+        assert_eq!(ui_focus.focused(buttons.into_iter()), Some(bottom));
+
+        // Focus stays on the last button when a menu is rebuilt with fewer.
+        assert_eq!(ui_focus.focused(buttons[1..].iter().copied()), Some(top_right));
     }
 }
-
-fn is_position_in_node(position: Vec2, node: &Node, transform: &GlobalTransform) -> bool {
-    let xmin = transform.translation.x - (node.size.x / 2.);
-    let xmax = transform.translation.x + (node.size.x / 2.);
-    let ymin = transform.translation.y - (node.size.y / 2.);
-    let ymax = transform.translation.y + (node.size.y / 2.);
-
-    position.x >= xmin
-        && position.x <= xmax
-        && position.y >= ymin
-        && position.y <= ymax
-}
\ No newline at end of file
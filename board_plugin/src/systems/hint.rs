@@ -0,0 +1,125 @@
+use crate::components::{HintMarker, HintTask};
+use crate::events::{ShowHintEvent, TileTriggerEvent};
+use crate::resources::*;
+use bevy::log;
+use bevy::prelude::*;
+use bevy::tasks::AsyncComputeTaskPool;
+use futures_lite::future;
+
+/// Starts searching for the best trigger when a hint is asked for. Classic games have their own
+/// hints, found by solving the puzzle, and versus games and spectators have none.
+pub fn request_hint(
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    board: Res<Board>,
+    puzzle_state: Option<Res<PuzzleState>>,
+    thread_pool: Res<AsyncComputeTaskPool>,
+    hint_task_query: Query<Entity, With<HintTask>>,
+    mut show_hint_evr: EventReader<ShowHintEvent>,
+) {
+    if show_hint_evr.iter().count() == 0
+        || matches!(*game_mode, GameMode::Classic(_) | GameMode::Versus | GameMode::Spectate) {
+        return;
+    }
+
+    if puzzle_state.is_some_and(|puzzle_state| { puzzle_state.clicks_remaining == 0 }) {
+        log::info!("No clicks left to give a hint for");
+        return;
+    }
+
+    // Only the latest request matters.
+    for entity in hint_task_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let tile_map = board.tile_map.clone();
+    let task = thread_pool.spawn(async move {
+        Hint::find(&tile_map, HINT_LOOKAHEAD_TICKS)
+    });
+
+    commands.spawn()
+        .insert(Name::new("Hint Task"))
+        .insert(HintTask(task));
+}
+
+pub fn show_hint(
+    mut commands: Commands,
+    board: Res<Board>,
+    hint_marker_query: Query<Entity, With<HintMarker>>,
+    mut hint_task_query: Query<(Entity, &mut HintTask)>,
+) {
+    for (entity, mut hint_task) in hint_task_query.iter_mut() {
+        let hint = match future::block_on(future::poll_once(&mut hint_task.0)) {
+            Some(hint) => hint,
+            None => continue,
+        };
+
+        commands.entity(entity).despawn();
+
+        for entity in hint_marker_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        match hint {
+            Some(hint) => {
+                log::info!("Hint: trigger {} for {} points", hint.coordinates, hint.score);
+                commands.entity(board.entity).with_children(|parent| {
+                    spawn_hint_marker(parent, &hint.coordinates, board.tile_size);
+                });
+            },
+            None => log::info!("No trigger would light a tile"),
+        }
+    }
+}
+
+/// Hints are for the board as it was, so they're cleared once a tile is triggered.
+pub fn clear_hints(
+    mut commands: Commands,
+    hint_marker_query: Query<Entity, With<HintMarker>>,
+    hint_task_query: Query<Entity, With<HintTask>>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+) {
+    if tile_trigger_evr.iter().count() == 0 {
+        return;
+    }
+
+    for entity in hint_marker_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for entity in hint_task_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn teardown_hints(
+    mut commands: Commands,
+    hint_task_query: Query<Entity, With<HintTask>>,
+) {
+    for entity in hint_task_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn spawn_hint_marker(
+    parent: &mut ChildBuilder,
+    coordinates: &Coordinates,
+    tile_size: f32,
+) {
+    parent
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(1., 0.6, 0., 0.8),
+                custom_size: Some(Vec2::splat(tile_size * 0.4)),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(
+                coordinates.x as f32 * tile_size,
+                coordinates.y as f32 * tile_size,
+                2.,
+            ),
+            ..Default::default()
+        })
+        .insert(Name::new(format!("Hint {}", coordinates)))
+        .insert(HintMarker);
+}
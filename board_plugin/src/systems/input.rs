@@ -1,6 +1,6 @@
 use crate::components::MainCamera;
-use crate::events::{TileTriggerEvent, TogglePauseEvent};
-use crate::resources::Board;
+use crate::events::{CycleThemeEvent, ShowHintEvent, ShowSolutionEvent, TileTriggerEvent, TogglePauseEvent, UndoEvent};
+use crate::resources::{Board, Coordinates};
 use bevy::input::ElementState;
 use bevy::input::keyboard::{KeyCode, KeyboardInput};
 use bevy::input::mouse::MouseButtonInput;
@@ -11,6 +11,7 @@ pub fn handle_mouse_input(
     windows: Res<Windows>,
     board: Res<Board>,
     camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    interaction_query: Query<&Interaction>,
     mut button_evr: EventReader<MouseButtonInput>,
     mut tile_trigger_evw: EventWriter<TileTriggerEvent>,
 ) {
@@ -18,32 +19,52 @@ pub fn handle_mouse_input(
 
     let window = windows.get(camera.window).unwrap();
 
+    // Clicks on the UI, such as the minimap, are for the UI rather than the tiles underneath.
+    let is_over_ui = interaction_query.iter().any(|interaction| { *interaction != Interaction::None });
+
     for event in button_evr.iter() {
-        if event.state == ElementState::Pressed && event.button == MouseButton::Left {
-            if let Some(screen_position) = window.cursor_position() {
-                let window_size = Vec2::new(window.width() as f32, window.height() as f32);
-                let gpu_coords = (screen_position / window_size) * 2.0 - Vec2::ONE;
-                let gpu_to_world = camera_transform.compute_matrix() * camera.projection_matrix.inverse();
-                let world_position = gpu_to_world.project_point3(gpu_coords.extend(-1.0));
-                let world_position: Vec2 = world_position.truncate();
-            
-                let tile_position = world_position / board.tile_size;
-                log::info!("LMB pressed at {}", tile_position);
-
-                let coordinates = (
-                    tile_position.x.round() as i32,
-                    tile_position.y.round() as i32
-                ).into();
-
-                tile_trigger_evw.send(TileTriggerEvent(coordinates));
+        if event.state == ElementState::Pressed && event.button == MouseButton::Left && !is_over_ui {
+            if let Some(coordinates) = get_cursor_coordinates(window, camera, camera_transform, board.tile_size) {
+                log::info!("LMB pressed at {}", coordinates);
+                tile_trigger_evw.send(TileTriggerEvent {
+                    coordinates,
+                    owner: None,
+                });
             }
         }
     }
 }
 
+/// Maps the cursor position in `window` to the coordinates of the tile underneath it.
+pub fn get_cursor_coordinates(
+    window: &Window,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    tile_size: f32,
+) -> Option<Coordinates> {
+    let screen_position = window.cursor_position()?;
+
+    let window_size = Vec2::new(window.width(), window.height());
+    let gpu_coords = (screen_position / window_size) * 2.0 - Vec2::ONE;
+    let gpu_to_world = camera_transform.compute_matrix() * camera.projection_matrix.inverse();
+    let world_position = gpu_to_world.project_point3(gpu_coords.extend(-1.0));
+    let world_position: Vec2 = world_position.truncate();
+
+    let tile_position = world_position / tile_size;
+
+    Some((
+        tile_position.x.round() as i32,
+        tile_position.y.round() as i32
+    ).into())
+}
+
 pub fn handle_keyboard_input(
     mut keyboard_evr: EventReader<KeyboardInput>,
     mut toggle_pause_evr: EventWriter<TogglePauseEvent>,
+    mut show_hint_evw: EventWriter<ShowHintEvent>,
+    mut show_solution_evw: EventWriter<ShowSolutionEvent>,
+    mut undo_evw: EventWriter<UndoEvent>,
+    mut cycle_theme_evw: EventWriter<CycleThemeEvent>,
 ) {
     for event in keyboard_evr.iter() {
         if let ElementState::Released = event.state {
@@ -52,6 +73,22 @@ pub fn handle_keyboard_input(
                     toggle_pause_evr.send(TogglePauseEvent);
                     log::info!("Pause toggled (key code: {:?})", key_code);
                 }
+
+                if key_code == KeyCode::H {
+                    show_hint_evw.send(ShowHintEvent);
+                }
+
+                if key_code == KeyCode::S {
+                    show_solution_evw.send(ShowSolutionEvent);
+                }
+
+                if key_code == KeyCode::U {
+                    undo_evw.send(UndoEvent);
+                }
+
+                if key_code == KeyCode::T {
+                    cycle_theme_evw.send(CycleThemeEvent);
+                }
             }
         }
     }
@@ -1,34 +1,179 @@
+use crate::components::Score;
 use crate::events::*;
-use crate::resources::Board;
+use crate::resources::{
+    Board, ClassicPuzzle, GameHistory, GameMode, GameStats, Locale, Player, PuzzleState, Versus,
+};
 use crate::tick::UpdateTickTimer;
 use bevy::log;
 use bevy::prelude::*;
 
-
+#[allow(clippy::too_many_arguments)]
 pub fn handle_tile_trigger(
+    game_mode: Res<GameMode>,
     mut board: ResMut<Board>,
+    mut puzzle_state: Option<ResMut<PuzzleState>>,
+    mut classic_puzzle: Option<ResMut<ClassicPuzzle>>,
+    mut game_history: Option<ResMut<GameHistory>>,
+    game_stats: Res<GameStats>,
+    versus: Option<Res<Versus>>,
+    score_query: Query<&Score>,
     mut tile_trigger_evr: EventReader<TileTriggerEvent>,
     mut tile_update_evw: EventWriter<TileUpdateEvent>,
+    mut wasted_click_evw: EventWriter<WastedClickEvent>,
+    mut tile_lit_evw: EventWriter<TileLitEvent>,
+    mut loop_closed_evw: EventWriter<LoopClosedEvent>,
+    mut trigger_score_evw: EventWriter<TriggerScoreEvent>,
 ) {
+    // Online triggers are sent to the server, which lights the tiles, and spectators can't trigger.
+    if game_mode.is_remote() {
+        return;
+    }
+
+    // The mouse belongs to player one in versus games.
+    let mouse_owner = versus.map(|_| { Player::One });
+
     for event in tile_trigger_evr.iter() {
-        let coordinates = &event.0;
-        log::info!("Enlightening tile {}", coordinates);
-        board.tile_map.make_tile_white(coordinates.x, coordinates.y);
+        let coordinates = &event.coordinates;
+        let owner = event.owner.or(mouse_owner);
+
+        if let Some(classic_puzzle) = classic_puzzle.as_mut() {
+            if !classic_puzzle.is_solved && ClassicPuzzle::toggle(&mut board.tile_map, *coordinates) {
+                log::info!("Toggling tile {}", coordinates);
+                classic_puzzle.moves += 1;
+            }
+            continue;
+        }
+
+        if let Some(puzzle_state) = puzzle_state.as_ref() {
+            if puzzle_state.clicks_remaining == 0 {
+                log::info!("No clicks remaining, ignoring tile {}", coordinates);
+                continue;
+            }
+        }
+
+        let previous_tile_map = board.tile_map.clone();
+
+        let was_white = board.tile_map.get(coordinates).is_some_and(|tile| { tile.is_white() });
+        let enclosed_before = board.tile_map.enclosed_tiles();
+
+        log::info!("Enlightening tile {} for {:?}", coordinates, owner);
+        let is_lit = board.tile_map.make_tile_white_as(coordinates.x, coordinates.y, owner);
+        if !is_lit || was_white {
+            wasted_click_evw.send(WastedClickEvent(*coordinates));
+        }
+        if !is_lit {
+            continue;
+        }
+
+        tile_lit_evw.send(TileLitEvent(*coordinates));
+
+        let newly_enclosed: Vec<_> = board.tile_map.enclosed_tiles().difference(&enclosed_before).copied().collect();
+        if !newly_enclosed.is_empty() {
+            log::info!("Closed a loop around {} tiles", newly_enclosed.len());
+            loop_closed_evw.send(LoopClosedEvent(newly_enclosed));
+        }
+
+        let clicks_remaining = puzzle_state.as_ref().map(|puzzle_state| { puzzle_state.clicks_remaining });
+
+        if let Some(game_history) = game_history.as_mut() {
+            let score = score_query.get_single().map_or(0, |score| { score.0 });
+            game_history.record_move(&previous_tile_map, score, clicks_remaining, &game_stats);
+        }
+
+        trigger_score_evw.send(TriggerScoreEvent {
+            coordinates: *coordinates,
+            previous_tile_map,
+            tile_map: board.tile_map.clone(),
+        });
+
+        if let Some(puzzle_state) = puzzle_state.as_mut() {
+            puzzle_state.clicks_remaining -= 1;
+        }
     }
 
     tile_update_evw.send(TileUpdateEvent);
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update(
+    game_mode: Res<GameMode>,
     mut board: ResMut<Board>,
+    classic_puzzle: Option<Res<ClassicPuzzle>>,
+    puzzle_state: Option<Res<PuzzleState>>,
+    mut game_history: Option<ResMut<GameHistory>>,
+    game_stats: Res<GameStats>,
+    score_query: Query<&Score>,
     time: Res<Time>,
     mut timer: ResMut<UpdateTickTimer>,
     mut tile_update_evw: EventWriter<TileUpdateEvent>,
 ) {
+    // Classic tiles never fade, and remote tiles fade wherever the game is run.
+    if classic_puzzle.is_some() || game_mode.is_remote() {
+        return;
+    }
+
     if !timer.0.tick(time.time_since_startup()).just_finished() {
         return;
     }
 
+    if let Some(game_history) = game_history.as_mut() {
+        let score = score_query.get_single().map_or(0, |score| { score.0 });
+        let clicks_remaining = puzzle_state.map(|puzzle_state| { puzzle_state.clicks_remaining });
+        game_history.record_tick(&board.tile_map, score, clicks_remaining, &game_stats);
+    }
+
     board.tile_map.tick_update();
     tile_update_evw.send(TileUpdateEvent);
 }
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle_undo(
+    locale: Res<Locale>,
+    mut board: ResMut<Board>,
+    mut puzzle_state: Option<ResMut<PuzzleState>>,
+    mut game_history: Option<ResMut<GameHistory>>,
+    mut game_stats: ResMut<GameStats>,
+    mut score_query: Query<(&mut Score, &mut Text)>,
+    mut undo_evr: EventReader<UndoEvent>,
+    mut tile_update_evw: EventWriter<TileUpdateEvent>,
+) {
+    for _ in undo_evr.iter() {
+        let game_history = match game_history.as_mut() {
+            Some(game_history) => game_history,
+            None => {
+                log::info!("Undo isn't allowed in this mode");
+                continue;
+            },
+        };
+
+        let snapshot = match game_history.undo_move() {
+            Some(snapshot) => snapshot,
+            None => {
+                log::info!("No moves to undo");
+                continue;
+            },
+        };
+
+        log::info!("Undoing last move");
+
+        board.tile_map = snapshot.tile_map;
+
+        for (mut score, mut text) in score_query.iter_mut() {
+            score.0 = snapshot.score;
+            text.sections[0].value = locale.format("hud-score", &[("score", locale.format_number(score.0))]);
+        }
+
+        // Time spent playing isn't given back by undoing a move, and the undo itself is remembered.
+        *game_stats = GameStats {
+            undos: game_stats.undos + 1,
+            survival_time: game_stats.survival_time,
+            ..snapshot.stats
+        };
+
+        if let (Some(puzzle_state), Some(clicks_remaining)) = (puzzle_state.as_mut(), snapshot.clicks_remaining) {
+            puzzle_state.clicks_remaining = clicks_remaining;
+        }
+
+        tile_update_evw.send(TileUpdateEvent);
+    }
+}
@@ -0,0 +1,368 @@
+use crate::components::{Disabled, MenuPanel, UiButton, UiHighlightable, UiRoot};
+use crate::resources::UiFocus;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use std::fmt::Debug;
+
+/// A screen of the UI described as data: a column of text, buttons that send events, and any
+/// custom nodes, built by [`spawn_menu`] and removed by [`teardown_menu`].
+pub struct Menu<'a> {
+    name: String,
+    margin: f32,
+    is_overlay: bool,
+    items: Vec<MenuItem<'a>>,
+    corner_button: Option<MenuButton>,
+}
+
+enum MenuItem<'a> {
+    Text(MenuText),
+    Buttons {
+        width: f32,
+        buttons: Vec<MenuButton>,
+    },
+    Custom(Box<dyn FnOnce(&mut ChildBuilder) + 'a>),
+}
+
+impl<'a> Menu<'a> {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            margin: 96.,
+            is_overlay: false,
+            items: vec![],
+            corner_button: None,
+        }
+    }
+
+    /// Sets the space above and below the menu.
+    pub fn with_margin(mut self, margin: f32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Draws the menu over whatever else is on screen, such as the board and score.
+    pub fn overlay(mut self) -> Self {
+        self.is_overlay = true;
+        self
+    }
+
+    pub fn title(self, text: impl Into<String>) -> Self {
+        self.text(MenuText::new(text, 32., Color::WHITE))
+    }
+
+    pub fn text(mut self, text: MenuText) -> Self {
+        self.items.push(MenuItem::Text(text));
+        self
+    }
+
+    /// A column of buttons, no wider than `width`.
+    pub fn buttons(mut self, width: f32, buttons: Vec<MenuButton>) -> Self {
+        self.items.push(MenuItem::Buttons { width, buttons });
+        self
+    }
+
+    /// Nodes that don't fit the other items, built in their place in the column.
+    pub fn custom(mut self, build: impl FnOnce(&mut ChildBuilder) + 'a) -> Self {
+        self.items.push(MenuItem::Custom(Box::new(build)));
+        self
+    }
+
+    /// A small button in the top right corner of the window.
+    pub fn corner_button(mut self, button: MenuButton) -> Self {
+        self.corner_button = Some(button);
+        self
+    }
+}
+
+/// A block of centred text, made of sections that can each have their own size and colour.
+pub struct MenuText {
+    sections: Vec<(String, f32, Color)>,
+    margin: f32,
+}
+
+impl MenuText {
+    pub fn new(text: impl Into<String>, font_size: f32, color: Color) -> Self {
+        Self {
+            sections: vec![(text.into(), font_size, color)],
+            margin: 16.,
+        }
+    }
+
+    pub fn with_section(mut self, text: impl Into<String>, font_size: f32, color: Color) -> Self {
+        self.sections.push((text.into(), font_size, color));
+        self
+    }
+
+    /// Sets the space around the text.
+    pub fn with_margin(mut self, margin: f32) -> Self {
+        self.margin = margin;
+        self
+    }
+}
+
+pub struct MenuButton {
+    text: String,
+    name: String,
+    font_size: f32,
+    on_click: UiButton,
+    is_disabled: bool,
+}
+
+impl MenuButton {
+    /// A button that sends `on_click_event` when it's clicked.
+    pub fn new<T>(text: impl Into<String>, name: &str, on_click_event: T) -> Self
+    where
+        T: Debug + Clone + Send + Sync + 'static,
+    {
+        Self {
+            text: text.into(),
+            name: name.to_string(),
+            font_size: 48.,
+            on_click: UiButton::new(on_click_event),
+            is_disabled: false,
+        }
+    }
+
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    /// Greys the button out so it can't be clicked or focused, while it still shows what it
+    /// would do.
+    pub fn disabled(mut self, is_disabled: bool) -> Self {
+        self.is_disabled = is_disabled;
+        self
+    }
+}
+
+/// Builds `menu` under the UI root.
+pub fn spawn_menu(
+    commands: &mut Commands,
+    ui_root: &UiRoot,
+    font: &Handle<Font>,
+    menu: Menu,
+) {
+    let Menu { name, margin, is_overlay, items, corner_button } = menu;
+
+    commands.entity(ui_root.0).with_children(|parent| {
+        parent
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                    position_type: if is_overlay { PositionType::Absolute } else { PositionType::Relative },
+                    margin: Rect {
+                        left: Val::Px(128.),
+                        right: Val::Px(128.),
+                        top: Val::Px(margin),
+                        bottom: Val::Px(margin),
+                    },
+                    flex_direction: FlexDirection::ColumnReverse,
+                    ..Default::default()
+                },
+                color: Color::NONE.into(),
+                ..Default::default()
+            })
+            .insert(Name::new(format!("{} Panel", name)))
+            .insert(MenuPanel)
+            .with_children(|parent| {
+                for item in items {
+                    match item {
+                        MenuItem::Text(text) => spawn_text(parent, font, &name, text),
+                        MenuItem::Buttons { width, buttons } => spawn_buttons(parent, font, &name, width, buttons),
+                        MenuItem::Custom(build) => build(parent),
+                    }
+                }
+            });
+
+        if let Some(button) = corner_button {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        position: Rect {
+                            top: Val::Px(16.),
+                            right: Val::Px(16.),
+                            ..Default::default()
+                        },
+                        size: Size::new(Val::Px(128.), Val::Auto),
+                        flex_direction: FlexDirection::ColumnReverse,
+                        ..Default::default()
+                    },
+                    color: Color::NONE.into(),
+                    ..Default::default()
+                })
+                .insert(Name::new(format!("{} Corner", name)))
+                .insert(MenuPanel)
+                .with_children(|parent| {
+                    spawn_button(parent, font, button);
+                });
+        }
+    });
+}
+
+/// Removes every menu built by [`spawn_menu`], and the keyboard focus with it.
+pub fn teardown_menu(
+    mut commands: Commands,
+    mut ui_focus: ResMut<UiFocus>,
+    menu_query: Query<Entity, With<MenuPanel>>,
+) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    ui_focus.0 = None;
+}
+
+fn spawn_text(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    menu_name: &str,
+    text: MenuText,
+) {
+    let sections = text.sections.into_iter()
+        .map(|(value, font_size, color)| {
+            TextSection {
+                value,
+                style: TextStyle {
+                    font: font.clone(),
+                    font_size,
+                    color,
+                },
+            }
+        })
+        .collect();
+
+    parent
+        .spawn_bundle(TextBundle {
+            style: Style {
+                align_self: AlignSelf::Center,
+                margin: Rect::all(Val::Px(text.margin)),
+                ..Default::default()
+            },
+            text: Text {
+                sections,
+                alignment: TextAlignment {
+                    vertical: VerticalAlign::Center,
+                    horizontal: HorizontalAlign::Center,
+                },
+            },
+            ..Default::default()
+        })
+        .insert(Name::new(format!("{} Text", menu_name)));
+}
+
+fn spawn_buttons(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    menu_name: &str,
+    width: f32,
+    buttons: Vec<MenuButton>,
+) {
+    parent
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                max_size: Size {
+                    width: Val::Px(width),
+                    ..Default::default()
+                },
+                margin: Rect::all(Val::Auto),
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::ColumnReverse,
+                ..Default::default()
+            },
+            color: Color::NONE.into(),
+            ..Default::default()
+        })
+        .insert(Name::new(format!("{} Buttons", menu_name)))
+        .with_children(|parent| {
+            for button in buttons {
+                spawn_button(parent, font, button);
+            }
+        });
+}
+
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    button: MenuButton,
+) {
+    let MenuButton { text, name, font_size, on_click, is_disabled } = button;
+
+    let color = if is_disabled { Color::DARK_GRAY } else { Color::WHITE };
+
+    let mut root = parent.spawn_bundle(NodeBundle {
+        style: Style {
+            size: Size::new(Val::Percent(100.), Val::Px(64.)),
+            padding: Rect::all(Val::Px(2.)),
+            margin: Rect {
+                bottom: Val::Px(8.),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        color: color.into(),
+        ..Default::default()
+    });
+    let root_entity = root.id();
+
+    root.insert(Name::new(format!("{} Button", name)))
+        .insert(on_click)
+        .insert(Interaction::default());
+    if is_disabled {
+        root.insert(Disabled);
+    }
+
+    root.with_children(|parent| {
+        parent
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::FlexEnd,
+                    ..Default::default()
+                },
+                color: Color::BLACK.into(),
+                ..Default::default()
+            })
+            .insert(Name::new(format!("{} Button Interior", name)))
+            // The interior covers the button, so it lets the pointer through to the root.
+            .insert(FocusPolicy::Pass)
+            .insert(UiHighlightable {
+                default_color: Color::BLACK,
+                hover_color: Color::WHITE,
+                pressed_color: Color::WHITE,
+                root_entity,
+            })
+            .with_children(|parent| {
+                parent
+                    .spawn_bundle(TextBundle {
+                        style: Style {
+                            align_self: AlignSelf::Center,
+                            ..Default::default()
+                        },
+                        text: Text::with_section(
+                            text,
+                            TextStyle {
+                                font: font.clone(),
+                                font_size,
+                                color,
+                            },
+                            TextAlignment {
+                                vertical: VerticalAlign::Center,
+                                horizontal: HorizontalAlign::Center,
+                            },
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(Name::new(format!("{} Button Text", name)))
+                    .insert(UiHighlightable {
+                        default_color: color,
+                        hover_color: Color::BLACK,
+                        pressed_color: Color::BLACK,
+                        root_entity,
+                    });
+            });
+    });
+}
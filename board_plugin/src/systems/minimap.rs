@@ -0,0 +1,367 @@
+use crate::components::{MainCamera, MinimapMap, MinimapPanel, MinimapViewport, OffscreenArrow, ThemedUi, UiRoot};
+use crate::events::TileUpdateEvent;
+use crate::resources::{Board, Coordinates, EffectTextures, GameMode, Minimap, Settings, Theme, TileKind, TileMap};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, FilterMode, TextureDimension, TextureFormat};
+use bevy::ui::FocusPolicy;
+
+/// How many pixels across the minimap is.
+const MINIMAP_SIZE: f32 = 160.;
+const ARROW_SIZE: f32 = 24.;
+/// How far arrows are kept from the edges of the screen.
+const ARROW_INSET: f32 = 16.;
+/// The most arrows shown at once. They point to the nearest lit tiles out of view.
+const MAX_ARROWS: usize = 8;
+
+/// The parts of an arrow that move it around the edge of the screen.
+type ArrowParts<'a> = (&'a mut Style, &'a mut Transform, &'a mut Visibility);
+
+/// Every node the minimap adds to the UI.
+type MinimapUi = Or<(With<MinimapPanel>, With<OffscreenArrow>)>;
+
+pub fn setup_minimap(
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    effect_textures: Res<EffectTextures>,
+    mut images: ResMut<Assets<Image>>,
+    ui_root_query: Query<&UiRoot>,
+) {
+    if !game_mode.has_minimap() {
+        return;
+    }
+
+    // The map is drawn by `update_minimap` once the board is ready.
+    let image = images.add(Image::default());
+    commands.insert_resource(Minimap::new(image.clone()));
+
+    let ui_root = ui_root_query.single();
+
+    commands.entity(ui_root.0).with_children(|parent| {
+        parent
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        top: Val::Px(16.),
+                        right: Val::Px(16.),
+                        ..Default::default()
+                    },
+                    size: Size::new(Val::Px(MINIMAP_SIZE), Val::Px(MINIMAP_SIZE)),
+                    padding: Rect::all(Val::Px(2.)),
+                    ..Default::default()
+                },
+                color: Color::DARK_GRAY.into(),
+                ..Default::default()
+            })
+            .insert(Name::new("Minimap Panel"))
+            .insert(MinimapPanel)
+            .with_children(|parent| {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                            overflow: Overflow::Hidden,
+                            ..Default::default()
+                        },
+                        color: Color::WHITE.into(),
+                        image: image.into(),
+                        ..Default::default()
+                    })
+                    .insert(Name::new("Minimap"))
+                    .insert(MinimapMap)
+                    .insert(Interaction::default())
+                    // The map is already drawn in the theme's colours, so it mustn't be tinted.
+                    .insert(ThemedUi {
+                        color: None,
+                        text_colors: vec![],
+                        highlight_colors: None,
+                    })
+                    .with_children(|parent| {
+                        parent
+                            .spawn_bundle(NodeBundle {
+                                style: Style {
+                                    position_type: PositionType::Absolute,
+                                    ..Default::default()
+                                },
+                                color: Color::rgba(1., 1., 1., 0.25).into(),
+                                ..Default::default()
+                            })
+                            .insert(Name::new("Minimap Viewport"))
+                            .insert(MinimapViewport)
+                            .insert(FocusPolicy::Pass);
+                    });
+            });
+
+        for _ in 0..MAX_ARROWS {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        size: Size::new(Val::Px(ARROW_SIZE), Val::Px(ARROW_SIZE)),
+                        ..Default::default()
+                    },
+                    color: Color::WHITE.into(),
+                    image: effect_textures.arrow.clone().into(),
+                    visibility: Visibility { is_visible: false },
+                    ..Default::default()
+                })
+                .insert(Name::new("Offscreen Arrow"))
+                .insert(OffscreenArrow)
+                .insert(FocusPolicy::Pass);
+        }
+    });
+}
+
+/// Redraws the minimap around the lit tiles, and finds the lit regions, whenever the board
+/// changes.
+pub fn update_minimap(
+    theme: Res<Theme>,
+    board: Res<Board>,
+    minimap: Option<ResMut<Minimap>>,
+    mut images: ResMut<Assets<Image>>,
+    mut tile_update_evr: EventReader<TileUpdateEvent>,
+) {
+    let is_board_updated = tile_update_evr.iter().count() > 0;
+
+    let mut minimap = match minimap {
+        Some(minimap) => minimap,
+        None => return,
+    };
+
+    if !is_board_updated && !minimap.is_added() && !theme.is_changed() {
+        return;
+    }
+
+    // Once every tile has gone dark, the map stays where the light was last.
+    if let Some(bounds) = board.tile_map.lit_bounds() {
+        minimap.fit(bounds);
+    }
+
+    minimap.clusters = board.tile_map.lit_clusters()
+        .iter()
+        .filter_map(|cluster| { Coordinates::bounds(cluster) })
+        .collect();
+
+    let image = minimap_image(&minimap, &board.tile_map, &theme);
+    if let Some(existing_image) = images.get_mut(&minimap.image) {
+        *existing_image = image;
+    }
+}
+
+/// Moves the camera to wherever the minimap is clicked, and follows the pointer while it's
+/// dragged, stopping at the edges of the map.
+pub fn handle_minimap_input(
+    windows: Res<Windows>,
+    board: Res<Board>,
+    minimap: Option<Res<Minimap>>,
+    map_query: Query<(&Interaction, &Node, &GlobalTransform), With<MinimapMap>>,
+    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+) {
+    let minimap = match minimap {
+        Some(minimap) => minimap,
+        None => return,
+    };
+
+    let position = match windows.get_primary().and_then(|window| { window.cursor_position() }) {
+        Some(position) => position,
+        None => return,
+    };
+
+    for (interaction, node, transform) in map_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        let bottom_left = transform.translation.truncate() - node.size / 2.;
+        let point = ((position - bottom_left) / node.size).clamp(Vec2::ZERO, Vec2::ONE);
+        let target = minimap.to_board(point) * board.tile_size;
+
+        for mut camera_transform in camera_query.iter_mut() {
+            camera_transform.translation.x = target.x;
+            camera_transform.translation.y = target.y;
+        }
+    }
+}
+
+/// Outlines the part of the board the camera can see on the minimap.
+pub fn update_minimap_viewport(
+    windows: Res<Windows>,
+    board: Res<Board>,
+    minimap: Option<Res<Minimap>>,
+    camera_query: Query<(&Transform, &OrthographicProjection), With<MainCamera>>,
+    mut viewport_query: Query<&mut Style, With<MinimapViewport>>,
+) {
+    let minimap = match minimap {
+        Some(minimap) => minimap,
+        None => return,
+    };
+
+    let (window, (camera_transform, projection)) = match (windows.get_primary(), camera_query.get_single()) {
+        (Some(window), Ok(camera)) => (window, camera),
+        _ => return,
+    };
+
+    let (min, max) = visible_area(window, camera_transform, projection, board.tile_size);
+    let min = minimap.to_map(min) * 100.;
+    let max = minimap.to_map(max) * 100.;
+
+    let position = Rect {
+        left: Val::Percent(min.x),
+        bottom: Val::Percent(min.y),
+        ..Default::default()
+    };
+    let size = Size::new(Val::Percent(max.x - min.x), Val::Percent(max.y - min.y));
+
+    for mut style in viewport_query.iter_mut() {
+        // Only changed styles are written, so the UI isn't laid out again every frame.
+        if style.position != position || style.size != size {
+            style.position = position;
+            style.size = size;
+        }
+    }
+}
+
+/// Points arrows from the edges of the screen towards the nearest lit regions the camera can't
+/// see.
+pub fn update_offscreen_arrows(
+    windows: Res<Windows>,
+    settings: Res<Settings>,
+    board: Res<Board>,
+    minimap: Option<Res<Minimap>>,
+    camera_query: Query<(&Transform, &OrthographicProjection), With<MainCamera>>,
+    mut arrow_query: Query<ArrowParts, (With<OffscreenArrow>, Without<MainCamera>)>,
+) {
+    let minimap = match minimap {
+        Some(minimap) => minimap,
+        None => return,
+    };
+
+    let (window, (camera_transform, projection)) = match (windows.get_primary(), camera_query.get_single()) {
+        (Some(window), Ok(camera)) => (window, camera),
+        _ => return,
+    };
+
+    let (min, max) = visible_area(window, camera_transform, projection, board.tile_size);
+    let center = (min + max) / 2.;
+
+    // Offsets from the middle of the screen to each region out of view, nearest first. Tiles
+    // reach half a tile past their coordinates.
+    let mut offsets: Vec<Vec2> = minimap.clusters.iter()
+        .map(|(cluster_min, cluster_max)| {
+            (
+                Vec2::new(cluster_min.x as f32, cluster_min.y as f32) - Vec2::splat(0.5),
+                Vec2::new(cluster_max.x as f32, cluster_max.y as f32) + Vec2::splat(0.5),
+            )
+        })
+        .filter(|(cluster_min, cluster_max)| {
+            cluster_max.x < min.x || cluster_min.x > max.x || cluster_max.y < min.y || cluster_min.y > max.y
+        })
+        .map(|(cluster_min, cluster_max)| { (cluster_min + cluster_max) / 2. - center })
+        .collect();
+    offsets.sort_by(|a, b| { a.length_squared().total_cmp(&b.length_squared()) });
+
+    // The arrows are sized by the UI scale, but placed here in unscaled pixels.
+    let arrow_size = ARROW_SIZE * settings.ui_scale as f32;
+    let half_screen = Vec2::new(window.width(), window.height()) / 2.;
+    let reach = half_screen - Vec2::splat(ARROW_INSET * settings.ui_scale as f32 + arrow_size / 2.);
+
+    for (i, (mut style, mut transform, mut visibility)) in arrow_query.iter_mut().enumerate() {
+        let direction = match offsets.get(i) {
+            Some(offset) => offset.normalize(),
+            None => {
+                if visibility.is_visible {
+                    visibility.is_visible = false;
+                }
+                continue;
+            },
+        };
+
+        // Follow the direction out from the middle of the screen until it meets the inset edge.
+        let distance = (reach.x / direction.x.abs()).min(reach.y / direction.y.abs());
+        let arrow_center = half_screen + direction * distance;
+
+        let position = Rect {
+            left: Val::Px(arrow_center.x - arrow_size / 2.),
+            bottom: Val::Px(arrow_center.y - arrow_size / 2.),
+            ..Default::default()
+        };
+        if style.position != position {
+            style.position = position;
+        }
+
+        transform.rotation = Quat::from_rotation_z(direction.y.atan2(direction.x));
+        visibility.is_visible = true;
+    }
+}
+
+pub fn teardown_minimap(
+    mut commands: Commands,
+    minimap_query: Query<Entity, MinimapUi>,
+) {
+    for entity in minimap_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    commands.remove_resource::<Minimap>();
+}
+
+/// The part of the board the camera can see, as its bottom left and top right corners measured
+/// in tiles.
+fn visible_area(
+    window: &Window,
+    camera_transform: &Transform,
+    projection: &OrthographicProjection,
+    tile_size: f32,
+) -> (Vec2, Vec2) {
+    let center = camera_transform.translation.truncate();
+    let half_size = Vec2::new(window.width(), window.height()) / 2. * projection.scale;
+
+    ((center - half_size) / tile_size, (center + half_size) / tile_size)
+}
+
+/// Draws the part of the board the minimap shows, one pixel per tile. Dark and missing tiles are
+/// left as the background.
+fn minimap_image(minimap: &Minimap, tile_map: &TileMap, theme: &Theme) -> Image {
+    let size = minimap.size;
+    let background = theme.background();
+
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    // Image rows run down from the top, and the board's rows run up.
+    for row in (0..size).rev() {
+        for column in 0..size {
+            let coordinates = Coordinates::from((minimap.origin.x + column, minimap.origin.y + row));
+
+            let color = match tile_map.get(&coordinates) {
+                Some(tile) if tile.is_not_black() || tile.kind == TileKind::Wall => {
+                    theme.tile_color(tile.lightness, tile.kind, tile.owner)
+                },
+                _ => background,
+            };
+
+            let [r, g, b, _] = color.as_rgba_f32();
+            data.extend_from_slice(&[
+                (r * 255.).round() as u8,
+                (g * 255.).round() as u8,
+                (b * 255.).round() as u8,
+                255,
+            ]);
+        }
+    }
+
+    let mut image = Image::new(
+        Extent3d {
+            width: size as u32,
+            height: size as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+
+    // Keep tiles as sharp squares when the map is stretched to fill its frame.
+    image.sampler_descriptor.mag_filter = FilterMode::Nearest;
+    image.sampler_descriptor.min_filter = FilterMode::Nearest;
+
+    image
+}
@@ -1,6 +1,24 @@
+pub mod achievements;
+pub mod classic;
+pub mod daily;
+pub mod editor;
+pub mod effects;
+pub mod floor;
 pub mod game_state;
 pub mod game_ui;
+pub mod hint;
 pub mod input;
 pub mod lighting;
+pub mod menu;
+pub mod minimap;
+pub mod online;
+pub mod particles;
+pub mod popups;
+pub mod puzzle;
 pub mod scoring;
+pub mod settings;
+pub mod spectator;
+pub mod stats;
+pub mod theme;
 pub mod tile;
+pub mod versus;
\ No newline at end of file
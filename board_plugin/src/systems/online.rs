@@ -0,0 +1,168 @@
+use crate::components::{ConnectTask, Score};
+use crate::events::*;
+use crate::network::{GameClient, ServerMessage};
+use crate::resources::{Board, GameMode, Locale, OnlineConfig, Spectator};
+use crate::systems::game_state::GameState;
+use bevy::log;
+use bevy::prelude::*;
+use bevy::tasks::AsyncComputeTaskPool;
+use futures_lite::future;
+
+/// Starts connecting to the server. Connecting can take a while, so it's done on another thread
+/// and the game starts once it's connected.
+pub fn handle_start_online_event(
+    mut commands: Commands,
+    online_config: Option<Res<OnlineConfig>>,
+    thread_pool: Res<AsyncComputeTaskPool>,
+    connect_task_query: Query<&ConnectTask>,
+    mut start_online_evr: EventReader<StartOnlineEvent>,
+) {
+    if start_online_evr.iter().count() == 0 {
+        return;
+    }
+
+    let online_config = match online_config.as_ref() {
+        Some(online_config) => online_config,
+        None => {
+            log::error!("No server to connect to");
+            return;
+        },
+    };
+
+    connect(&mut commands, &thread_pool, &connect_task_query, &online_config.addr, GameMode::Online);
+}
+
+/// Starts connecting to the server at `addr` on another thread, unless a connection is already being
+/// made. `poll_connection` starts the game in `game_mode` once it's connected.
+pub fn connect(
+    commands: &mut Commands,
+    thread_pool: &AsyncComputeTaskPool,
+    connect_task_query: &Query<&ConnectTask>,
+    addr: &str,
+    game_mode: GameMode,
+) {
+    if !connect_task_query.is_empty() {
+        log::info!("Already connecting to {}", addr);
+        return;
+    }
+
+    log::info!("Connecting to {}", addr);
+
+    let addr = addr.to_string();
+    let reconnect_pool = thread_pool.0.clone();
+    let task = thread_pool.spawn(async move {
+        GameClient::connect(&addr, reconnect_pool)
+    });
+
+    commands.spawn()
+        .insert(Name::new("Connect Task"))
+        .insert(ConnectTask {
+            task,
+            game_mode,
+        });
+}
+
+/// Starts the online game, or spectating, once the connection is made.
+pub fn poll_connection(
+    mut commands: Commands,
+    mut game_state: ResMut<State<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    mut connect_task_query: Query<(Entity, &mut ConnectTask)>,
+) {
+    for (entity, mut connect_task) in connect_task_query.iter_mut() {
+        let result = match future::block_on(future::poll_once(&mut connect_task.task)) {
+            Some(result) => result,
+            None => continue,
+        };
+
+        commands.entity(entity).despawn();
+
+        match result {
+            Ok(game_client) => {
+                match connect_task.game_mode {
+                    GameMode::Spectate => commands.insert_resource(Spectator::live(game_client)),
+                    _ => commands.insert_resource(game_client),
+                }
+                *game_mode = connect_task.game_mode;
+                GameState::InGame.transition(&mut game_state);
+            },
+            Err(e) => log::error!("Can't connect to the server: {}", e),
+        }
+    }
+}
+
+/// Gives up connecting when the main menu is left some other way.
+pub fn cancel_connecting(
+    mut commands: Commands,
+    connect_task_query: Query<Entity, With<ConnectTask>>,
+) {
+    for entity in connect_task_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Sends clicks to the server rather than lighting tiles here. The server decides what they do.
+pub fn send_triggers(
+    game_client: Option<ResMut<GameClient>>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+) {
+    let mut game_client = match game_client {
+        Some(game_client) => game_client,
+        None => return,
+    };
+
+    for event in tile_trigger_evr.iter() {
+        log::info!("Sending trigger for tile {} at tick {}", event.coordinates, game_client.tick());
+        game_client.trigger(event.coordinates);
+    }
+}
+
+/// Brings the board and score up to date with the server.
+pub fn poll_server(
+    mut board: ResMut<Board>,
+    game_client: Option<ResMut<GameClient>>,
+    locale: Res<Locale>,
+    mut score_query: Query<(&mut Score, &mut Text)>,
+    mut tile_update_evw: EventWriter<TileUpdateEvent>,
+    mut tick_evw: EventWriter<TickEvent>,
+    mut game_over_evw: EventWriter<GameOverEvent>,
+) {
+    let mut game_client = match game_client {
+        Some(game_client) => game_client,
+        None => return,
+    };
+
+    let (mut score, mut text) = score_query.single_mut();
+
+    let mut is_changed = false;
+    for message in game_client.poll() {
+        match message {
+            ServerMessage::Welcome { player, .. } => log::info!("Playing online as player {}", player),
+            ServerMessage::Snapshot { score: new_score, .. } => {
+                score.0 = new_score;
+                is_changed = true;
+            },
+            ServerMessage::Delta { score: new_score, .. } => {
+                tick_evw.send(TickEvent {
+                    score: new_score,
+                    score_increment: new_score.saturating_sub(score.0),
+                });
+                score.0 = new_score;
+                is_changed = true;
+            },
+            ServerMessage::GameOver { score, .. } => game_over_evw.send(GameOverEvent(score)),
+        }
+    }
+
+    if is_changed {
+        board.tile_map = game_client.tile_map().clone();
+        text.sections[0].value = locale.format("hud-score", &[("score", locale.format_number(score.0))]);
+        tile_update_evw.send(TileUpdateEvent);
+    }
+}
+
+pub fn disconnect(
+    mut commands: Commands,
+) {
+    commands.remove_resource::<GameClient>();
+}
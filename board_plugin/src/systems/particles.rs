@@ -0,0 +1,198 @@
+use crate::components::Particle;
+use crate::events::{LoopClosedEvent, TileFadedOutEvent, TileLitEvent};
+use crate::resources::{Board, EffectTextures, EntityPool, Settings, Theme, TileKind, MAX_LIGHTNESS};
+use crate::systems::tile::tile_translation;
+use bevy::prelude::*;
+use rand::Rng;
+use std::f32::consts::TAU;
+
+/// The most particles alive at once, however big the board gets.
+const MAX_PARTICLES: usize = 384;
+
+/// How a group of particles is thrown out.
+struct Emission {
+    count: usize,
+    /// The range of starting speeds, in tiles per second.
+    speed: (f32, f32),
+    /// The range of lifetimes, in seconds.
+    lifetime: (f32, f32),
+    /// The range of sizes, in tiles.
+    size: (f32, f32),
+    drag: f32,
+    /// Upward drift, in tiles per second per second.
+    lift: f32,
+}
+
+/// Quick, bright sparks from a triggered tile.
+const SPARKS: Emission = Emission {
+    count: 10,
+    speed: (4., 9.),
+    lifetime: (0.2, 0.45),
+    size: (0.15, 0.3),
+    drag: 4.,
+    lift: 0.,
+};
+
+/// Slow, dim embers rising from a tile whose light went out.
+const EMBERS: Emission = Emission {
+    count: 2,
+    speed: (0.1, 0.5),
+    lifetime: (0.8, 1.6),
+    size: (0.1, 0.2),
+    drag: 1.,
+    lift: 1.5,
+};
+
+/// Particles from each newly surrounded space when a loop closes.
+const BURST: Emission = Emission {
+    count: 4,
+    speed: (2., 6.),
+    lifetime: (0.5, 1.),
+    size: (0.2, 0.4),
+    drag: 2.,
+    lift: 0.5,
+};
+
+/// The most tiles that shed embers in one frame. Lots of tiles can go out in the same tick on a
+/// big board, and a few embers read the same as hundreds.
+const MAX_EMBER_TILES: usize = 24;
+/// The most spaces a closed loop bursts from.
+const MAX_BURST_TILES: usize = 24;
+
+pub fn setup_particles(
+    mut commands: Commands,
+    effect_textures: Res<EffectTextures>,
+) {
+    let entities = (0..MAX_PARTICLES)
+        .map(|_| {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    texture: effect_textures.glow.clone(),
+                    visibility: Visibility {
+                        is_visible: false,
+                    },
+                    ..Default::default()
+                })
+                .insert(Name::new("Particle"))
+                .insert(Particle {
+                    velocity: Vec2::ZERO,
+                    drag: 0.,
+                    lift: 0.,
+                    age: 0.,
+                    lifetime: 0.,
+                    color: Color::NONE,
+                })
+                .id()
+        })
+        .collect();
+
+    commands.insert_resource(EntityPool::<Particle>::new(entities));
+}
+
+pub fn teardown_particles(
+    mut commands: Commands,
+    particle_query: Query<Entity, With<Particle>>,
+) {
+    commands.remove_resource::<EntityPool<Particle>>();
+
+    for entity in particle_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Throws out sparks from tiles a trigger lights, embers from tiles that go out, and a burst from
+/// the inside of a closed loop. Nothing is emitted with reduced motion.
+#[allow(clippy::too_many_arguments)]
+pub fn emit_particles(
+    settings: Res<Settings>,
+    theme: Res<Theme>,
+    board: Res<Board>,
+    mut particle_pool: ResMut<EntityPool<Particle>>,
+    mut particle_query: Query<(&mut Particle, &mut Transform, &mut Sprite, &mut Visibility)>,
+    mut tile_lit_evr: EventReader<TileLitEvent>,
+    mut tile_faded_out_evr: EventReader<TileFadedOutEvent>,
+    mut loop_closed_evr: EventReader<LoopClosedEvent>,
+) {
+    if settings.reduced_motion {
+        return;
+    }
+
+    let bright = theme.tile_color(MAX_LIGHTNESS, TileKind::Normal, None);
+    let dim = theme.tile_color(MAX_LIGHTNESS / 3, TileKind::Normal, None);
+    let mut rng = rand::thread_rng();
+
+    let mut emit = |coordinates, emission: &Emission, color| {
+        let origin = tile_translation(coordinates, board.tile_size, 4.);
+
+        for _ in 0..emission.count {
+            let entity = match particle_pool.take() {
+                Some(entity) => entity,
+                None => return,
+            };
+            let (mut particle, mut transform, mut sprite, mut visibility) = match particle_query.get_mut(entity) {
+                Ok(particle) => particle,
+                Err(_) => continue,
+            };
+
+            let angle = rng.gen_range(0.0..TAU);
+            let speed = rng.gen_range(emission.speed.0..emission.speed.1) * board.tile_size;
+
+            *particle = Particle {
+                velocity: Vec2::new(angle.cos(), angle.sin()) * speed,
+                drag: emission.drag,
+                lift: emission.lift * board.tile_size,
+                age: 0.,
+                lifetime: rng.gen_range(emission.lifetime.0..emission.lifetime.1),
+                color,
+            };
+            *transform = Transform::from_translation(origin);
+            sprite.color = color;
+            sprite.custom_size = Some(Vec2::splat(board.tile_size * rng.gen_range(emission.size.0..emission.size.1)));
+            visibility.is_visible = true;
+        }
+    };
+
+    for event in tile_lit_evr.iter() {
+        emit(&event.0, &SPARKS, bright);
+    }
+
+    for event in tile_faded_out_evr.iter().take(MAX_EMBER_TILES) {
+        emit(&event.0, &EMBERS, dim);
+    }
+
+    for event in loop_closed_evr.iter() {
+        for coordinates in event.0.iter().take(MAX_BURST_TILES) {
+            emit(coordinates, &BURST, bright);
+        }
+    }
+}
+
+/// Moves each particle in use and fades it out, hiding it again when its time is up.
+pub fn update_particles(
+    time: Res<Time>,
+    mut particle_query: Query<(&mut Particle, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    let delta = time.delta_seconds();
+
+    for (mut particle, mut transform, mut sprite, mut visibility) in particle_query.iter_mut() {
+        if !visibility.is_visible {
+            continue;
+        }
+
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            visibility.is_visible = false;
+            continue;
+        }
+
+        let velocity = particle.velocity;
+        transform.translation += velocity.extend(0.) * delta;
+
+        let drag = (1. - particle.drag * delta).max(0.);
+        particle.velocity = velocity * drag + Vec2::new(0., particle.lift * delta);
+
+        let mut color = particle.color;
+        color.set_a(color.a() * (1. - particle.age / particle.lifetime));
+        sprite.color = color;
+    }
+}
@@ -0,0 +1,177 @@
+use crate::components::{ScorePopup, ScorePopupTask};
+use crate::events::TriggerScoreEvent;
+use crate::resources::{Board, EntityPool, Hint, Locale, Settings, Theme, TileKind, HINT_LOOKAHEAD_TICKS, MAX_LIGHTNESS};
+use crate::systems::tile::tile_translation;
+use bevy::prelude::*;
+use bevy::tasks::AsyncComputeTaskPool;
+use futures_lite::future;
+
+/// The most popups shown at once. Quick clicking replaces the oldest.
+const MAX_SCORE_POPUPS: usize = 24;
+/// How long a popup is shown, in seconds.
+const POPUP_LIFETIME: f32 = 1.2;
+/// How far a popup rises before it's gone, in tiles.
+const POPUP_RISE: f32 = 2.5;
+/// How tall popup text is, in tiles.
+const POPUP_FONT_SIZE: f32 = 1.25;
+
+pub fn setup_score_popups(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    let font = asset_server.load("fonts/Lato/Lato-Light.ttf");
+
+    let entities = (0..MAX_SCORE_POPUPS)
+        .map(|_| {
+            commands
+                .spawn_bundle(Text2dBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 16.,
+                            color: Color::WHITE,
+                        },
+                        TextAlignment {
+                            vertical: VerticalAlign::Center,
+                            horizontal: HorizontalAlign::Center,
+                        },
+                    ),
+                    visibility: Visibility {
+                        is_visible: false,
+                    },
+                    ..Default::default()
+                })
+                .insert(Name::new("Score Popup"))
+                .insert(ScorePopup {
+                    origin: Vec3::ZERO,
+                    age: 0.,
+                    color: Color::NONE,
+                })
+                .id()
+        })
+        .collect();
+
+    commands.insert_resource(EntityPool::<ScorePopup>::new(entities));
+}
+
+pub fn teardown_score_popups(
+    mut commands: Commands,
+    popup_query: Query<Entity, With<ScorePopup>>,
+    popup_task_query: Query<Entity, With<ScorePopupTask>>,
+) {
+    commands.remove_resource::<EntityPool<ScorePopup>>();
+
+    for entity in popup_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for entity in popup_task_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Works out what each trigger earns: how much more the board scores over the next ticks than it
+/// would have without the trigger. Both boards are played forward on another thread, like hints.
+pub fn start_score_popups(
+    mut commands: Commands,
+    thread_pool: Res<AsyncComputeTaskPool>,
+    mut trigger_score_evr: EventReader<TriggerScoreEvent>,
+) {
+    for event in trigger_score_evr.iter() {
+        let TriggerScoreEvent { coordinates, previous_tile_map, tile_map } = event.clone();
+        let task = thread_pool.spawn(async move {
+            Hint::simulate(tile_map, HINT_LOOKAHEAD_TICKS)
+                .saturating_sub(Hint::simulate(previous_tile_map, HINT_LOOKAHEAD_TICKS))
+        });
+
+        commands.spawn()
+            .insert(Name::new("Score Popup Task"))
+            .insert(ScorePopupTask {
+                coordinates,
+                task,
+            });
+    }
+}
+
+/// Shows a "+N" on each triggered tile with the score the trigger earns over the next ticks, once
+/// it's been worked out.
+pub fn show_score_popups(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+    board: Res<Board>,
+    mut popup_pool: ResMut<EntityPool<ScorePopup>>,
+    mut popup_query: Query<(&mut ScorePopup, &mut Transform, &mut Text, &mut Visibility)>,
+    mut popup_task_query: Query<(Entity, &mut ScorePopupTask)>,
+) {
+    let color = theme.tile_color(MAX_LIGHTNESS, TileKind::Normal, None);
+
+    for (task_entity, mut popup_task) in popup_task_query.iter_mut() {
+        let score = match future::block_on(future::poll_once(&mut popup_task.task)) {
+            Some(score) => score,
+            None => continue,
+        };
+
+        commands.entity(task_entity).despawn();
+
+        if score == 0 {
+            continue;
+        }
+
+        let entity = match popup_pool.take() {
+            Some(entity) => entity,
+            None => return,
+        };
+        let (mut popup, mut transform, mut text, mut visibility) = match popup_query.get_mut(entity) {
+            Ok(popup) => popup,
+            Err(_) => continue,
+        };
+
+        let origin = tile_translation(&popup_task.coordinates, board.tile_size, 5.);
+        *popup = ScorePopup {
+            origin,
+            age: 0.,
+            color,
+        };
+        *transform = Transform::from_translation(origin);
+
+        text.sections[0].value = format!("+{}", locale.format_number(score));
+        text.sections[0].style.color = color;
+        text.sections[0].style.font_size = POPUP_FONT_SIZE * board.tile_size;
+        visibility.is_visible = true;
+    }
+}
+
+/// Raises each popup in use and fades it out, hiding it again when its time is up. With reduced
+/// motion popups stay where they were shown, and only fade.
+pub fn update_score_popups(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    board: Res<Board>,
+    mut popup_query: Query<(&mut ScorePopup, &mut Transform, &mut Text, &mut Visibility)>,
+) {
+    for (mut popup, mut transform, mut text, mut visibility) in popup_query.iter_mut() {
+        if !visibility.is_visible {
+            continue;
+        }
+
+        popup.age += time.delta_seconds();
+        let progress = popup.age / POPUP_LIFETIME;
+        if progress >= 1. {
+            visibility.is_visible = false;
+            continue;
+        }
+
+        // Rises quickly at first and slows to a stop.
+        if !settings.reduced_motion {
+            let rise = 1. - (1. - progress).powi(2);
+            transform.translation = popup.origin + Vec3::new(0., rise * POPUP_RISE * board.tile_size, 0.);
+        }
+
+        // Stays solid for the first half, then fades out.
+        let mut color = popup.color;
+        color.set_a(color.a() * (2. - progress * 2.).min(1.));
+        text.sections[0].style.color = color;
+    }
+}
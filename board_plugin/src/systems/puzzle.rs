@@ -0,0 +1,119 @@
+use crate::components::{ClicksText, Score};
+use crate::events::*;
+use crate::persistence;
+use crate::resources::*;
+use crate::systems::game_state::GameState;
+use bevy::log;
+use bevy::prelude::*;
+
+pub fn load_levels(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(Levels {
+        index: asset_server.load("levels/all.levels.ron"),
+        levels: vec![],
+    });
+    commands.insert_resource(persistence::load::<LevelProgress>(LevelProgress::SAVE_NAME));
+}
+
+pub fn populate_levels(
+    asset_server: Res<AssetServer>,
+    level_indices: Res<Assets<LevelIndex>>,
+    mut levels: ResMut<Levels>,
+    mut level_index_evr: EventReader<AssetEvent<LevelIndex>>,
+) {
+    for event in level_index_evr.iter() {
+        match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                if *handle != levels.index {
+                    continue;
+                }
+
+                if let Some(index) = level_indices.get(handle) {
+                    levels.levels = index.levels.iter()
+                        .map(|path| { (path.clone(), asset_server.load(path.as_str())) })
+                        .collect();
+                    log::info!("Found {} levels", levels.levels.len());
+                }
+            },
+            AssetEvent::Removed { .. } => {},
+        }
+    }
+}
+
+/// Completes the level when its goal is met, and ends it when the clicks have run out and the goal
+/// can't be met any more.
+pub fn check_goal(
+    mut puzzle_state: Option<ResMut<PuzzleState>>,
+    board: Res<Board>,
+    score_query: Query<&Score>,
+    mut level_complete_evw: EventWriter<LevelCompleteEvent>,
+    mut game_over_evw: EventWriter<GameOverEvent>,
+) {
+    let puzzle_state = match puzzle_state.as_mut() {
+        Some(puzzle_state) if !puzzle_state.is_complete => puzzle_state,
+        _ => return,
+    };
+
+    let score = score_query.single();
+
+    if puzzle_state.goal.is_met(&board.tile_map, score.0) {
+        puzzle_state.is_complete = true;
+        level_complete_evw.send(LevelCompleteEvent(score.0));
+    } else if puzzle_state.clicks_remaining == 0 && !puzzle_state.goal.can_be_met_without_clicks() {
+        log::info!("Out of clicks without meeting the goal");
+        game_over_evw.send(GameOverEvent(score.0));
+    }
+}
+
+pub fn handle_level_complete_event(
+    mut game_state: ResMut<State<GameState>>,
+    mut final_score: ResMut<FinalScore>,
+    mut level_progress: ResMut<LevelProgress>,
+    levels: Res<Levels>,
+    puzzle_state: Option<Res<PuzzleState>>,
+    mut level_complete_evr: EventReader<LevelCompleteEvent>,
+) {
+    for event in level_complete_evr.iter() {
+        final_score.0 = event.0;
+
+        if let Some((path, _)) = puzzle_state.as_ref().and_then(|p| { p.level }).and_then(|i| { levels.levels.get(i) }) {
+            log::info!("Completed level {} with score {}", path, event.0);
+            level_progress.complete(path, event.0);
+            persistence::save(LevelProgress::SAVE_NAME, &*level_progress);
+        }
+
+        GameState::GameOver.transition(&mut game_state);
+    }
+}
+
+pub fn update_clicks_text(
+    game_mode: Res<GameMode>,
+    puzzle_state: Option<Res<PuzzleState>>,
+    classic_puzzle: Option<Res<ClassicPuzzle>>,
+    daily_challenge: Option<Res<DailyChallenge>>,
+    locale: Res<Locale>,
+    mut clicks_text_query: Query<&mut Text, With<ClicksText>>,
+) {
+    let value = if let Some(puzzle_state) = puzzle_state {
+        locale.format("hud-clicks", &[("clicks", puzzle_state.clicks_remaining.to_string())])
+    } else if let Some(classic_puzzle) = classic_puzzle {
+        locale.format("hud-moves", &[("moves", classic_puzzle.moves.to_string())])
+    } else if *game_mode == GameMode::Versus {
+        locale.get("hud-versus")
+    } else if *game_mode == GameMode::Online {
+        locale.get("hud-online")
+    } else if *game_mode == GameMode::Spectate {
+        locale.get("hud-spectating")
+    } else if let Some(challenge) = daily_challenge.filter(|_| { *game_mode == GameMode::Daily }) {
+        let key = if challenge.is_scored { "hud-daily" } else { "hud-daily-unscored" };
+        locale.format(key, &[("variant", locale.get(challenge.variant.key()))])
+    } else {
+        return;
+    };
+
+    for mut text in clicks_text_query.iter_mut() {
+        text.sections[0].value = value.clone();
+    }
+}
@@ -1,13 +1,26 @@
-use crate::components::{Score, UiRoot};
-use crate::events::GameOverEvent;
-use crate::resources::Board;
+use crate::components::{ClicksText, FinalScoreCountUp, HudText, Score, UiRoot};
+use crate::events::{GameOverEvent, TickEvent};
+use crate::resources::{
+    Board, ClassicPuzzle, FinalScore, GameMode, GameStats, Locale, Settings, Theme, Versus, MAX_LIGHTNESS,
+};
 use crate::tick::UpdateTickTimer;
-use crate::utils::format_number;
+use crate::utils::format_duration;
 use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+/// The HUD warns that the light is going out when fewer tiles than this are lit.
+const DANGER_LIT_TILES: usize = 5;
+const DANGER_COLOR: Color = Color::rgb(1., 0.3, 0.2);
+/// How many times a second the warning pulses.
+const WARNING_PULSE_RATE: f32 = 1.5;
+/// How long the final score takes to count up, in seconds.
+const COUNT_UP_TIME: f32 = 1.5;
 
 pub fn setup_score_ui(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    game_mode: Res<GameMode>,
     mut ui_root_query: Query<&mut UiRoot>,
 ) {
     let ui_root = ui_root_query.single_mut();
@@ -33,7 +46,7 @@ pub fn setup_score_ui(
                                 ..Default::default()
                             },
                             text: Text::with_section(
-                                "Score: 0",
+                                locale.format("hud-score", &[("score", locale.format_number(0))]),
                                 TextStyle {
                                     font: asset_server.load("fonts/Lato/Lato-Light.ttf"),
                                     font_size: 32.,
@@ -45,6 +58,61 @@ pub fn setup_score_ui(
                         })
                         .insert(Name::new("Score Text"))
                         .insert(Score(0));
+                    parent
+                        .spawn_bundle(TextBundle {
+                            style: Style {
+                                margin: Rect::all(Val::Px(16.)),
+                                ..Default::default()
+                            },
+                            text: Text::with_section(
+                                "",
+                                TextStyle {
+                                    font: asset_server.load("fonts/Lato/Lato-Light.ttf"),
+                                    font_size: 32.,
+                                    color: Color::WHITE,
+                                },
+                                Default::default()
+                            ),
+                            ..Default::default()
+                        })
+                        .insert(Name::new("Clicks Text"))
+                        .insert(ClicksText);
+
+                    // Classic, versus and spectated games don't send ticks, so they'd never fill it in.
+                    if matches!(*game_mode, GameMode::Classic(_) | GameMode::Versus | GameMode::Spectate) {
+                        return;
+                    }
+
+                    parent
+                        .spawn_bundle(TextBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                position: Rect {
+                                    top: Val::Px(56.),
+                                    left: Val::Px(16.),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            },
+                            text: Text {
+                                sections: vec![Color::WHITE, DANGER_COLOR].into_iter()
+                                    .map(|color| {
+                                        TextSection {
+                                            value: String::new(),
+                                            style: TextStyle {
+                                                font: asset_server.load("fonts/Lato/Lato-Light.ttf"),
+                                                font_size: 20.,
+                                                color,
+                                            },
+                                        }
+                                    })
+                                    .collect(),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        })
+                        .insert(Name::new("HUD Text"))
+                        .insert(HudText { is_warning: false });
                 });
         });
     
@@ -62,30 +130,127 @@ pub fn teardown_score_ui(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_score(
+    game_mode: Res<GameMode>,
     board: Res<Board>,
+    classic_puzzle: Option<Res<ClassicPuzzle>>,
+    versus: Option<Res<Versus>>,
+    locale: Res<Locale>,
     time: Res<Time>,
     mut timer: ResMut<UpdateTickTimer>,
     mut score_query: Query<(&mut Score, &mut Text)>,
+    mut tick_evw: EventWriter<TickEvent>,
     mut game_over_evw: EventWriter<GameOverEvent>,
 ) {
+    // Classic games are scored by moves rather than by light, versus games score each player, and
+    // remote games are scored wherever they're run.
+    if classic_puzzle.is_some() || versus.is_some() || game_mode.is_remote() {
+        return;
+    }
+
     if !timer.0.tick(time.time_since_startup()).just_finished() {
         return;
     }
 
     let (mut score, mut text) = score_query.single_mut();
 
-    let score_increment = board.tile_map
-        .non_black_tiles()
-        .into_iter()
-        .map(|tile| { f64::log(tile.lightness as f64 + 1., 2.) as u64 })
-        .reduce(|a, b| { a + b })
-        .unwrap_or(0);
+    let score_increment = board.tile_map.tick_score();
+
+    score.0 += score_increment;
+    text.sections[0].value = locale.format("hud-score", &[("score", locale.format_number(score.0))]);
 
-    score.0 = score.0 + score_increment;
-    text.sections[0].value = format!("Score: {}", format_number(score.0));
+    tick_evw.send(TickEvent {
+        score: score.0,
+        score_increment,
+    });
 
-    if score_increment == 0 {
+    if !board.tile_map.has_fading_light() {
         game_over_evw.send(GameOverEvent(score.0));
     }
 }
+
+/// Shows the score per tick, how many tiles are lit, the brightest tile and how long the game has
+/// lasted after each tick, and warns when the light is close to going out.
+pub fn update_hud(
+    locale: Res<Locale>,
+    theme: Res<Theme>,
+    board: Res<Board>,
+    game_stats: Option<Res<GameStats>>,
+    mut hud_query: Query<(&mut HudText, &mut Text)>,
+    mut tick_evr: EventReader<TickEvent>,
+) {
+    let event = match tick_evr.iter().last() {
+        Some(event) => *event,
+        None => return,
+    };
+
+    let lit_tiles = board.tile_map.non_black_tiles();
+    let peak_lightness = lit_tiles.iter().map(|tile| { tile.lightness }).max().unwrap_or(0);
+    let survival_time = game_stats.map_or(0., |game_stats| { game_stats.survival_time });
+    let is_warning = lit_tiles.len() < DANGER_LIT_TILES;
+
+    let stats = locale.format("hud-stats", &[
+        ("rate", locale.format_number(event.score_increment)),
+        ("lit", lit_tiles.len().to_string()),
+        ("peak", (peak_lightness as u32 * 100 / MAX_LIGHTNESS as u32).to_string()),
+        ("time", format_duration(survival_time)),
+    ]);
+
+    for (mut hud_text, mut text) in hud_query.iter_mut() {
+        hud_text.is_warning = is_warning;
+
+        text.sections[0].value = stats.clone();
+        text.sections[0].style.color = if is_warning { DANGER_COLOR } else { theme.ui_color(Color::WHITE) };
+
+        text.sections[1].value = if is_warning { format!("  {}", locale.get("hud-warning")) } else { String::new() };
+        text.sections[1].style.color = DANGER_COLOR;
+    }
+}
+
+/// Pulses the HUD's warning while the light is close to going out. With reduced motion it's shown
+/// steadily instead.
+pub fn pulse_hud_warning(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut hud_query: Query<(&HudText, &mut Text)>,
+) {
+    for (hud_text, mut text) in hud_query.iter_mut() {
+        if !hud_text.is_warning {
+            continue;
+        }
+
+        let alpha = if settings.reduced_motion {
+            1.
+        } else {
+            0.7 + 0.3 * (time.seconds_since_startup() as f32 * WARNING_PULSE_RATE * TAU).cos()
+        };
+        text.sections[1].style.color.set_a(alpha);
+    }
+}
+
+/// Counts the final score up from zero on the game over menu, quickly at first and slowing as it
+/// nears the total. With reduced motion the menu shows the total at once.
+pub fn count_up_final_score(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    locale: Res<Locale>,
+    final_score: Res<FinalScore>,
+    mut count_up_query: Query<(&mut FinalScoreCountUp, &mut Text)>,
+) {
+    if settings.reduced_motion {
+        return;
+    }
+
+    for (mut count_up, mut text) in count_up_query.iter_mut() {
+        if count_up.elapsed >= COUNT_UP_TIME {
+            continue;
+        }
+
+        count_up.elapsed += time.delta_seconds();
+        let progress = (count_up.elapsed / COUNT_UP_TIME).min(1.) as f64;
+        let score = (final_score.0 as f64 * (1. - (1. - progress).powi(3))).round() as u64;
+
+        text.sections[0].value = locale.format("hud-score", &[("score", locale.format_number(score))]);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::components::{LightnessLabel, ScaledUi, TileComponent};
+use crate::events::*;
+use crate::persistence;
+use crate::resources::{lightness_band, Board, Locale, Settings, Theme};
+use bevy::ecs::query::ChangeTrackers;
+use bevy::log;
+use bevy::prelude::*;
+
+/// UI nodes that haven't had their sizes remembered yet.
+type UnscaledUi = (With<Node>, Without<ScaledUi>);
+
+pub fn load_settings(
+    mut commands: Commands,
+    mut locale: ResMut<Locale>,
+) {
+    let settings = persistence::load::<Settings>(Settings::SAVE_NAME);
+
+    // Set straight away, so the main menu is built in the saved language.
+    locale.language = settings.language;
+
+    commands.insert_resource(settings);
+}
+
+pub fn handle_settings_events(
+    mut settings: ResMut<Settings>,
+    mut change_settings_evr: EventReader<ChangeSettingsEvent>,
+) {
+    let mut is_changed = false;
+
+    for event in change_settings_evr.iter() {
+        *settings = event.0;
+        is_changed = true;
+    }
+
+    if is_changed {
+        log::info!("Settings changed: {:?}", *settings);
+        persistence::save(Settings::SAVE_NAME, &*settings);
+    }
+}
+
+/// Remembers the sizes new UI nodes were built with, and scales them for the UI scale.
+pub fn scale_new_ui(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut ui_query: Query<(Entity, &mut Style, Option<&mut Text>), UnscaledUi>,
+) {
+    for (entity, mut style, text) in ui_query.iter_mut() {
+        let scaled_ui = ScaledUi {
+            style: style.clone(),
+            font_sizes: text.as_ref()
+                .map(|text| { text.sections.iter().map(|section| { section.style.font_size }).collect() })
+                .unwrap_or_default(),
+        };
+
+        if settings.ui_scale != 1. {
+            apply_ui_scale_to_node(settings.ui_scale as f32, &scaled_ui, &mut style, text);
+        }
+        commands.entity(entity).insert(scaled_ui);
+    }
+}
+
+/// Resizes the UI when the UI scale changes. Only the UI is scaled, so the board and camera stay
+/// as they are.
+pub fn apply_ui_scale(
+    settings: Res<Settings>,
+    mut ui_query: Query<(&ScaledUi, &mut Style, Option<&mut Text>)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (scaled_ui, mut style, text) in ui_query.iter_mut() {
+        apply_ui_scale_to_node(settings.ui_scale as f32, scaled_ui, &mut style, text);
+    }
+}
+
+/// Scales the sizes given in pixels. Percentages already follow their parent, and are left as they
+/// are, since some are kept up to date by other systems.
+fn apply_ui_scale_to_node(scale: f32, scaled_ui: &ScaledUi, style: &mut Style, text: Option<Mut<Text>>) {
+    let base = &scaled_ui.style;
+
+    scale_size(&mut style.size, base.size, scale);
+    scale_size(&mut style.min_size, base.min_size, scale);
+    scale_size(&mut style.max_size, base.max_size, scale);
+    scale_rect(&mut style.position, base.position, scale);
+    scale_rect(&mut style.margin, base.margin, scale);
+    scale_rect(&mut style.padding, base.padding, scale);
+    scale_rect(&mut style.border, base.border, scale);
+    scale_val(&mut style.flex_basis, base.flex_basis, scale);
+
+    if let Some(mut text) = text {
+        for (section, font_size) in text.sections.iter_mut().zip(scaled_ui.font_sizes.iter()) {
+            section.style.font_size = font_size * scale;
+        }
+    }
+}
+
+fn scale_size(size: &mut Size<Val>, base: Size<Val>, scale: f32) {
+    scale_val(&mut size.width, base.width, scale);
+    scale_val(&mut size.height, base.height, scale);
+}
+
+fn scale_rect(rect: &mut Rect<Val>, base: Rect<Val>, scale: f32) {
+    scale_val(&mut rect.left, base.left, scale);
+    scale_val(&mut rect.right, base.right, scale);
+    scale_val(&mut rect.top, base.top, scale);
+    scale_val(&mut rect.bottom, base.bottom, scale);
+}
+
+fn scale_val(val: &mut Val, base: Val, scale: f32) {
+    if let Val::Px(px) = base {
+        *val = Val::Px(px * scale);
+    }
+}
+
+pub fn apply_language(
+    settings: Res<Settings>,
+    mut locale: ResMut<Locale>,
+) {
+    // Only written when it differs, so screens showing text only rebuild when the language changes.
+    if locale.language != settings.language {
+        locale.language = settings.language;
+    }
+}
+
+/// Labels every lit tile with its lightness band in high-contrast mode, and removes the labels
+/// otherwise.
+pub fn update_lightness_labels(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    theme: Res<Theme>,
+    board: Option<Res<Board>>,
+    tile_query: Query<(Entity, &TileComponent, Option<&Children>, ChangeTrackers<TileComponent>)>,
+    mut label_query: Query<&mut Text, With<LightnessLabel>>,
+) {
+    let board = match board {
+        Some(board) => board,
+        None => return,
+    };
+
+    let is_changed = settings.is_changed() || theme.is_changed();
+
+    for (entity, tile_component, children, tracker) in tile_query.iter() {
+        if !is_changed && !tracker.is_changed() {
+            continue;
+        }
+
+        let label_entity = children.into_iter()
+            .flat_map(|children| { children.iter() })
+            .find(|child| { label_query.get(**child).is_ok() });
+
+        let band = lightness_band(tile_component.lightness).filter(|_| { settings.high_contrast });
+
+        match (band, label_entity) {
+            (Some(band), Some(label_entity)) => {
+                let mut text = label_query.get_mut(*label_entity).unwrap();
+                text.sections[0].value = band.to_string();
+                text.sections[0].style.color = get_label_color(tile_component, &theme);
+            },
+            (Some(band), None) => {
+                let text = Text::with_section(
+                    band.to_string(),
+                    TextStyle {
+                        font: asset_server.load("fonts/Lato/Lato-Light.ttf"),
+                        font_size: board.tile_size * 0.75,
+                        color: get_label_color(tile_component, &theme),
+                    },
+                    TextAlignment {
+                        vertical: VerticalAlign::Center,
+                        horizontal: HorizontalAlign::Center,
+                    },
+                );
+
+                commands.entity(entity).with_children(|parent| {
+                    parent
+                        .spawn_bundle(Text2dBundle {
+                            text,
+                            transform: Transform::from_xyz(0., 0., 1.),
+                            ..Default::default()
+                        })
+                        .insert(Name::new("Lightness Label"))
+                        .insert(LightnessLabel);
+                });
+            },
+            (None, Some(label_entity)) => {
+                commands.entity(*label_entity).despawn_recursive();
+            },
+            (None, None) => {},
+        }
+    }
+}
+
+/// Black on light tiles and white on dark ones.
+fn get_label_color(tile_component: &TileComponent, theme: &Theme) -> Color {
+    let [r, g, b, _] = tile_component.get_color(theme).as_rgba_f32();
+
+    if 0.299 * r + 0.587 * g + 0.114 * b > 0.5 {
+        Color::BLACK
+    } else {
+        Color::WHITE
+    }
+}
@@ -0,0 +1,369 @@
+use crate::components::{
+    ConnectTask, MainCamera, PlaybackText, Score, SpectatorPanel, TimelineFill, TimelineTrack, UiRoot,
+};
+use crate::events::*;
+use crate::network::ServerMessage;
+use crate::resources::{Board, GameMode, Locale, Spectator, SpectatorConfig, SpectatorFrame};
+use crate::simulation::{Replay, SimulationConfig};
+use crate::systems::game_state::GameState;
+use crate::systems::online;
+use bevy::input::ElementState;
+use bevy::input::keyboard::{KeyCode, KeyboardInput};
+use bevy::input::mouse::MouseWheel;
+use bevy::log;
+use bevy::prelude::*;
+use bevy::tasks::AsyncComputeTaskPool;
+use bevy::ui::FocusPolicy;
+use std::fs;
+
+/// How fast the camera pans, in pixels per second at normal zoom.
+const CAMERA_PAN_SPEED: f32 = 400.;
+const MIN_CAMERA_SCALE: f32 = 0.25;
+const MAX_CAMERA_SCALE: f32 = 8.;
+
+/// How many frames the arrow keys skip.
+const SKIP_FRAMES: i64 = 50;
+
+/// Loads the replay and starts playing it back, or starts connecting to the live game, which is
+/// shown once `online::poll_connection` has connected.
+pub fn handle_start_spectate_event(
+    mut commands: Commands,
+    mut game_state: ResMut<State<GameState>>,
+    mut game_mode: ResMut<GameMode>,
+    spectator_config: Option<Res<SpectatorConfig>>,
+    thread_pool: Res<AsyncComputeTaskPool>,
+    connect_task_query: Query<&ConnectTask>,
+    mut start_spectate_evr: EventReader<StartSpectateEvent>,
+) {
+    for _ in start_spectate_evr.iter() {
+        let spectator = match spectator_config.as_deref() {
+            Some(SpectatorConfig::Live { addr }) => {
+                online::connect(&mut commands, &thread_pool, &connect_task_query, addr, GameMode::Spectate);
+                continue;
+            },
+            Some(SpectatorConfig::Replay { path }) => match load_replay(path) {
+                Ok(replay) => Spectator::replay(replay, SimulationConfig::default().ticks),
+                Err(e) => {
+                    log::error!("Can't load replay {}: {}", path, e);
+                    continue;
+                },
+            },
+            None => {
+                log::error!("Nothing to spectate");
+                continue;
+            },
+        };
+
+        commands.insert_resource(spectator);
+        *game_mode = GameMode::Spectate;
+        GameState::InGame.transition(&mut game_state);
+    }
+}
+
+pub fn setup_spectator_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    spectator: Option<Res<Spectator>>,
+    mut ui_root_query: Query<&mut UiRoot>,
+) {
+    if spectator.is_none() {
+        return;
+    }
+
+    let ui_root = ui_root_query.single_mut();
+
+    commands.entity(ui_root.0).with_children(|parent| {
+        parent
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        left: Val::Px(16.),
+                        right: Val::Px(16.),
+                        bottom: Val::Px(16.),
+                        ..Default::default()
+                    },
+                    flex_direction: FlexDirection::ColumnReverse,
+                    ..Default::default()
+                },
+                color: Color::NONE.into(),
+                ..Default::default()
+            })
+            .insert(Name::new("Spectator Panel"))
+            .insert(SpectatorPanel)
+            .with_children(|parent| {
+                parent
+                    .spawn_bundle(TextBundle {
+                        style: Style {
+                            margin: Rect {
+                                bottom: Val::Px(8.),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        text: Text::with_section(
+                            "",
+                            TextStyle {
+                                font: asset_server.load("fonts/Lato/Lato-Light.ttf"),
+                                font_size: 20.,
+                                color: Color::WHITE,
+                            },
+                            Default::default()
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(Name::new("Playback Text"))
+                    .insert(PlaybackText);
+
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Percent(100.), Val::Px(12.)),
+                            ..Default::default()
+                        },
+                        color: Color::DARK_GRAY.into(),
+                        ..Default::default()
+                    })
+                    .insert(Name::new("Timeline Track"))
+                    .insert(TimelineTrack)
+                    .insert(Interaction::default())
+                    .with_children(|parent| {
+                        parent
+                            .spawn_bundle(NodeBundle {
+                                style: Style {
+                                    size: Size::new(Val::Percent(0.), Val::Percent(100.)),
+                                    ..Default::default()
+                                },
+                                color: Color::WHITE.into(),
+                                ..Default::default()
+                            })
+                            .insert(Name::new("Timeline Fill"))
+                            .insert(TimelineFill)
+                            .insert(FocusPolicy::Pass);
+                    });
+            });
+    });
+}
+
+/// Plays, pauses, skips, and changes the speed of playback.
+///
+/// | Key           | Action                      |
+/// |:--------------|:----------------------------|
+/// | Enter         | Play or pause               |
+/// | Left / Right  | Skip back or forwards       |
+/// | , / .         | Step back or forwards       |
+/// | - / =         | Slow down or speed up       |
+/// | End           | Jump to the newest frame    |
+pub fn handle_playback_input(
+    spectator: Option<ResMut<Spectator>>,
+    mut keyboard_evr: EventReader<KeyboardInput>,
+) {
+    let mut spectator = match spectator {
+        Some(spectator) => spectator,
+        None => return,
+    };
+
+    for event in keyboard_evr.iter() {
+        if event.state != ElementState::Pressed {
+            continue;
+        }
+
+        match event.key_code {
+            Some(KeyCode::Return) => spectator.is_paused = !spectator.is_paused,
+            Some(KeyCode::Left) => spectator.skip(-SKIP_FRAMES),
+            Some(KeyCode::Right) => spectator.skip(SKIP_FRAMES),
+            Some(KeyCode::Comma) => {
+                spectator.is_paused = true;
+                spectator.skip(-1);
+            },
+            Some(KeyCode::Period) => {
+                spectator.is_paused = true;
+                spectator.skip(1);
+            },
+            Some(KeyCode::Minus) | Some(KeyCode::NumpadSubtract) => spectator.slow_down(),
+            Some(KeyCode::Equals) | Some(KeyCode::NumpadAdd) => spectator.speed_up(),
+            Some(KeyCode::End) => spectator.skip_to_end(),
+            _ => {},
+        }
+    }
+}
+
+/// Seeks to wherever the timeline is clicked, and follows the pointer while it's dragged, even
+/// past either end.
+pub fn handle_timeline_input(
+    windows: Res<Windows>,
+    spectator: Option<ResMut<Spectator>>,
+    track_query: Query<(&Interaction, &Node, &GlobalTransform), With<TimelineTrack>>,
+) {
+    let mut spectator = match spectator {
+        Some(spectator) => spectator,
+        None => return,
+    };
+
+    let position = match windows.get_primary().and_then(|window| { window.cursor_position() }) {
+        Some(position) => position,
+        None => return,
+    };
+
+    for (interaction, node, transform) in track_query.iter() {
+        if *interaction == Interaction::Clicked {
+            let left = transform.translation.x - node.size.x / 2.;
+            spectator.seek(((position.x - left) / node.size.x) as f64);
+        }
+    }
+}
+
+/// Records live frames, moves playback on, and shows the current frame with the game's own
+/// rendering systems.
+pub fn update_playback(
+    time: Res<Time>,
+    locale: Res<Locale>,
+    mut board: ResMut<Board>,
+    spectator: Option<ResMut<Spectator>>,
+    mut score_query: Query<(&mut Score, &mut Text)>,
+    mut tile_update_evw: EventWriter<TileUpdateEvent>,
+) {
+    let mut spectator = match spectator {
+        Some(spectator) => spectator,
+        None => return,
+    };
+
+    let messages = match spectator.client.as_mut() {
+        Some(game_client) => game_client.poll(),
+        None => vec![],
+    };
+    for message in messages {
+        if let ServerMessage::Snapshot { .. } | ServerMessage::Delta { .. } = message {
+            let game_client = spectator.client.as_ref().unwrap();
+            let frame = SpectatorFrame {
+                tile_map: game_client.tile_map().clone(),
+                score: game_client.score(),
+            };
+            spectator.push_frame(frame);
+        }
+    }
+
+    spectator.advance(time.delta_seconds_f64());
+
+    let frame_index = spectator.frame_index();
+    if spectator.shown_frame == Some(frame_index) {
+        return;
+    }
+
+    let frame = match spectator.frame() {
+        Some(frame) => frame,
+        None => return,
+    };
+
+    board.tile_map = frame.tile_map.clone();
+
+    let (mut score, mut text) = score_query.single_mut();
+    score.0 = frame.score;
+    text.sections[0].value = locale.format("hud-score", &[("score", locale.format_number(score.0))]);
+
+    spectator.shown_frame = Some(frame_index);
+    tile_update_evw.send(TileUpdateEvent);
+}
+
+pub fn update_spectator_ui(
+    locale: Res<Locale>,
+    spectator: Option<Res<Spectator>>,
+    mut playback_text_query: Query<&mut Text, With<PlaybackText>>,
+    mut timeline_fill_query: Query<&mut Style, With<TimelineFill>>,
+) {
+    let spectator = match spectator {
+        Some(spectator) => spectator,
+        None => return,
+    };
+
+    let status = if spectator.is_following {
+        locale.get("spectator-live")
+    } else if spectator.is_paused {
+        locale.get("spectator-paused")
+    } else {
+        locale.format("spectator-playing", &[("speed", spectator.speed().to_string())])
+    };
+
+    for mut text in playback_text_query.iter_mut() {
+        text.sections[0].value = locale.format("spectator-status", &[
+            ("status", status.clone()),
+            ("tick", spectator.frame_index().to_string()),
+            ("last", spectator.frame_count().saturating_sub(1).to_string()),
+        ]);
+    }
+
+    for mut style in timeline_fill_query.iter_mut() {
+        style.size.width = Val::Percent(spectator.progress() as f32 * 100.);
+    }
+}
+
+/// Pans the camera with WASD and zooms it with the mouse wheel or Q and E.
+pub fn move_camera(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    spectator: Option<Res<Spectator>>,
+    mut mouse_wheel_evr: EventReader<MouseWheel>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    if spectator.is_none() {
+        return;
+    }
+
+    let (mut transform, mut projection) = match camera_query.get_single_mut() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+
+    let mut direction = Vec2::ZERO;
+    if keys.pressed(KeyCode::W) {
+        direction.y += 1.;
+    }
+    if keys.pressed(KeyCode::S) {
+        direction.y -= 1.;
+    }
+    if keys.pressed(KeyCode::A) {
+        direction.x -= 1.;
+    }
+    if keys.pressed(KeyCode::D) {
+        direction.x += 1.;
+    }
+
+    if direction != Vec2::ZERO {
+        let distance = CAMERA_PAN_SPEED * projection.scale * time.delta_seconds();
+        transform.translation += (direction.normalize() * distance).extend(0.);
+    }
+
+    let mut zoom: f32 = mouse_wheel_evr.iter().map(|event| { event.y }).sum();
+    if keys.pressed(KeyCode::Q) {
+        zoom -= 4. * time.delta_seconds();
+    }
+    if keys.pressed(KeyCode::E) {
+        zoom += 4. * time.delta_seconds();
+    }
+
+    if zoom != 0. {
+        projection.scale = (projection.scale * 1.1_f32.powf(-zoom)).clamp(MIN_CAMERA_SCALE, MAX_CAMERA_SCALE);
+    }
+}
+
+pub fn teardown_spectator_ui(
+    mut commands: Commands,
+    spectator_panel_query: Query<Entity, With<SpectatorPanel>>,
+) {
+    for entity in spectator_panel_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn stop_spectating(
+    mut commands: Commands,
+) {
+    commands.remove_resource::<Spectator>();
+}
+
+fn load_replay(path: &str) -> Result<Replay, anyhow::Error> {
+    let contents = fs::read_to_string(path)?;
+    let replay: Replay = ron::from_str(&contents)?;
+
+    Ok(replay)
+}
@@ -0,0 +1,60 @@
+use crate::events::*;
+use crate::persistence;
+use crate::resources::{Board, GameMode, GameStats, LifetimeStats};
+use bevy::log;
+use bevy::prelude::*;
+
+pub fn load_lifetime_stats(
+    mut commands: Commands,
+) {
+    commands.insert_resource(persistence::load::<LifetimeStats>(LifetimeStats::SAVE_NAME));
+}
+
+pub fn record_clicks(
+    mut game_stats: ResMut<GameStats>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut wasted_click_evr: EventReader<WastedClickEvent>,
+) {
+    game_stats.clicks += tile_trigger_evr.iter().count() as u64;
+    game_stats.wasted_clicks += wasted_click_evr.iter().count() as u64;
+}
+
+pub fn record_ticks(
+    board: Res<Board>,
+    time: Res<Time>,
+    mut game_stats: ResMut<GameStats>,
+    mut tick_evr: EventReader<TickEvent>,
+) {
+    // Only runs while playing, so time spent paused isn't counted.
+    game_stats.record_time(time.delta_seconds_f64());
+
+    for event in tick_evr.iter() {
+        game_stats.record_tick(&board.tile_map, event.score, event.score_increment);
+    }
+}
+
+pub fn handle_game_end(
+    game_mode: Res<GameMode>,
+    game_stats: Option<Res<GameStats>>,
+    mut lifetime_stats: ResMut<LifetimeStats>,
+    mut game_over_evr: EventReader<GameOverEvent>,
+    mut level_complete_evr: EventReader<LevelCompleteEvent>,
+) {
+    // A game can end both ways in the same frame, but is only counted once.
+    let is_ended = game_over_evr.iter().count() + level_complete_evr.iter().count() > 0;
+
+    let game_stats = match game_stats {
+        Some(game_stats) if is_ended => game_stats,
+        _ => return,
+    };
+
+    log::info!("Game stats: {:?}", *game_stats);
+
+    if !game_mode.records_lifetime_stats() || game_stats.undos > 0 {
+        log::info!("Not adding the game to lifetime stats");
+        return;
+    }
+
+    lifetime_stats.add(&game_stats);
+    persistence::save(LifetimeStats::SAVE_NAME, &*lifetime_stats);
+}
@@ -0,0 +1,104 @@
+use crate::components::{ThemedUi, TileFade, UiHighlightable};
+use crate::events::CycleThemeEvent;
+use crate::persistence;
+use crate::resources::Theme;
+use bevy::log;
+use bevy::prelude::*;
+
+/// The parts of a UI node that take colours from the theme.
+type ThemedParts<'a> = (Option<&'a mut UiColor>, Option<&'a mut Text>, Option<&'a mut UiHighlightable>);
+
+/// UI nodes that haven't had their colours remembered yet. Text on the board isn't UI, so it's
+/// left alone.
+type UnthemedUi = (With<Node>, Without<ThemedUi>, Or<(With<UiColor>, With<Text>)>);
+
+pub fn load_theme(
+    mut commands: Commands,
+) {
+    commands.insert_resource(persistence::load::<Theme>(Theme::SAVE_NAME));
+}
+
+pub fn handle_cycle_theme_event(
+    mut theme: ResMut<Theme>,
+    mut cycle_theme_evr: EventReader<CycleThemeEvent>,
+) {
+    if cycle_theme_evr.iter().count() == 0 {
+        return;
+    }
+
+    theme.palette = theme.palette.next();
+    persistence::save(Theme::SAVE_NAME, &*theme);
+
+    log::info!("Switched to the {:?} theme", theme.palette);
+}
+
+/// Remembers the colours new UI nodes were built with, and recolours them for the theme.
+pub fn theme_new_ui(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    mut ui_query: Query<(Entity, ThemedParts), UnthemedUi>,
+) {
+    for (entity, (ui_color, text, highlightable)) in ui_query.iter_mut() {
+        let themed_ui = ThemedUi {
+            color: ui_color.as_ref().map(|ui_color| { ui_color.0 }),
+            text_colors: text.as_ref()
+                .map(|text| { text.sections.iter().map(|section| { section.style.color }).collect() })
+                .unwrap_or_default(),
+            highlight_colors: highlightable.as_ref().map(|highlightable| {
+                [highlightable.default_color, highlightable.hover_color, highlightable.pressed_color]
+            }),
+        };
+
+        apply_theme_to_ui(&theme, &themed_ui, ui_color, text, highlightable);
+        commands.entity(entity).insert(themed_ui);
+    }
+}
+
+/// Recolours the background, the board and the UI when the theme changes.
+pub fn apply_theme(
+    theme: Res<Theme>,
+    mut clear_color: ResMut<ClearColor>,
+    mut tile_fade_query: Query<&mut TileFade>,
+    mut ui_query: Query<(&ThemedUi, ThemedParts)>,
+) {
+    if !theme.is_changed() {
+        return;
+    }
+
+    clear_color.0 = theme.background();
+
+    // Tiles and their halos are recoloured by `animate_tiles`, which only looks at changed tiles.
+    for mut tile_fade in tile_fade_query.iter_mut() {
+        tile_fade.set_changed();
+    }
+
+    for (themed_ui, (ui_color, text, highlightable)) in ui_query.iter_mut() {
+        apply_theme_to_ui(&theme, themed_ui, ui_color, text, highlightable);
+    }
+}
+
+fn apply_theme_to_ui(
+    theme: &Theme,
+    themed_ui: &ThemedUi,
+    ui_color: Option<Mut<UiColor>>,
+    text: Option<Mut<Text>>,
+    highlightable: Option<Mut<UiHighlightable>>,
+) {
+    if let (Some(mut ui_color), Some(color)) = (ui_color, themed_ui.color) {
+        ui_color.0 = theme.ui_color(color);
+    }
+
+    if let Some(mut text) = text {
+        for (section, color) in text.sections.iter_mut().zip(themed_ui.text_colors.iter()) {
+            section.style.color = theme.ui_color(*color);
+        }
+    }
+
+    if let (Some(mut highlightable), Some([default_color, hover_color, pressed_color])) =
+        (highlightable, themed_ui.highlight_colors)
+    {
+        highlightable.default_color = theme.ui_color(default_color);
+        highlightable.hover_color = theme.ui_color(hover_color);
+        highlightable.pressed_color = theme.ui_color(pressed_color);
+    }
+}
@@ -1,26 +1,47 @@
-use crate::components::TileComponent;
+use crate::components::{BoardComponent, TileComponent, TileFade, TileGlow};
 use crate::events::*;
 use crate::resources::*;
+use crate::tick::UpdateTickTimer;
 use bevy::prelude::*;
 use bevy::log;
 use bevy::utils::HashSet;
 
+/// How many tiles across a bright tile's halo is.
+const GLOW_TILES: f32 = 3.;
+/// The fraction of full lightness above which tiles start to glow.
+const GLOW_THRESHOLD: f32 = 0.5;
+/// How opaque the halo of a fully lit tile is. Halos are drawn over the tiles around them, so
+/// they're kept faint.
+const GLOW_ALPHA: f32 = 0.3;
+
+/// Updates each tile from the board. Its colour follows in `animate_tiles`.
 pub fn update_tiles(
+    mut commands: Commands,
     board: Res<Board>,
-    mut tile_query: Query<(&mut TileComponent, &mut Sprite)>,
+    mut tile_query: Query<(Entity, &mut TileComponent)>,
     mut tile_update_evr: EventReader<TileUpdateEvent>,
     mut tile_create_evw: EventWriter<TileCreateEvent>,
+    mut tile_faded_out_evw: EventWriter<TileFadedOutEvent>,
 ) {
     let mut existing_tile_coords: HashSet<Coordinates> = HashSet::default();
 
     for _ in tile_update_evr.iter() {
-        for (mut tile_component, mut sprite) in tile_query.iter_mut() {
+        for (entity, mut tile_component) in tile_query.iter_mut() {
             existing_tile_coords.insert(tile_component.coordinates);
             if let Some(tile) = board.tile_map.get(&tile_component.coordinates) {
                 log::debug!("Updating tile {}", tile_component.coordinates);
 
+                if tile_component.lightness > 0 && tile.is_black() {
+                    tile_faded_out_evw.send(TileFadedOutEvent(tile_component.coordinates));
+                }
+
                 tile_component.lightness = tile.lightness;
-                sprite.color = tile_component.get_color();
+                tile_component.kind = tile.kind;
+                tile_component.owner = tile.owner;
+            } else {
+                log::debug!("Removing tile {}", tile_component.coordinates);
+
+                commands.entity(entity).despawn_recursive();
             }
         }
     }
@@ -30,9 +51,64 @@ pub fn update_tiles(
     }
 }
 
+/// Blends each changed tile's colour towards its lightness over one tick, or straight away with
+/// reduced motion, and brightens its halo to match.
+#[allow(clippy::type_complexity)]
+pub fn animate_tiles(
+    time: Res<Time>,
+    timer: Res<UpdateTickTimer>,
+    settings: Res<Settings>,
+    theme: Res<Theme>,
+    mut tile_query: Query<
+        (&TileComponent, &mut TileFade, &mut Sprite, &mut Visibility, &Children),
+        Or<(Changed<TileComponent>, Changed<TileFade>)>,
+    >,
+    mut glow_query: Query<(&mut Sprite, &mut Visibility), (With<TileGlow>, Without<TileComponent>)>,
+) {
+    let duration = timer.0.duration().as_secs_f32();
+
+    for (tile_component, mut fade, mut sprite, mut visibility, children) in tile_query.iter_mut() {
+        let lightness = tile_component.lightness as f32;
+        if fade.to != lightness {
+            fade.from = fade.shown;
+            fade.to = lightness;
+            fade.elapsed = 0.;
+        }
+
+        // Only written while fading, so finished tiles drop out of the query.
+        if fade.shown != fade.to {
+            fade.elapsed = if settings.reduced_motion { duration } else { fade.elapsed + time.delta_seconds() };
+
+            let t = (fade.elapsed / duration).min(1.);
+            fade.shown = if t < 1. { fade.from + (fade.to - fade.from) * t } else { fade.to };
+        }
+
+        sprite.color = theme.tile_color_at(fade.shown, tile_component.kind, tile_component.owner);
+
+        // With dynamic lighting, the lit floor shows through where dark tiles would be, and its
+        // light takes the place of halos.
+        visibility.is_visible =
+            !settings.dynamic_lighting || tile_component.kind != TileKind::Normal || fade.shown > 0.;
+        let is_glowing = !settings.dynamic_lighting && tile_component.kind != TileKind::Wall;
+
+        let glow = (fade.shown / MAX_LIGHTNESS as f32 - GLOW_THRESHOLD) / (1. - GLOW_THRESHOLD);
+        let mut glow_color = sprite.color;
+        glow_color.set_a(glow.max(0.) * GLOW_ALPHA);
+
+        for child in children.iter() {
+            if let Ok((mut glow_sprite, mut glow_visibility)) = glow_query.get_mut(*child) {
+                glow_visibility.is_visible = is_glowing && glow > 0.;
+                glow_sprite.color = glow_color;
+            }
+        }
+    }
+}
+
 pub fn create_new_tiles(
     mut commands: Commands,
     board: Res<Board>,
+    theme: Res<Theme>,
+    effect_textures: Res<EffectTextures>,
     mut tile_create_evr: EventReader<TileCreateEvent>,
 ) {
     for event in tile_create_evr.iter() {
@@ -40,23 +116,58 @@ pub fn create_new_tiles(
         let tile = board.tile_map.get(coordinates).unwrap();
 
         commands.entity(board.entity).with_children(|parent| {
-            spawn_tile(parent, &tile, coordinates, board.tile_size, board.tile_padding);
+            spawn_tile(parent, tile, coordinates, &board, &theme, &effect_textures);
         });
 
         log::info!("Created tile at {}", coordinates);
     }
 }
 
+/// Spawns the board entity and inserts the `Board` resource, creating a sprite for every tile.
+pub fn spawn_board(
+    commands: &mut Commands,
+    tile_map: TileMap,
+    tile_size: f32,
+    tile_padding: f32,
+    tile_create_evw: &mut EventWriter<TileCreateEvent>,
+) {
+    let entity = commands.spawn()
+        .insert(Name::new("Board"))
+        .insert(Transform::from_translation(Vec3::new(0., 0., 0.)))
+        .insert(GlobalTransform::default())
+        .insert(BoardComponent)
+        .id();
+
+    for coordinates in tile_map.keys() {
+        tile_create_evw.send(TileCreateEvent(*coordinates));
+    }
+
+    commands.insert_resource(Board {
+        tile_map,
+        tile_size,
+        tile_padding,
+        entity,
+    });
+}
+
+/// Where a tile is drawn, relative to the board, at depth `z`.
+pub(crate) fn tile_translation(coordinates: &Coordinates, tile_size: f32, z: f32) -> Vec3 {
+    Vec3::new(coordinates.x as f32 * tile_size, coordinates.y as f32 * tile_size, z)
+}
+
 fn spawn_tile(
     parent: &mut ChildBuilder,
     tile: &Tile,
     coordinates: &Coordinates,
-    tile_size: f32,
-    tile_padding: f32,
+    board: &Board,
+    theme: &Theme,
+    effect_textures: &EffectTextures,
 ) {
     let tile_component = TileComponent {
         coordinates: *coordinates,
         lightness: tile.lightness,
+        kind: tile.kind,
+        owner: tile.owner,
     };
 
     let mut commands = parent.spawn();
@@ -64,17 +175,33 @@ fn spawn_tile(
     commands
         .insert_bundle(SpriteBundle {
             sprite: Sprite {
-                color: tile_component.get_color(),
-                custom_size: Some(Vec2::splat(tile_size - tile_padding)),
+                color: tile_component.get_color(theme),
+                custom_size: Some(Vec2::splat(board.tile_size - board.tile_padding)),
                 ..Default::default()
             },
-            transform: Transform::from_xyz(
-                coordinates.x as f32 * tile_size,
-                coordinates.y as f32 * tile_size,
-                1.,
-            ),
+            transform: Transform::from_translation(tile_translation(coordinates, board.tile_size, 1.)),
             ..Default::default()
         })
         .insert(Name::new(format!("Tile {}", coordinates)))
-        .insert(tile_component);
+        .insert(tile_component)
+        .insert(TileFade::new(tile.lightness))
+        .with_children(|parent| {
+            // Drawn over the tiles around it, like light bleeding out, but under lightness labels.
+            parent
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::NONE,
+                        custom_size: Some(Vec2::splat(board.tile_size * GLOW_TILES)),
+                        ..Default::default()
+                    },
+                    texture: effect_textures.glow.clone(),
+                    transform: Transform::from_xyz(0., 0., 0.5),
+                    visibility: Visibility {
+                        is_visible: false,
+                    },
+                    ..Default::default()
+                })
+                .insert(Name::new(format!("Tile {} Glow", coordinates)))
+                .insert(TileGlow);
+        });
 }
@@ -0,0 +1,149 @@
+use crate::components::{Score, VersusCursor};
+use crate::events::*;
+use crate::resources::{Board, Coordinates, Locale, Player, Versus};
+use crate::tick::UpdateTickTimer;
+use bevy::input::ElementState;
+use bevy::input::keyboard::{KeyCode, KeyboardInput};
+use bevy::log;
+use bevy::prelude::*;
+
+/// Moves player two's cursor with the arrow keys or a gamepad's d-pad, and triggers the tile under
+/// it with enter or the gamepad's south button.
+pub fn handle_cursor_input(
+    versus: Option<ResMut<Versus>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut keyboard_evr: EventReader<KeyboardInput>,
+    mut tile_trigger_evw: EventWriter<TileTriggerEvent>,
+) {
+    let mut versus = match versus {
+        Some(versus) => versus,
+        None => return,
+    };
+
+    let mut offset = Coordinates::from((0, 0));
+    let mut is_triggered = false;
+
+    for event in keyboard_evr.iter() {
+        if event.state != ElementState::Pressed {
+            continue;
+        }
+
+        match event.key_code {
+            Some(KeyCode::Up) => offset.y += 1,
+            Some(KeyCode::Down) => offset.y -= 1,
+            Some(KeyCode::Left) => offset.x -= 1,
+            Some(KeyCode::Right) => offset.x += 1,
+            Some(KeyCode::Return) | Some(KeyCode::RShift) => is_triggered = true,
+            _ => {},
+        }
+    }
+
+    for gamepad in gamepads.iter() {
+        let is_pressed = |button_type| {
+            gamepad_buttons.just_pressed(GamepadButton(*gamepad, button_type))
+        };
+
+        if is_pressed(GamepadButtonType::DPadUp) {
+            offset.y += 1;
+        }
+        if is_pressed(GamepadButtonType::DPadDown) {
+            offset.y -= 1;
+        }
+        if is_pressed(GamepadButtonType::DPadLeft) {
+            offset.x -= 1;
+        }
+        if is_pressed(GamepadButtonType::DPadRight) {
+            offset.x += 1;
+        }
+        if is_pressed(GamepadButtonType::South) {
+            is_triggered = true;
+        }
+    }
+
+    versus.cursor = versus.cursor + offset;
+
+    if is_triggered {
+        tile_trigger_evw.send(TileTriggerEvent {
+            coordinates: versus.cursor,
+            owner: Some(Player::Two),
+        });
+    }
+}
+
+/// Keeps the marker for player two's cursor over the tile it points at.
+pub fn update_cursor(
+    mut commands: Commands,
+    board: Res<Board>,
+    versus: Option<Res<Versus>>,
+    mut cursor_query: Query<&mut Transform, With<VersusCursor>>,
+) {
+    let versus = match versus {
+        Some(versus) => versus,
+        None => return,
+    };
+
+    let translation = Vec3::new(
+        versus.cursor.x as f32 * board.tile_size,
+        versus.cursor.y as f32 * board.tile_size,
+        2.,
+    );
+
+    if let Ok(mut transform) = cursor_query.get_single_mut() {
+        transform.translation = translation;
+        return;
+    }
+
+    commands.entity(board.entity).with_children(|parent| {
+        parent
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::hsla(Player::Two.hue(), 1., 0.5, 0.8),
+                    custom_size: Some(Vec2::splat(board.tile_size * 0.4)),
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(translation),
+                ..Default::default()
+            })
+            .insert(Name::new("Versus Cursor"))
+            .insert(VersusCursor);
+    });
+}
+
+/// Scores each player's own light every tick, ending the game when either player has none left.
+pub fn update_scores(
+    board: Res<Board>,
+    versus: Option<ResMut<Versus>>,
+    locale: Res<Locale>,
+    time: Res<Time>,
+    mut timer: ResMut<UpdateTickTimer>,
+    mut score_query: Query<&mut Text, With<Score>>,
+    mut game_over_evw: EventWriter<GameOverEvent>,
+) {
+    let mut versus = match versus {
+        Some(versus) => versus,
+        None => return,
+    };
+
+    if !timer.0.tick(time.time_since_startup()).just_finished() {
+        return;
+    }
+
+    let outcome = versus.tick(&board.tile_map);
+
+    let mut text = score_query.single_mut();
+    text.sections[0].value = locale.format("hud-versus-scores", &[
+        ("one", locale.format_number(versus.score(Player::One))),
+        ("two", locale.format_number(versus.score(Player::Two))),
+    ]);
+
+    if let Some(outcome) = outcome {
+        log::info!("Versus game over: {:?}", outcome);
+
+        let best_score = Player::ALL.into_iter()
+            .map(|player| { versus.score(player) })
+            .max()
+            .unwrap_or(0);
+        game_over_evw.send(GameOverEvent(best_score));
+    }
+}
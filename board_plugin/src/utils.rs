@@ -1,18 +1,24 @@
-pub fn format_number(n: u64) -> String {
+/// Formats `n` with `separator` between each group of three digits, such as `1,234,567`.
+pub fn format_number(n: u64, separator: char) -> String {
     let n_string = n.to_string();
 
     let mut chars = vec![];
 
-    let mut i = 0;
-    for n_char in n_string.chars().rev() {
+    for (i, n_char) in n_string.chars().rev().enumerate() {
         if i != 0 && i % 3 == 0 {
-            chars.push(' ');
+            chars.push(separator);
         }
         chars.push(n_char);
-        i = i + 1;
     }
 
     chars.into_iter()
         .rev()
         .collect()
 }
+
+/// Formats `seconds` as minutes and seconds, such as `3:07`.
+pub fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.max(0.) as u64;
+
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
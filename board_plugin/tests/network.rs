@@ -0,0 +1,174 @@
+use board_plugin::network::*;
+use board_plugin::resources::TileMap;
+use bevy::tasks::TaskPool;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn start_server(max_ticks: u64) -> ServerHandle {
+    let config = ServerConfig {
+        tick_interval: Duration::from_millis(5),
+        max_ticks: Some(max_ticks),
+        ..Default::default()
+    };
+
+    GameServer::bind("127.0.0.1:0", config)
+        .and_then(|server| { server.spawn() })
+        .expect("Can't start the server")
+}
+
+/// Polls every client until `is_done` holds for all of them.
+fn poll_until(clients: &mut [&mut GameClient], is_done: impl Fn(&GameClient) -> bool) {
+    let deadline = Instant::now() + TIMEOUT;
+
+    loop {
+        for client in clients.iter_mut() {
+            client.poll();
+        }
+
+        if clients.iter().all(|client| { is_done(client) }) {
+            return;
+        }
+
+        assert!(Instant::now() < deadline, "Timed out waiting for the clients");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn two_clients_see_the_same_board() {
+    const MAX_TICKS: u64 = 200;
+
+    let server = start_server(MAX_TICKS);
+    let addr = server.local_addr.to_string();
+
+    let mut client_a = GameClient::connect(&addr, TaskPool::new()).unwrap();
+    let mut client_b = GameClient::connect(&addr, TaskPool::new()).unwrap();
+    poll_until(&mut [&mut client_a, &mut client_b], |client| { client.player().is_some() });
+
+    assert_ne!(client_a.player(), client_b.player());
+
+    // A gray tile next to the centre: lighting it spreads the board beyond its starting 9 tiles.
+    client_a.trigger((1, 0).into());
+
+    poll_until(&mut [&mut client_a, &mut client_b], |client| { client.tick() == MAX_TICKS });
+
+    assert_eq!(client_a.tile_map(), client_b.tile_map());
+    assert_eq!(client_a.score(), client_b.score());
+    assert!(client_a.tile_map().len() > 9, "The trigger never reached the board");
+
+    server.stop().unwrap();
+}
+
+#[test]
+fn client_reconnects_as_the_same_player() {
+    const MAX_TICKS: u64 = 60;
+
+    let server = start_server(MAX_TICKS);
+    let addr = server.local_addr.to_string();
+
+    let mut client = GameClient::connect(&addr, TaskPool::new()).unwrap();
+    poll_until(&mut [&mut client], |client| { client.tick() == MAX_TICKS });
+
+    let session = client.session();
+    let player = client.player();
+    let tile_map = client.tile_map().clone();
+
+    client.reconnect().unwrap();
+    let mut messages = Vec::new();
+    let deadline = Instant::now() + TIMEOUT;
+    while !messages.iter().any(|message| { matches!(message, ServerMessage::Snapshot { .. }) }) {
+        assert!(Instant::now() < deadline, "Timed out waiting for a snapshot");
+        messages.extend(client.poll());
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    assert!(client.is_connected());
+    assert_eq!(client.session(), session);
+    assert_eq!(client.player(), player);
+    assert_eq!(client.tile_map(), &tile_map);
+
+    server.stop().unwrap();
+}
+
+#[test]
+fn late_trigger_is_applied_at_its_tick() {
+    let mut on_time = Authority::new(TileMap::new(), DEFAULT_MAX_REWIND_TICKS);
+    let mut late = on_time.clone();
+
+    for _ in 0..5 {
+        on_time.step();
+    }
+    assert!(on_time.trigger(5, (1, 1).into()));
+    for _ in 0..10 {
+        on_time.step();
+    }
+
+    // The same trigger, arriving 10 ticks after the player made it.
+    for _ in 0..15 {
+        late.step();
+    }
+    assert!(late.trigger(5, (1, 1).into()));
+
+    assert_eq!(late.tick(), on_time.tick());
+    assert_eq!(late.score(), on_time.score());
+    assert_eq!(late.tile_map(), on_time.tile_map());
+}
+
+#[test]
+fn trigger_older_than_the_rewind_window_is_applied_at_the_oldest_tick() {
+    let mut clamped = Authority::new(TileMap::new(), 4);
+    for _ in 0..10 {
+        clamped.step();
+    }
+    let mut oldest = clamped.clone();
+
+    clamped.trigger(0, (0, 1).into());
+    oldest.trigger(6, (0, 1).into());
+
+    assert_eq!(clamped.tile_map(), oldest.tile_map());
+    assert_eq!(clamped.score(), oldest.score());
+}
+
+#[test]
+fn stalled_handshake_doesnt_hold_up_other_clients() {
+    let server = start_server(1000);
+    let addr = server.local_addr.to_string();
+
+    // Connects without ever sending the handshake, which the server waits 2 seconds for.
+    let _stalled = TcpStream::connect(&addr).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let start = Instant::now();
+    let mut client = GameClient::connect(&addr, TaskPool::new()).unwrap();
+    poll_until(&mut [&mut client], |client| { client.player().is_some() });
+
+    assert!(start.elapsed() < Duration::from_secs(1), "Joining took {:?}", start.elapsed());
+
+    server.stop().unwrap();
+}
+
+#[test]
+fn polling_doesnt_wait_for_a_server_that_went_away() {
+    let server = start_server(1000);
+    let addr = server.local_addr;
+
+    let mut client = GameClient::connect(&addr.to_string(), TaskPool::new()).unwrap();
+    poll_until(&mut [&mut client], |client| { client.player().is_some() });
+
+    // Something else takes the port, and accepts connections without ever answering them.
+    server.stop().unwrap();
+    let _listener = TcpListener::bind(addr).unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while Instant::now() < deadline {
+        let start = Instant::now();
+        client.poll();
+        assert!(start.elapsed() < Duration::from_millis(100), "Polling took {:?}", start.elapsed());
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    assert!(!client.is_connected());
+}
@@ -0,0 +1,94 @@
+use anyhow::{anyhow, bail, Context};
+use board_plugin::network::*;
+use std::env;
+use std::process;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+const USAGE: &str = "\
+Hosts a shared game of Lights Out for clients to join over WebSocket.
+
+Usage: lights-out-server [options]
+
+Options:
+    --bind <address>    Address to listen on (default 127.0.0.1)
+    --port <n>          Port to listen on (default 9001)
+    --tick <ms>         Milliseconds between ticks (default 100)
+    --rewind <n>        Ticks a late trigger can be moved back by (default 20)
+    --help              Show this message
+
+Players join with: lights-out --connect <address>:<port>";
+
+#[derive(Debug, Default)]
+struct Args {
+    bind: Option<String>,
+    port: Option<u16>,
+    tick_millis: Option<u64>,
+    rewind: Option<u64>,
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("Error: {:#}", e);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), anyhow::Error> {
+    let args = match parse_args(env::args().skip(1))? {
+        Some(args) => args,
+        None => {
+            println!("{}", USAGE);
+            return Ok(());
+        },
+    };
+
+    let mut config = ServerConfig::default();
+    if let Some(tick_millis) = args.tick_millis { config.tick_interval = Duration::from_millis(tick_millis); }
+    if let Some(rewind) = args.rewind { config.max_rewind_ticks = rewind; }
+
+    let addr = format!(
+        "{}:{}",
+        args.bind.as_deref().unwrap_or("127.0.0.1"),
+        args.port.unwrap_or(DEFAULT_PORT),
+    );
+
+    let mut server = GameServer::bind(&addr, config)
+        .with_context(|| { format!("Can't listen on {}", addr) })?;
+    eprintln!("Listening on ws://{}", server.local_addr()?);
+
+    server.run(&AtomicBool::new(false))?;
+
+    Ok(())
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, anyhow::Error> {
+    let mut parsed = Args::default();
+
+    while let Some(arg) = args.next() {
+        if arg == "--help" || arg == "-h" {
+            return Ok(None);
+        }
+
+        let value = args.next()
+            .ok_or_else(|| { anyhow!("{} needs a value\n\n{}", arg, USAGE) })?;
+
+        match arg.as_str() {
+            "--bind" => parsed.bind = Some(value),
+            "--port" => parsed.port = Some(parse_number(&arg, &value)?),
+            "--tick" => parsed.tick_millis = Some(parse_number(&arg, &value)?),
+            "--rewind" => parsed.rewind = Some(parse_number(&arg, &value)?),
+            _ => bail!("Unknown option {}\n\n{}", arg, USAGE),
+        }
+    }
+
+    Ok(Some(parsed))
+}
+
+fn parse_number<T: std::str::FromStr>(arg: &str, value: &str) -> Result<T, anyhow::Error>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    value.parse()
+        .with_context(|| { format!("{} needs a whole number, not \"{}\"", arg, value) })
+}
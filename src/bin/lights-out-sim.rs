@@ -0,0 +1,254 @@
+use anyhow::{anyhow, bail, Context};
+use board_plugin::simulation::*;
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::process;
+
+const USAGE: &str = "\
+Plays Lights Out without a window, and prints the results.
+
+Usage: lights-out-sim [options]
+
+Options:
+    --seed <n>          Seed of the first game's starting board (default 0)
+    --games <n>         Number of games, each with the next seed (default 1)
+    --config <path>     RON file with any of the fields of SimulationConfig
+    --strategy <name>   idle, random, greedy, or lookahead (default greedy)
+    --ticks <n>         Stop games still going after this many ticks (default 3000)
+    --interval <n>      Ticks between the bot's triggers (default 10)
+    --replay <path>     RON replay to play back instead of a bot
+    --record <path>     Write a RON replay of the game, for a single game only
+    --format <format>   json or csv (default json)
+    --help              Show this message
+
+Options given on the command line override the config file.
+A single game prints its statistics for every tick. Several games print one
+summary per game, and the score distribution on stderr.";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Format {
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Default)]
+struct Args {
+    config_path: Option<String>,
+    replay_path: Option<String>,
+    record_path: Option<String>,
+    seed: Option<u64>,
+    games: Option<u64>,
+    strategy: Option<StrategyKind>,
+    ticks: Option<u64>,
+    interval: Option<u64>,
+    format: Option<Format>,
+}
+
+#[derive(Serialize)]
+struct GameOutput<'a> {
+    #[serde(flatten)]
+    result: &'a SimulationResult,
+    per_tick: &'a [TickStats],
+}
+
+#[derive(Serialize)]
+struct BatchOutput<'a> {
+    games: &'a [SimulationResult],
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("Error: {:#}", e);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), anyhow::Error> {
+    let args = match parse_args(env::args().skip(1))? {
+        Some(args) => args,
+        None => {
+            println!("{}", USAGE);
+            return Ok(());
+        },
+    };
+
+    let mut config: SimulationConfig = match &args.config_path {
+        Some(path) => {
+            let contents = fs::read_to_string(path)
+                .with_context(|| { format!("Can't read config {}", path) })?;
+            ron::from_str(&contents)
+                .with_context(|| { format!("Can't parse config {}", path) })?
+        },
+        None => SimulationConfig::default(),
+    };
+
+    if let Some(seed) = args.seed { config.seed = seed; }
+    if let Some(games) = args.games { config.games = games; }
+    if let Some(strategy) = args.strategy { config.strategy = strategy; }
+    if let Some(ticks) = args.ticks { config.ticks = ticks; }
+    if let Some(interval) = args.interval { config.interval = interval; }
+    let format = args.format.unwrap_or(Format::Json);
+
+    if args.record_path.is_some() && config.games != 1 {
+        bail!("--record only works with a single game");
+    }
+
+    if let Some(path) = &args.replay_path {
+        let contents = fs::read_to_string(path)
+            .with_context(|| { format!("Can't read replay {}", path) })?;
+        let replay: Replay = ron::from_str(&contents)
+            .with_context(|| { format!("Can't parse replay {}", path) })?;
+
+        // A replay only makes sense on the board it was recorded on.
+        let mut simulation = Simulation::new(replay.seed);
+        let mut strategy = ReplayStrategy::new(replay);
+        return play_game(&mut simulation, &mut strategy, config.ticks, format, args.record_path.as_deref());
+    }
+
+    if config.games == 1 {
+        let mut simulation = Simulation::new(config.seed);
+        let mut strategy = config.build_strategy(config.seed);
+        return play_game(&mut simulation, strategy.as_mut(), config.ticks, format, args.record_path.as_deref());
+    }
+
+    let results = run_batch(&config.seeds(), config.ticks, |seed| { config.build_strategy(seed) });
+    print_batch(&results, format)?;
+
+    if let Some(report) = BatchReport::new(&results) {
+        eprintln!("{}", report);
+    }
+
+    Ok(())
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, anyhow::Error> {
+    let mut parsed = Args::default();
+
+    while let Some(arg) = args.next() {
+        if arg == "--help" || arg == "-h" {
+            return Ok(None);
+        }
+
+        let value = args.next()
+            .ok_or_else(|| { anyhow!("{} needs a value\n\n{}", arg, USAGE) })?;
+
+        match arg.as_str() {
+            "--config" => parsed.config_path = Some(value),
+            "--replay" => parsed.replay_path = Some(value),
+            "--record" => parsed.record_path = Some(value),
+            "--seed" => parsed.seed = Some(parse_number(&arg, &value)?),
+            "--games" => parsed.games = Some(parse_number(&arg, &value)?),
+            "--ticks" => parsed.ticks = Some(parse_number(&arg, &value)?),
+            "--interval" => parsed.interval = Some(parse_number(&arg, &value)?),
+            "--strategy" => parsed.strategy = Some(value.parse().map_err(|e| { anyhow!("{}", e) })?),
+            "--format" => parsed.format = Some(match value.as_str() {
+                "json" => Format::Json,
+                "csv" => Format::Csv,
+                _ => bail!("Unknown format \"{}\", expected json or csv", value),
+            }),
+            _ => bail!("Unknown option {}\n\n{}", arg, USAGE),
+        }
+    }
+
+    Ok(Some(parsed))
+}
+
+fn parse_number(arg: &str, value: &str) -> Result<u64, anyhow::Error> {
+    value.parse()
+        .with_context(|| { format!("{} needs a whole number, not \"{}\"", arg, value) })
+}
+
+/// Plays a single game and prints it, writing a replay of it to `record_path` if given.
+fn play_game(
+    simulation: &mut Simulation,
+    strategy: &mut dyn Strategy,
+    max_ticks: u64,
+    format: Format,
+    record_path: Option<&str>,
+) -> Result<(), anyhow::Error> {
+    let path = match record_path {
+        Some(path) => path,
+        None => return print_game(simulation, strategy, max_ticks, format),
+    };
+
+    let mut strategy = RecordingStrategy::new(strategy, simulation.seed);
+    print_game(simulation, &mut strategy, max_ticks, format)?;
+
+    let contents = ron::ser::to_string_pretty(&strategy.replay, Default::default())?;
+    fs::write(path, contents)
+        .with_context(|| { format!("Can't write replay {}", path) })?;
+
+    Ok(())
+}
+
+fn print_game(
+    simulation: &mut Simulation,
+    strategy: &mut dyn Strategy,
+    max_ticks: u64,
+    format: Format,
+) -> Result<(), anyhow::Error> {
+    let mut per_tick = vec![];
+    while !simulation.is_over && simulation.tick < max_ticks {
+        per_tick.push(simulation.step(strategy));
+    }
+    let result = simulation.result(strategy);
+
+    match format {
+        Format::Json => {
+            println!("{}", serde_json::to_string_pretty(&GameOutput {
+                result: &result,
+                per_tick: &per_tick,
+            })?);
+        },
+        Format::Csv => {
+            println!("tick,triggers,score_increment,score,lit_tiles");
+            for stats in per_tick {
+                println!(
+                    "{},{},{},{},{}",
+                    stats.tick,
+                    stats.triggers,
+                    stats.score_increment,
+                    stats.score,
+                    stats.lit_tiles,
+                );
+            }
+            eprintln!(
+                "Seed {}: scored {} in {} ticks with {}",
+                result.seed,
+                result.score,
+                result.ticks,
+                result.strategy,
+            );
+        },
+    }
+
+    Ok(())
+}
+
+fn print_batch(results: &[SimulationResult], format: Format) -> Result<(), anyhow::Error> {
+    match format {
+        Format::Json => {
+            println!("{}", serde_json::to_string_pretty(&BatchOutput {
+                games: results,
+            })?);
+        },
+        Format::Csv => {
+            println!("seed,strategy,score,ticks,triggers,wasted_triggers,survived");
+            for result in results {
+                println!(
+                    "{},{},{},{},{},{},{}",
+                    result.seed,
+                    result.strategy,
+                    result.score,
+                    result.ticks,
+                    result.triggers,
+                    result.wasted_triggers,
+                    result.survived,
+                );
+            }
+        },
+    }
+
+    Ok(())
+}
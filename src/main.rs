@@ -1,5 +1,7 @@
 use bevy::prelude::*;
+use board_plugin::resources::{OnlineConfig, SpectatorConfig};
 use board_plugin::BoardPlugin;
+use std::env;
 
 #[cfg(feature = "debug")]
 use bevy_inspector_egui::WorldInspectorPlugin;
@@ -21,5 +23,23 @@ fn main() {
 
     app.add_plugin(BoardPlugin);
 
+    // `--connect <address>` plays online on a server started with lights-out-server.
+    let args: Vec<String> = env::args().collect();
+    let arg_value = |name: &str| {
+        args.iter().position(|arg| { arg == name }).and_then(|i| { args.get(i + 1) }).cloned()
+    };
+    if let Some(addr) = arg_value("--connect") {
+        app.insert_resource(OnlineConfig {
+            addr,
+        });
+    }
+
+    // `--spectate <address>` watches a game on a server, and `--replay <path>` plays back a replay.
+    if let Some(addr) = arg_value("--spectate") {
+        app.insert_resource(SpectatorConfig::Live { addr });
+    } else if let Some(path) = arg_value("--replay") {
+        app.insert_resource(SpectatorConfig::Replay { path });
+    }
+
     app.run();
 }
\ No newline at end of file